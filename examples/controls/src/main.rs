@@ -1,3 +1,3 @@
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), exposed::Error> {
     exposed::window::utility::run::<controls::App>(Default::default())
 }
@@ -1,14 +1,14 @@
-use exposed::window::utility::HeapEventHandler;
+use exposed::window::{EventHandler, EventHandlerBuilder};
 
-fn event_loop(mut event_handler: HeapEventHandler<App>) {
-    while event_handler.user_data.running {
+fn event_loop(mut event_handler: EventHandler<App>) {
+    while event_handler.user_data().running {
         event_handler.wait();
         event_handler.dispatch();
     }
 }
 
-fn main() -> Result<(), std::io::Error> {
-    let event_handler = HeapEventHandler::<App>::new(Default::default())?;
+fn main() -> Result<(), exposed::Error> {
+    let event_handler = EventHandlerBuilder::default().build::<App>()?;
 
     event_loop(event_handler);
 
@@ -10,10 +10,10 @@ use std::{
 use camera::Camera;
 use exposed::{
     destroy::Destroyable,
-    log::{log_fatal, LogResult},
+    log::{log_error, log_fatal, LogResult},
     window::{
-        android_on_create, utility::ExtendedEvent, Android, Context, Event, Key, MouseButton, ScanCode, Size, Touch, TouchPhase,
-        WindowBuilder, WindowHandle,
+        android_on_create, utility::ExtendedEvent, Android, Context, Event, Gesture, GestureKind, Key, MouseButton, ScanCode, Size,
+        Touch, TouchPhase, WindowBuilder, WindowHandle,
     },
 };
 use exposed_gl::{get_proc_addr, load_lib_opengl, surface_config, tokens, GlContext, GlDefaultPicker, GlSurface};
@@ -34,6 +34,7 @@ pub struct App {
     camera: Camera,
     touch_count: usize,
     touch_buffer: [Touch; 2],
+    pinch_scale: f32,
     models: Vec<Pmx>,
     pmx_renderer: PmxRenderer,
     gl_context: Destroyable<GlContext>,
@@ -122,6 +123,7 @@ impl App {
             render: true,
             touch_count: Default::default(),
             touch_buffer: Default::default(),
+            pinch_scale: 1.0,
         })
     }
 
@@ -166,7 +168,7 @@ impl Event for App {
         self.running = false;
     }
 
-    fn render(&mut self, _: WindowHandle) {
+    fn render(&mut self, window: WindowHandle) {
         unsafe { gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT) };
 
         if self.render {
@@ -178,6 +180,11 @@ impl Event for App {
         if let Some(surface) = &mut self.surface {
             surface.swap_buffers().log_error();
         }
+
+        match exposed::window::utility::capture_to_env(window) {
+            Ok(captured) => self.running &= !captured,
+            Err(e) => log_error!("Model", "{e}"),
+        }
     }
 
     fn mouse_wheel(&mut self, _: WindowHandle, _delta_x: f32, delta_y: f32) {
@@ -326,6 +333,26 @@ impl Event for App {
         self.touch_count = 0;
     }
 
+    fn gesture_begin(&mut self, _: WindowHandle, _: Gesture) {
+        self.pinch_scale = 1.0;
+    }
+
+    fn gesture_update(&mut self, _: WindowHandle, gesture: Gesture) {
+        match gesture.kind {
+            GestureKind::Pinch => {
+                self.camera.radius *= self.pinch_scale / gesture.scale;
+                self.camera.orbit(gesture.rotation.to_radians(), 0.0);
+                self.pinch_scale = gesture.scale;
+            }
+            GestureKind::Swipe => {
+                self.camera.orbit(-gesture.delta.0 * SENSITIVITY, -gesture.delta.1 * SENSITIVITY);
+            }
+        }
+
+        self.camera.update();
+        self.redraw();
+    }
+
     fn create(context: Context) -> Option<Self> {
         match Self::new(context) {
             Ok(s) => Some(s),
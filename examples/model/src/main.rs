@@ -1,5 +1,5 @@
 use exposed::window::utility;
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), exposed::Error> {
     utility::run::<model::App>(Default::default())
 }
@@ -0,0 +1,103 @@
+use exposed::{
+    destroy::Destroyable,
+    window::{Context, Event, MouseButton, PixelSurface, Rect, WindowBuilder, WindowHandle},
+};
+
+const SQUARE: i32 = 48;
+
+/// Draws a gradient on the CPU, clicking moves a square and presents only what changed.
+pub struct App {
+    _window: Destroyable<WindowHandle>,
+    surface: Destroyable<PixelSurface>,
+    square_x: i32,
+    /// Area covered by the square in the last present.
+    last_square: Option<Rect>,
+    running: bool,
+}
+
+impl App {
+    fn square(&self) -> Rect {
+        Rect { left: self.square_x, top: SQUARE, right: self.square_x + SQUARE, bottom: SQUARE * 2 }
+    }
+}
+
+impl Event for App {
+    fn create(context: Context) -> Option<Self> {
+        let window = Destroyable(WindowBuilder::default().with_size(640, 480).build::<Self>(context).ok()?);
+
+        let surface = match PixelSurface::new(*window) {
+            Ok(surface) => Destroyable(surface),
+            Err(e) => {
+                eprintln!("{e}");
+                return None;
+            }
+        };
+
+        window.show().ok()?;
+        window.redraw().ok()?;
+
+        Some(Self { _window: window, surface, square_x: SQUARE, last_square: None, running: true })
+    }
+
+    fn render(&mut self, _: WindowHandle) {
+        let square = self.square();
+        let old_size = (self.surface.width(), self.surface.height());
+
+        // Resizes the buffer, the size can't be read while the buffer is borrowed.
+        if let Err(e) = self.surface.buffer_mut() {
+            return eprintln!("{e}");
+        }
+
+        let (width, height) = (self.surface.width() as i32, self.surface.height() as i32);
+        let buffer = match self.surface.buffer_mut() {
+            Ok(buffer) => buffer,
+            Err(e) => return eprintln!("{e}"),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let inside = x >= square.left && x < square.right && y >= square.top && y < square.bottom;
+
+                buffer[(y * width + x) as usize] = if inside {
+                    0x00FFFFFF
+                } else {
+                    let red = (x * 255 / width) as u32;
+                    let green = (y * 255 / height) as u32;
+                    (red << 16) | (green << 8) | 0x80
+                };
+            }
+        }
+
+        // A reallocated buffer must be presented whole.
+        let result = match self.last_square {
+            Some(last) if old_size == (width as u32, height as u32) => self.surface.present_with_damage(&[last, square]),
+            _ => self.surface.present(),
+        };
+
+        if let Err(e) = result {
+            eprintln!("{e}");
+        }
+
+        self.last_square = Some(square);
+    }
+
+    fn mouse_button_down(&mut self, window: WindowHandle, button: MouseButton) {
+        if button == MouseButton::LEFT {
+            self.square_x = (self.square_x + SQUARE) % (self.surface.width() as i32 - SQUARE).max(SQUARE);
+            let _ = window.redraw();
+        }
+    }
+
+    fn close_requested(&mut self, _: WindowHandle) {
+        self.running = false;
+    }
+}
+
+impl exposed::window::utility::ExtendedEvent for App {
+    #[inline]
+    fn is_running(&mut self) -> bool {
+        self.running
+    }
+}
+
+exposed::window::android_on_create!(exposed::window::Android<App>);
@@ -0,0 +1,3 @@
+fn main() -> Result<(), exposed::Error> {
+    exposed::window::utility::run::<pixels::App>(Default::default())
+}
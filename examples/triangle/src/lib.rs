@@ -172,7 +172,7 @@ impl exposed::window::Event for App {
         Some(Self { vao, vbo, program, context, display: Some(display), _window: window, running })
     }
 
-    fn render(&mut self, _: exposed::window::WindowHandle) {
+    fn render(&mut self, window: exposed::window::WindowHandle) {
         unsafe {
             gl::Clear(gl::COLOR_BUFFER_BIT);
             gl::UseProgram(self.program);
@@ -191,6 +191,11 @@ impl exposed::window::Event for App {
                 eprintln!("{e}");
             }
         }
+
+        match exposed::window::utility::capture_to_env(window) {
+            Ok(captured) => self.running &= !captured,
+            Err(e) => eprintln!("{e}"),
+        }
     }
 
     fn resized(&mut self, _window: exposed::window::WindowHandle, width: i32, height: i32) {
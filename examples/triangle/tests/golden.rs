@@ -0,0 +1,31 @@
+use std::{fs::File, path::Path, process::Command};
+
+use exposed::window::Image;
+
+/// Largest channel difference allowed between drivers.
+const TOLERANCE: u8 = 8;
+
+/// Run with `xvfb-run cargo test -- --ignored`. Set `EXPOSED_BLESS` to replace the golden image.
+#[test]
+#[ignore = "needs an X server"]
+fn triangle_golden_test() {
+    let capture = std::env::temp_dir().join("triangle_capture.png");
+
+    let status = Command::new(env!("CARGO_BIN_EXE_triangle")).env("EXPOSED_CAPTURE", &capture).status().unwrap();
+    assert!(status.success());
+
+    let image = Image::read_png(File::open(&capture).unwrap()).unwrap();
+
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/triangle.png");
+    if std::env::var_os("EXPOSED_BLESS").is_some() {
+        image.write_png(File::create(&golden).unwrap()).unwrap();
+        eprintln!("Wrote golden image to {}.", golden.display());
+        return;
+    }
+
+    let expected = File::open(&golden)
+        .unwrap_or_else(|e| panic!("Missing golden image {}, set EXPOSED_BLESS to write it. {e}", golden.display()));
+    let expected = Image::read_png(expected).unwrap();
+    let difference = image.max_difference(&expected);
+    assert!(difference <= TOLERANCE, "Capture differs from {} by {difference}, see {}.", golden.display(), capture.display());
+}
@@ -434,56 +434,18 @@ impl Drop for Renderer {
     fn drop(&mut self) {}
 }
 
-fn raw_display_handle(window: WindowHandle) -> RawDisplayHandle {
-    todo!()
-}
-
-fn raw_window_handle(window: WindowHandle) -> RawWindowHandle {
-    todo!()
-}
-
+/// Vulkano needs the window to be `Send` and `Sync`, the handles come from `exposed`.
 struct W(WindowHandle);
 
 unsafe impl HasRawDisplayHandle for W {
     fn raw_display_handle(&self) -> RawDisplayHandle {
-        #[cfg(target_os = "android")]
-        return RawDisplayHandle::Android(AndroidDisplayHandle::empty());
-        #[cfg(target_os = "windows")]
-        return RawDisplayHandle::Windows(WindowsDisplayHandle::empty());
-
-        #[cfg(target_os = "linux")]
-        return {
-            let mut d = raw_window_handle::XlibDisplayHandle::empty();
-            let c = unsafe { exposed::window::_x11::ThreadContext::current_thread() };
-            d.display = c.display as _;
-            d.screen = c.screen_id;
-            raw_window_handle::RawDisplayHandle::Xlib(d)
-        };
+        self.0.raw_display_handle()
     }
 }
 
 unsafe impl HasRawWindowHandle for W {
     fn raw_window_handle(&self) -> RawWindowHandle {
-        #[cfg(target_os = "android")]
-        return RawWindowHandle::AndroidNdk({
-            let mut handle = AndroidNdkWindowHandle::empty();
-            handle.a_native_window = self.0 .0.native_handle() as _;
-            handle
-        });
-        #[cfg(target_os = "windows")]
-        return RawWindowHandle::Win32({
-            let mut handle = Win32WindowHandle::empty();
-            handle.hwnd = self.0 .0 .0 as _;
-            handle.hinstance = unsafe { exposed::window::win32::HINSTANCE } as _;
-            handle
-        });
-
-        #[cfg(target_os = "linux")]
-        return {
-            let mut w = raw_window_handle::XlibWindowHandle::empty();
-            w.window = self.0 .0 .0;
-            raw_window_handle::RawWindowHandle::Xlib(w)
-        };
+        self.0.raw_window_handle()
     }
 }
 
@@ -1,7 +1,7 @@
 use std::{
     alloc::{alloc, dealloc, Layout},
     ffi::c_void,
-    io::{Error, ErrorKind},
+    io::Error,
     ptr::{null, null_mut},
     sync::atomic::{AtomicPtr, Ordering},
 };
@@ -9,11 +9,7 @@ use std::{
 use exposed::{
     destroy::{Destroy, Destroyable},
     log::{log_verbose, log_warn},
-    window::{
-        android::ndk::ANativeWindow_setBuffersGeometry,
-        platform::{WindowBuilder, WindowHandle},
-        Context, Event,
-    },
+    window::{self, android::ndk::ANativeWindow_setBuffersGeometry, platform::WindowHandle, Context, Event, WindowBuilder},
 };
 use glutin_egl_sys::{
     egl::{
@@ -40,9 +36,10 @@ fn egl_error(egl: &mut Egl) -> i32 {
     unsafe { egl.GetError() }
 }
 
+/// Error of the EGL function `$function` with the code of `eglGetError`.
 macro_rules! egl_error {
-    ($egl:expr, $m:tt) => {
-        Err(Error::new(ErrorKind::Other, format!($m, egl_error($egl))))
+    ($egl:expr, $function:literal) => {
+        Err(exposed::Error::BackendError { function: $function, code: egl_error($egl) as _ }.into())
     };
 }
 
@@ -61,7 +58,7 @@ pub fn load_lib_opengl() -> Result<(), Error> {
             }
 
             if LIB_OPENGL.is_null() {
-                todo!("Error")
+                return Err(exposed::Error::LibraryNotLoaded { library: "libEGL", source: None }.into());
             }
         } else {
             // TODO Log warning
@@ -101,14 +98,14 @@ pub fn load_lib_opengl() -> Result<(), Error> {
 
         let display = egl.GetDisplay(egl::DEFAULT_DISPLAY);
         if display.is_null() {
-            return egl_error!(egl, "{}");
+            return egl_error!(egl, "eglGetDisplay");
         }
 
         let mut major = 0;
         let mut minor = 0;
 
         if egl.Initialize(display, &mut major, &mut minor) == 0 {
-            return egl_error!(egl, "Failed to initialize EGLDisplay. (EGL error: {})");
+            return egl_error!(egl, "eglInitialize");
         }
 
         EGL_DISPLAY.store(display.cast_mut(), Ordering::Release);
@@ -149,7 +146,7 @@ pub fn get_egl() -> Result<&'static mut Egl, Error> {
         if !EGL.is_null() {
             Ok(&mut *EGL)
         } else {
-            Err(Error::new(ErrorKind::NotFound, "First initialize EGL with load_lib_opengl."))
+            Err(exposed::Error::LibraryNotLoaded { library: "libEGL", source: None }.into())
         }
     }
 }
@@ -158,7 +155,9 @@ pub fn get_display() -> Result<EGLDisplay, Error> {
     let display = unsafe { EGL_DISPLAY.load(Ordering::Acquire) };
 
     if display.is_null() {
-        return Err(Error::new(ErrorKind::NotFound, "EGL_DISPLAY is null. Initialize with load_lib_opengl."));
+        return Err(
+            exposed::Error::DisplayUnavailable(Some("EGL_DISPLAY is null, initialize with load_lib_opengl.".into())).into()
+        );
     }
 
     Ok(display)
@@ -183,11 +182,11 @@ pub struct GlSurface {
 impl GlSurface {
     pub fn build_with<E: Event, P: GlConfigPicker>(
         window_builder: &WindowBuilder, context: Context, min_config: &[u32], picker: &mut P,
-    ) -> Result<(GlSurface, WindowHandle), Error> {
+    ) -> Result<(GlSurface, window::WindowHandle), Error> {
         let window = Destroyable(window_builder.build::<E>(context)?);
-        let surface = Self::build::<E, P>(window.0, min_config, picker)?;
+        let surface = Self::build::<E, P>(window.0 .0, min_config, picker)?;
 
-        Ok((surface, unsafe { window.into_inner() }))
+        Ok((surface, window.into_inner()))
     }
 
     pub fn build<E: Event, P: GlConfigPicker>(window: WindowHandle, config: &[u32], picker: &mut P) -> Result<GlSurface, Error> {
@@ -196,7 +195,7 @@ impl GlSurface {
 
         let mut configs_capacity = 0;
         if unsafe { egl.GetConfigs(display, null_mut(), 0, &mut configs_capacity) } == 0 {
-            return egl_error!(egl, "Failed at eglGetConfigs. (EGL error: {})");
+            return egl_error!(egl, "eglGetConfigs");
         }
 
         let mut configs = Vec::with_capacity(configs_capacity as usize);
@@ -205,7 +204,7 @@ impl GlSurface {
         if unsafe { egl.ChooseConfig(display, config.as_ptr().cast(), configs.as_mut_ptr(), configs_capacity, &mut configs_len) }
             == 0
         {
-            return egl_error!(egl, "Failed at eglGetConfigs. (EGL error: {})");
+            return egl_error!(egl, "eglGetConfigs");
         }
         unsafe { configs.set_len(configs_len as usize) };
 
@@ -219,7 +218,7 @@ impl GlSurface {
         if let Some(picked_config) = picked_config {
             let mut format = 0;
             if unsafe { egl.GetConfigAttrib(display, picked_config as _, egl::NATIVE_VISUAL_ID as _, &mut format) } == 0 {
-                return egl_error!(egl, "Failed to get config attrib. (EGL error: {})");
+                return egl_error!(egl, "eglGetConfigAttrib");
             }
 
             unsafe { ANativeWindow_setBuffersGeometry(window.native_handle(), 0, 0, format) };
@@ -227,7 +226,7 @@ impl GlSurface {
             let surface = unsafe { egl.CreateWindowSurface(display, picked_config as _, window.native_handle() as _, 0 as _) };
 
             if surface.is_null() {
-                return egl_error!(egl, "Failed to create surface. (EGL error: {})");
+                return egl_error!(egl, "eglCreateWindowSurface");
             }
 
             Ok(GlSurface { surface, config: picked_config as _ })
@@ -243,7 +242,7 @@ impl GlSurface {
         let context: EGLContext = unsafe { egl.CreateContext(display, self.config, share_context.0, config.as_ptr().cast()) };
 
         if context.is_null() {
-            return egl_error!(egl, "Failed at eglCreateContext. (EGL error: {})");
+            return egl_error!(egl, "eglCreateContext");
         }
 
         Ok(GlContext(context))
@@ -254,7 +253,7 @@ impl GlSurface {
         let display = get_display()?;
 
         if unsafe { egl.SwapBuffers(display, self.surface) } == 0 {
-            return egl_error!(egl, "Failed to swap buffers. (EGL error: {}).");
+            return egl_error!(egl, "eglSwapBuffers");
         }
 
         Ok(())
@@ -265,7 +264,7 @@ impl GlSurface {
         let display = get_display()?;
 
         if unsafe { egl.SwapInterval(display, interval) } == 0 {
-            return egl_error!(egl, "Failed to set swap interval to {interval}. (EGL error: {}).");
+            return egl_error!(egl, "eglSwapInterval");
         }
 
         Ok(())
@@ -283,7 +282,7 @@ impl GlSurface {
         }
 
         if unsafe { egl.MakeCurrent(display, surface, surface, context.0) } == 0 {
-            return egl_error!(egl, "Failed to make context current. (EGL error: {})");
+            return egl_error!(egl, "eglMakeCurrent");
         }
 
         Ok(())
@@ -294,7 +293,7 @@ impl GlSurface {
         let display = get_display()?;
 
         if unsafe { egl.MakeCurrent(display, null(), null(), null()) } == 0 {
-            return egl_error!(egl, "Failed to make context current. (EGL error: {})");
+            return egl_error!(egl, "eglMakeCurrent");
         }
 
         Ok(())
@@ -307,7 +306,7 @@ impl Destroy for GlSurface {
         let display = get_display()?;
 
         if unsafe { egl.DestroySurface(display, self.surface) } == 0 {
-            return egl_error!(egl, "Failed to destroy EGLsurface. (EGL error: {})");
+            return egl_error!(egl, "eglDestroySurface");
         }
 
         Ok(())
@@ -324,7 +323,7 @@ impl Destroy for GlContext {
         let display = get_display()?;
 
         if unsafe { egl.DestroyContext(display, self.0) } == 0 {
-            return egl_error!(egl, "Failed at eglDestroyContext. (EGL error: {})");
+            return egl_error!(egl, "eglDestroyContext");
         }
 
         Ok(())
@@ -347,7 +346,7 @@ impl GlPixelFormat {
 
         for (i, a) in attributes.iter().enumerate() {
             if unsafe { egl.GetConfigAttrib(display, self.format as _, *a as i32, values.get_unchecked_mut(i)) } == 0 {
-                return egl_error!(egl, "Failed at eglGetConfigAttrib. (EGL error: {})");
+                return egl_error!(egl, "eglGetConfigAttrib");
             }
         }
 
@@ -18,52 +18,66 @@ pub use picker::*;
 
 pub mod tokens;
 
-pub use platform::{free_lib_opengl, get_proc_addr, load_lib_opengl};
-
-use std::io::Error;
+pub use platform::get_proc_addr;
 
 use exposed::{
-    destroy::Destroy,
+    destroy::{track, untrack, Destroy},
     window::{Context, Event, WindowBuilder, WindowHandle},
+    Error,
 };
 
 use platform::GlPixelFormat;
 
+pub fn load_lib_opengl() -> Result<(), Error> {
+    Ok(platform::load_lib_opengl()?)
+}
+
+pub fn free_lib_opengl() -> Result<(), Error> {
+    Ok(platform::free_lib_opengl()?)
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct GlSurface(pub platform::GlSurface);
 
 impl GlSurface {
     pub fn swap_buffers(self) -> Result<(), Error> {
-        self.0.swap_buffers()
+        Ok(self.0.swap_buffers()?)
     }
 
     pub fn make_current(self, context: GlContext) -> Result<(), Error> {
-        self.0.make_current(context.0)
+        Ok(self.0.make_current(context.0)?)
     }
 
     pub fn set_swap_interval(self, interval: i32) -> Result<(), Error> {
-        self.0.set_swap_interval(interval)
+        Ok(self.0.set_swap_interval(interval)?)
     }
 
     pub fn build_with<E: Event>(
         window_builder: &WindowBuilder, context: Context, min_config: &[u32], picker: &mut impl GlConfigPicker,
     ) -> Result<(GlSurface, WindowHandle), Error> {
-        let (surface, window) = platform::GlSurface::build_with::<E, _>(&window_builder.0, context, min_config, picker)?;
-        Ok((surface.into(), window.into()))
+        // The window is tracked by the `WindowBuilder` that created it.
+        let (surface, window) = platform::GlSurface::build_with::<E, _>(window_builder, context, min_config, picker)?;
+        track("GlSurface", &surface);
+        Ok((surface.into(), window))
     }
 
     pub fn build<E: Event>(window: WindowHandle, config: &[u32], picker: &mut impl GlConfigPicker) -> Result<GlSurface, Error> {
-        Ok(platform::GlSurface::build::<E, _>(window.0, config, picker)?.into())
+        let surface = platform::GlSurface::build::<E, _>(window.0, config, picker)?;
+        track("GlSurface", &surface);
+        Ok(surface.into())
     }
 
     pub fn create_context(&self, config: &[u32], share_context: GlContext) -> Result<GlContext, Error> {
-        Ok(self.0.create_context(config, share_context.0)?.into())
+        let context = self.0.create_context(config, share_context.0)?;
+        track("GlContext", &context);
+        Ok(context.into())
     }
 }
 
 impl Destroy for GlSurface {
     fn destroy(&mut self) -> Result<(), std::io::Error> {
+        untrack("GlSurface", &self.0);
         self.0.destroy()
     }
 }
@@ -79,6 +93,7 @@ impl GlContext {
 
 impl Destroy for GlContext {
     fn destroy(&mut self) -> Result<(), std::io::Error> {
+        untrack("GlContext", &self.0);
         self.0.destroy()
     }
 }
@@ -3,7 +3,7 @@
 use std::{
     alloc::{dealloc, handle_alloc_error, Layout},
     ffi::{c_char, c_void},
-    io::{Error, ErrorKind},
+    io::Error,
     mem::zeroed,
     ptr::{null, null_mut},
 };
@@ -13,10 +13,7 @@ use std::alloc::alloc;
 use windows_sys::{
     w,
     Win32::{
-        Foundation::{
-            GetLastError, ERROR_DC_NOT_FOUND, ERROR_INVALID_OPERATION, ERROR_INVALID_PARAMETER, ERROR_INVALID_PIXEL_FORMAT,
-            ERROR_NO_SYSTEM_RESOURCES, HMODULE, FreeLibrary,
-        },
+        Foundation::{FreeLibrary, GetLastError, HMODULE},
         Graphics::{
             Gdi::{GetDC, ReleaseDC, HDC},
             OpenGL::{
@@ -35,10 +32,7 @@ use windows_sys::{
 
 use exposed::{
     destroy::{Destroy, Destroyable},
-    window::{
-        platform::{WindowBuilder, WindowHandle},
-        Context, Event,
-    },
+    window::{self, platform::WindowHandle, Context, Event, WindowBuilder},
 };
 
 use crate::GlConfigPicker;
@@ -86,31 +80,31 @@ impl GlSurfaceBuilder {
 
     pub fn build_with<E: Event>(
         &self, context: Context, window_builder: &WindowBuilder,
-    ) -> Result<(GlSurface, WindowHandle), Error> {
+    ) -> Result<(GlSurface, window::WindowHandle), Error> {
         let window = window_builder.build::<E>(context)?;
-        let display = self.build::<E>(window)?;
+        let display = self.build::<E>(window.0)?;
 
         Ok((display, window))
     }
 
     pub fn build<E: Event>(&self, window: WindowHandle) -> Result<GlSurface, Error> {
         if unsafe { WGL }.is_null() {
-            return Err(Error::new(std::io::ErrorKind::Other, "Todo wgl load error."));
+            return Err(exposed::Error::LibraryNotLoaded { library: "opengl32", source: None }.into());
         }
 
         let wgl = unsafe { &*WGL };
 
         let real_dc = unsafe { GetDC(window.0) };
         if real_dc == 0 {
-            return Err(Error::new(std::io::ErrorKind::Other, "GetDc failed to get DC for HWND."));
+            return Err(exposed::Error::BackendError { function: "GetDC", code: 0 }.into());
         }
 
         if !wgl.ChoosePixelFormatARB.is_loaded() {
-            return Err(Error::new(std::io::ErrorKind::Other, "Could not found ChoosePixelFormatARB!"));
+            return Err(exposed::Error::Unsupported("WGL_ARB_pixel_format").into());
         }
 
         if !wgl.CreateContextAttribsARB.is_loaded() {
-            return Err(Error::new(std::io::ErrorKind::Other, "Could not found CreateContextAttribsARB!"));
+            return Err(exposed::Error::Unsupported("WGL_ARB_create_context").into());
         }
 
         let mut pixel_format: i32 = 0;
@@ -187,18 +181,8 @@ impl GlContextBuilder {
                     as isize;
 
             if gl_context == 0 {
-                println!("{}", GetLastError());
-                match GetLastError() {
-                    ERROR_INVALID_OPERATION => return Err(Error::new(std::io::ErrorKind::Other, "ERROR_INVALID_OPERATION")),
-                    ERROR_DC_NOT_FOUND => return Err(Error::new(std::io::ErrorKind::Other, "ERROR_DC_NOT_FOUND")),
-                    ERROR_INVALID_PIXEL_FORMAT => {
-                        return Err(Error::new(std::io::ErrorKind::Other, "ERROR_INVALID_PIXEL_FORMAT"))
-                    }
-                    ERROR_NO_SYSTEM_RESOURCES => return Err(Error::new(std::io::ErrorKind::Other, "ERROR_NO_SYSTEM_RESOURCES")),
-                    ERROR_INVALID_PARAMETER => return Err(Error::new(std::io::ErrorKind::Other, "ERROR_INVALID_PARAMETER")),
-
-                    e => return Err(Error::new(std::io::ErrorKind::Other, format!("Unknown error {e}, {e:#X}"))),
-                }
+                let code = GetLastError() as _;
+                return Err(exposed::Error::BackendError { function: "wglCreateContextAttribsARB", code }.into());
             }
 
             Ok(GlContext { context: gl_context })
@@ -215,11 +199,11 @@ pub struct GlSurface {
 impl GlSurface {
     pub fn build_with<E: Event, P: GlConfigPicker>(
         window_builder: &WindowBuilder, context: Context, min_config: &[u32], picker: &mut P,
-    ) -> Result<(GlSurface, WindowHandle), Error> {
+    ) -> Result<(GlSurface, window::WindowHandle), Error> {
         let window = Destroyable(window_builder.build::<E>(context)?);
-        let surface = Self::build::<E, P>(window.0, min_config, picker)?;
+        let surface = Self::build::<E, P>(window.0 .0, min_config, picker)?;
 
-        Ok((surface, unsafe { window.into_inner() }))
+        Ok((surface, window.into_inner()))
     }
 
     pub fn build<E: Event, P: GlConfigPicker>(window: WindowHandle, config: &[u32], picker: &mut P) -> Result<GlSurface, Error> {
@@ -227,7 +211,7 @@ impl GlSurface {
 
         let dc = unsafe { GetDC(window.0) };
         if dc == 0 {
-            return Err(ErrorKind::Other.into());
+            return Err(exposed::Error::BackendError { function: "GetDC", code: 0 }.into());
         }
 
         let mut num_pixel_format = 0;
@@ -281,7 +265,7 @@ impl GlSurface {
 
             Ok(GlSurface { dc })
         } else {
-            todo!()
+            Err(exposed::Error::ConfigNotFound.into())
         }
     }
 
@@ -316,7 +300,7 @@ impl GlSurface {
                     Ok(())
                 }
             } else {
-                Err(Error::new(std::io::ErrorKind::Other, "wglSwapIntervalEXT is not loaded."))
+                Err(exposed::Error::Unsupported("WGL_EXT_swap_control").into())
             }
         }
     }
@@ -389,7 +373,7 @@ pub fn get_wgl() -> Result<&'static mut Wgl, Error> {
         if !WGL.is_null() {
             Ok(&mut *WGL)
         } else {
-            Err(Error::new(ErrorKind::NotFound, "First initialize WGL with load_lib_opengl."))
+            Err(exposed::Error::LibraryNotLoaded { library: "opengl32", source: None }.into())
         }
     }
 }
@@ -460,7 +444,7 @@ pub fn load_lib_opengl() -> Result<(), Error> {
         let dummy_dc = unsafe { GetDC(dummy_window) };
 
         if dummy_dc == 0 {
-            return Err(Error::new(std::io::ErrorKind::Other, format!("[{}:{}]Failed to get dummy DC", file!(), line!())));
+            return Err(exposed::Error::BackendError { function: "GetDC", code: 0 }.into());
         }
 
         let mut pfd: PIXELFORMATDESCRIPTOR = unsafe { zeroed() };
@@ -479,7 +463,7 @@ pub fn load_lib_opengl() -> Result<(), Error> {
         }
 
         if unsafe { SetPixelFormat(dummy_dc, pixel_format, &pfd) } == 0 {
-            return Err(Error::new(std::io::ErrorKind::Other, format!("[{}:{}] Failed to set pixel format.", file!(), line!())));
+            return Err(exposed::Error::BackendError { function: "SetPixelFormat", code: 0 }.into());
         }
 
         let dummy_context = unsafe { wglCreateContext(dummy_dc) };
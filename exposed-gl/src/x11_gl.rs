@@ -1,37 +1,26 @@
 use glutin_glx_sys as glx;
-use glx::{
-    glx_extra::{
-        types::{Display, GLXFBConfig},
-        MAX_SWAP_INTERVAL_EXT, SWAP_INTERVAL_EXT,
-    },
-    AllocNone, InputOutput, StructureNotifyMask, XIMStatusNothing, XNClientWindow_0,
+use glx::glx_extra::{
+    types::{Display, GLXFBConfig},
+    MAX_SWAP_INTERVAL_EXT, SWAP_INTERVAL_EXT,
 };
 use std::{
     alloc::{alloc, dealloc, Layout},
     ffi::{c_char, c_ulong},
-    io::{
-        Error,
-        ErrorKind::{self, Other},
-    },
+    io::Error,
     ptr::{null, null_mut},
 };
 
-use x11::xlib::{
-    ButtonMotionMask, ButtonPressMask, ButtonReleaseMask, CWBorderPixel, CWColormap, CWEventMask, EnterWindowMask, ExposureMask,
-    FocusChangeMask, KeyPressMask, KeyReleaseMask, LeaveWindowMask, PointerMotionMask, True, XBlackPixel, XCreateColormap,
-    XCreateIC, XCreateWindow, XFree, XIMPreeditNothing, XNInputStyle_0, XRootWindow, XSetICFocus, XSetWindowAttributes,
-    XWhitePixel,
-};
-
 use glutin_glx_sys::glx_extra::Glx;
 use libc::{c_void, dlclose, dlopen, dlsym, RTLD_LAZY, RTLD_LOCAL};
 
 use exposed::{
-    destroy::Destroy,
+    destroy::{Destroy, Destroyable},
     unsafe_utilities::to_ref::ToReference,
     window::{
-        platform::{WindowBuilder, WindowHandle},
-        Context, Event,
+        self,
+        _x11::{xlib::XFree, WindowVisual},
+        platform::WindowHandle,
+        Context, Event, WindowBuilder,
     },
 };
 
@@ -39,12 +28,6 @@ pub use glutin_glx_sys::glx_extra as pgl_extra;
 
 use crate::GlConfigPicker;
 
-macro_rules! error {
-    ($($arg:tt)*) => {
-        Err(Error::new(ErrorKind::Other, format!($($arg)*)))
-    };
-}
-
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct GlContext {
@@ -72,8 +55,9 @@ pub struct GlSurface {
 impl GlSurface {
     pub fn build_with<E: Event, P: GlConfigPicker>(
         window_builder: &WindowBuilder, context: Context, config: &[u32], picker: &mut P,
-    ) -> Result<(GlSurface, WindowHandle), Error> {
-        let c = unsafe { context.0.to_ref() };
+    ) -> Result<(GlSurface, window::WindowHandle), Error> {
+        let x11 = context.x11().ok_or(exposed::Error::Unsupported("GLX without an X11 connection"))?;
+        let c = unsafe { x11.0.to_ref() };
         let glx = get_glx()?;
 
         let display = c.display.cast();
@@ -82,7 +66,7 @@ impl GlSurface {
         let mut minor = 0;
 
         if unsafe { glx.QueryVersion(display, &mut major, &mut minor) } == 0 {
-            return error!("glxQueryVersion returned an error");
+            return Err(exposed::Error::BackendError { function: "glXQueryVersion", code: 0 }.into());
         }
 
         let mut config_size = 0;
@@ -99,78 +83,33 @@ impl GlSurface {
         if let Some(config) = picked_config {
             let visual = unsafe { glx.GetVisualFromFBConfig(display, config as _) };
             if visual.is_null() {
-                todo!()
+                return Err(exposed::Error::BackendError { function: "glXGetVisualFromFBConfig", code: 0 }.into());
             }
 
+            // Windows are created on the default screen, configs of the other screens can't be used for them.
             if c.screen_id != unsafe { visual.to_ref().screen } {
-                todo!()
+                unsafe { XFree(visual.cast()) };
+                return Err(exposed::Error::Unsupported("GL configs of another screen").into());
             }
 
-            let root_window = unsafe { XRootWindow(c.display, c.screen_id) };
-
-            let mut window_attrib = unsafe {
-                XSetWindowAttributes {
-                    border_pixel: XBlackPixel(c.display, c.screen_id),
-                    background_pixel: XWhitePixel(c.display, c.screen_id),
-                    override_redirect: True,
-                    colormap: XCreateColormap(c.display, root_window, visual.to_ref().visual.cast(), AllocNone),
-                    event_mask: KeyPressMask
-                        | KeyReleaseMask
-                        | FocusChangeMask
-                        | PointerMotionMask
-                        | ButtonMotionMask
-                        | ButtonPressMask
-                        | ButtonReleaseMask
-                        | EnterWindowMask
-                        | LeaveWindowMask
-                        | ExposureMask
-                        | StructureNotifyMask,
-                    ..std::mem::zeroed()
-                }
-            };
-
-            let window = unsafe {
-                XCreateWindow(
-                    c.display,
-                    root_window,
-                    window_builder.x,
-                    window_builder.y,
-                    window_builder.width as _,
-                    window_builder.height as _,
-                    0,
-                    visual.to_ref().depth,
-                    InputOutput as u32,
-                    visual.to_ref().visual.cast(),
-                    CWColormap | CWBorderPixel | CWEventMask,
-                    &mut window_attrib,
-                )
-            };
-
-            let ic = unsafe {
-                XCreateIC(
-                    c.im,
-                    XNInputStyle_0.as_ptr(),
-                    XIMPreeditNothing | XIMStatusNothing,
-                    XNClientWindow_0.as_ptr(),
-                    window,
-                    0usize,
-                )
-            };
-
-            c.window_map.insert(window, ic);
-
-            unsafe { XSetICFocus(ic) };
-
-            Ok((GlSurface { display, window, config: config as _ }, WindowHandle(window, display.cast())))
+            let window_visual =
+                unsafe { WindowVisual { visual: visual.to_ref().visual.cast(), depth: visual.to_ref().depth, colormap: 0 } };
+
+            let window = window_builder.build_with_visual::<E>(context, window_visual);
+            unsafe { XFree(visual.cast()) };
+
+            let window = Destroyable(window?);
+            let x11_window = window.0 .0.x11().ok_or(exposed::Error::Unsupported("GLX without an X11 connection"))?;
+            Ok((GlSurface { display, window: x11_window.0, config: config as _ }, window.into_inner()))
         } else {
-            todo!()
+            Err(exposed::Error::ConfigNotFound.into())
         }
     }
 
     pub fn build<E: Event, P: GlConfigPicker>(
         _window: WindowHandle, _config: &[u32], _picker: &mut P,
     ) -> Result<GlSurface, Error> {
-        Err(ErrorKind::Unsupported.into())
+        Err(exposed::Error::Unsupported("GlSurface::build on X11, use build_with").into())
     }
 
     pub fn set_swap_interval(self, _interval: i32) -> Result<(), Error> {
@@ -200,7 +139,7 @@ impl GlSurface {
         let glx = unsafe { &*GLX };
 
         if unsafe { glx.MakeCurrent(self.display, self.window, context.context as _) } == 0 {
-            return Err(Error::new(Other, "Failed to make current."));
+            return Err(exposed::Error::BackendError { function: "glXMakeCurrent", code: 0 }.into());
         }
 
         Ok(())
@@ -214,7 +153,7 @@ impl GlSurface {
         };
 
         if context.is_null() {
-            todo!()
+            return Err(exposed::Error::BackendError { function: "glXCreateContextAttribsARB", code: 0 }.into());
         }
 
         Ok(GlContext { context: context as _ })
@@ -258,7 +197,7 @@ pub fn load_lib_opengl() -> Result<(), Error> {
             }
 
             if LIB_OPENGL.is_null() {
-                todo!("Error")
+                return Err(exposed::Error::LibraryNotLoaded { library: "libGL", source: None }.into());
             }
         } else {
             // TODO Log warning
@@ -331,7 +270,7 @@ fn glx_load_test() {
 }
 
 pub fn lib_not_loaded_err<T>() -> Result<T, Error> {
-    Err(Error::new(std::io::ErrorKind::Other, "glX is not loaded. Load glX with `exposed_gl::load_lib_opengl`."))
+    Err(exposed::Error::LibraryNotLoaded { library: "libGL", source: None }.into())
 }
 
 pub unsafe fn get_proc_addr(symbol: *const c_char) -> *const c_void {
@@ -365,7 +304,7 @@ pub fn get_glx() -> Result<&'static mut Glx, Error> {
         if !GLX.is_null() {
             Ok(&mut *GLX)
         } else {
-            Err(Error::new(ErrorKind::NotFound, "First initialize GLX with load_lib_opengl."))
+            Err(exposed::Error::LibraryNotLoaded { library: "libGL", source: None }.into())
         }
     }
 }
@@ -1,4 +1,9 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
 
 use exposed_macro::log_error;
 
@@ -12,11 +17,11 @@ pub trait Destroy {
 pub struct Destroyable<T: Destroy>(pub T);
 
 impl<T: Destroy> Destroyable<T> {
-    /// Returns inner object while consuming itself.
-    pub unsafe fn into_inner(self) -> T {
-        let inner: T = std::mem::transmute_copy(&self.0);
-        std::mem::forget(self);
-        inner
+    /// Returns inner object while consuming itself, it's not destroyed.
+    pub fn into_inner(self) -> T {
+        let this = ManuallyDrop::new(self);
+        // `this` is never dropped, so `T` is moved out exactly once.
+        unsafe { std::ptr::read(&this.0) }
     }
 }
 
@@ -47,3 +52,128 @@ impl<T: Destroy> Drop for Destroyable<T> {
         }
     }
 }
+
+/// Records `resource` as created, it's reported with the backtrace of this call if it's still alive when the
+/// `EventHandler` is destroyed.
+///
+/// Resources are tracked with `debug_assertions` or the `track_resources` feature, `RUST_BACKTRACE` enables backtraces.
+/// Windows are tracked by `exposed`, other crates track theirs like `exposed-gl` does with GL surfaces and contexts.
+#[allow(unused)]
+pub fn track(kind: &'static str, resource: &(impl Hash + Debug)) {
+    #[cfg(any(debug_assertions, feature = "track_resources"))]
+    tracker::created(kind, resource);
+}
+
+/// Records `resource` as destroyed, destroying it again is reported.
+#[allow(unused)]
+pub fn untrack(kind: &'static str, resource: &(impl Hash + Debug)) {
+    #[cfg(any(debug_assertions, feature = "track_resources"))]
+    tracker::destroyed(kind, resource);
+}
+
+/// Logs the leaked and double-destroyed resources of the thread and forgets them, returns how many were reported.
+pub(crate) fn report_leaks() -> usize {
+    #[cfg(any(debug_assertions, feature = "track_resources"))]
+    return tracker::report();
+    #[cfg(not(any(debug_assertions, feature = "track_resources")))]
+    return 0;
+}
+
+#[cfg(any(debug_assertions, feature = "track_resources"))]
+mod tracker {
+    use std::{
+        backtrace::Backtrace,
+        cell::RefCell,
+        collections::HashMap,
+        fmt::Debug,
+        hash::{DefaultHasher, Hash, Hasher},
+    };
+
+    use exposed_macro::{log_error, log_warn};
+
+    thread_local! {
+        static TRACKER: RefCell<Tracker> = RefCell::new(Tracker::default());
+    }
+
+    struct Resource {
+        kind: &'static str,
+        name: String,
+        created: Backtrace,
+    }
+
+    #[derive(Default)]
+    struct Tracker {
+        alive: HashMap<(&'static str, u64), Resource>,
+        destroyed: HashMap<(&'static str, u64), Resource>,
+        destroyed_twice: Vec<(Resource, Backtrace)>,
+    }
+
+    fn key(kind: &'static str, resource: &impl Hash) -> (&'static str, u64) {
+        let mut hasher = DefaultHasher::new();
+        resource.hash(&mut hasher);
+        (kind, hasher.finish())
+    }
+
+    pub fn created(kind: &'static str, resource: &(impl Hash + Debug)) {
+        let key = key(kind, resource);
+        let resource = Resource { kind, name: format!("{resource:?}"), created: Backtrace::capture() };
+
+        TRACKER.with(|t| {
+            let mut t = t.borrow_mut();
+            // Handles are reused once they are destroyed.
+            t.destroyed.remove(&key);
+            t.alive.insert(key, resource);
+        })
+    }
+
+    pub fn destroyed(kind: &'static str, resource: &impl Hash) {
+        let key = key(kind, resource);
+
+        TRACKER.with(|t| {
+            let t = &mut *t.borrow_mut();
+            if let Some(resource) = t.alive.remove(&key) {
+                t.destroyed.insert(key, resource);
+            } else if let Some(resource) = t.destroyed.remove(&key) {
+                t.destroyed_twice.push((resource, Backtrace::capture()));
+            }
+        })
+    }
+
+    pub fn report() -> usize {
+        let t = TRACKER.with(|t| std::mem::take(&mut *t.borrow_mut()));
+
+        for r in t.alive.values() {
+            log_warn!("Destroy", "{} {} is never destroyed, created at:\n{}", r.kind, r.name, r.created);
+        }
+
+        for (r, destroyed) in &t.destroyed_twice {
+            log_error!(
+                "Destroy",
+                "{} {} is destroyed twice, created at:\n{}\ndestroyed again at:\n{destroyed}",
+                r.kind,
+                r.name,
+                r.created
+            );
+        }
+
+        t.alive.len() + t.destroyed_twice.len()
+    }
+}
+
+#[cfg(any(debug_assertions, feature = "track_resources"))]
+#[test]
+fn track_resources_test() {
+    track("window", &1u32);
+    track("window", &2u32);
+    untrack("window", &1u32);
+    untrack("window", &1u32);
+    untrack("context", &3u32);
+
+    // Window 2 leaked and window 1 was destroyed twice, the unknown context is ignored.
+    assert_eq!(report_leaks(), 2);
+    assert_eq!(report_leaks(), 0);
+
+    track("window", &1u32);
+    untrack("window", &1u32);
+    assert_eq!(report_leaks(), 0);
+}
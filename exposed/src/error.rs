@@ -0,0 +1,103 @@
+use std::{error::Error as StdError, fmt, io};
+
+/// Error of `exposed` and `exposed-gl`.
+///
+/// Converts to `io::Error` and back without losing the variant, so functions that still return `io::Error`
+/// carry it too: `exposed::Error::from(io_error)` recovers it.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The display server couldn't be reached, like `XOpenDisplay` failing or no Wayland socket.
+    DisplayUnavailable(Option<Box<dyn StdError + Send + Sync>>),
+    /// A platform function failed, `code` is the error it reported or 0 if it has none.
+    BackendError { function: &'static str, code: i64 },
+    /// The platform, the display server or the enabled features lack what is named.
+    Unsupported(&'static str),
+    /// A shared library or its functions couldn't be loaded.
+    LibraryNotLoaded { library: &'static str, source: Option<Box<dyn StdError + Send + Sync>> },
+    /// No configuration matches the requested attributes, like a GL framebuffer config.
+    ConfigNotFound,
+    /// `Event::create` returned `None`.
+    CreateFailed,
+    /// Another event handler is alive on this thread.
+    EventHandlerExists,
+    /// The window was already destroyed.
+    WindowDestroyed,
+    /// The window belongs to the event handler of another thread, `WindowProxy` sends requests to it.
+    WrongThread,
+    /// Error of the operating system or of IO.
+    Io(io::Error),
+}
+
+impl Error {
+    /// The kind of the `io::Error` it converts to.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::DisplayUnavailable(_) | Self::LibraryNotLoaded { .. } | Self::ConfigNotFound | Self::WindowDestroyed => {
+                io::ErrorKind::NotFound
+            }
+            Self::Unsupported(_) => io::ErrorKind::Unsupported,
+            Self::EventHandlerExists => io::ErrorKind::AlreadyExists,
+            Self::BackendError { .. } | Self::CreateFailed | Self::WrongThread => io::ErrorKind::Other,
+            Self::Io(e) => e.kind(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DisplayUnavailable(_) => write!(f, "The display server is unavailable."),
+            Self::BackendError { function, code } => write!(f, "{function} failed with {code}."),
+            Self::Unsupported(what) => write!(f, "Unsupported: {what}."),
+            Self::LibraryNotLoaded { library, .. } => write!(f, "{library} is not loaded."),
+            Self::ConfigNotFound => write!(f, "No config matches the requested attributes."),
+            Self::CreateFailed => write!(f, "Event::create returned None."),
+            Self::EventHandlerExists => write!(f, "Single EventHandler is allowed per thread."),
+            Self::WindowDestroyed => write!(f, "Window is destroyed."),
+            Self::WrongThread => write!(f, "Window belongs to another thread."),
+            Self::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::DisplayUnavailable(source) | Self::LibraryNotLoaded { source, .. } => {
+                source.as_deref().map(|e| e as &(dyn StdError + 'static))
+            }
+            Self::Io(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        e.downcast::<Error>().unwrap_or_else(Self::Io)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            e => io::Error::new(e.kind(), e),
+        }
+    }
+}
+
+#[test]
+fn error_io_round_trip_test() {
+    let e = io::Error::from(Error::Unsupported("xdg-decoration"));
+    assert_eq!(e.kind(), io::ErrorKind::Unsupported);
+    assert!(matches!(Error::from(e), Error::Unsupported("xdg-decoration")));
+
+    let e = io::Error::from(Error::Io(io::ErrorKind::WouldBlock.into()));
+    assert_eq!(e.kind(), io::ErrorKind::WouldBlock);
+    assert!(matches!(Error::from(e), Error::Io(e) if e.kind() == io::ErrorKind::WouldBlock));
+
+    let e = Error::DisplayUnavailable(Some("no socket".into()));
+    assert_eq!(e.source().unwrap().to_string(), "no socket");
+}
@@ -2,6 +2,9 @@ pub mod window;
 
 pub mod destroy;
 
+mod error;
+pub use error::Error;
+
 pub mod log {
     pub use exposed_macro::{
         cstr, log_debug, log_default, log_error, log_fatal, log_info, log_silent, log_unknown, log_verbose, log_warn,
@@ -21,3 +24,8 @@ pub mod log {
 }
 
 pub use unsafe_utilities;
+
+#[cfg(feature = "rwh_05")]
+pub use rwh_05;
+#[cfg(feature = "rwh_06")]
+pub use rwh_06;
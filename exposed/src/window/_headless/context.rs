@@ -0,0 +1,128 @@
+use std::{
+    cell::UnsafeCell,
+    collections::{HashMap, VecDeque},
+};
+
+use crate::window::{Image, Key, MouseButton, Rect, ScanCode, Touch, UserAttention, WindowKind};
+
+use super::WindowHandle;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Context(pub *mut ThreadContext);
+
+impl Context {
+    /// Always `None`, there is no X11 connection for GLX and Xlib calls.
+    #[cfg(target_os = "linux")]
+    pub fn x11(self) -> Option<crate::window::_x11::Context> {
+        None
+    }
+}
+
+thread_local! {
+    static CONTEXT: UnsafeCell<ThreadContext> = UnsafeCell::new(ThreadContext::new());
+}
+
+#[derive(Debug)]
+pub struct ThreadContext {
+    pub windows: HashMap<WindowHandle, VirtualWindow>,
+    pub next_window: u32,
+    pub events: VecDeque<InjectedEvent>,
+    pub dpi: u32,
+    /// Set while the thread's `EventHandler` is alive.
+    pub event_handler: bool,
+}
+
+impl ThreadContext {
+    pub fn new() -> Self {
+        Self { windows: HashMap::new(), next_window: 1, events: VecDeque::new(), dpi: 96, event_handler: false }
+    }
+
+    pub unsafe fn current_thread() -> &'static mut ThreadContext {
+        CONTEXT.with(|c| &mut *c.get())
+    }
+}
+
+/// Everything a real window manager would track, tests read it with `WindowHandle::virtual_window`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualWindow {
+    pub title: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub visible: bool,
+    pub focused: bool,
+    pub minimized: bool,
+    pub maximized: bool,
+    pub occluded: bool,
+    pub transparent: bool,
+    pub decorations: bool,
+    pub opacity: f32,
+    pub kind: WindowKind,
+    pub owner: Option<WindowHandle>,
+    pub anchor: Option<Rect>,
+    pub always_on_top: bool,
+    pub skip_taskbar: bool,
+    pub user_attention: Option<UserAttention>,
+    pub all_workspaces: bool,
+    pub workspace: Option<u32>,
+    /// Last frame presented with a `PixelSurface`, `capture` returns it.
+    pub frame: Option<Image>,
+}
+
+/// Input and window manager events a test queues with `EventHandler::inject`, `EventHandler::dispatch` delivers them.
+///
+/// Events that change the window, like `Resized`, `Moved` or `Focused`, update its `VirtualWindow` first.
+#[derive(Debug, Clone)]
+pub enum InjectedEvent {
+    Render(crate::window::WindowHandle),
+    Resized(crate::window::WindowHandle, i32, i32),
+    Moved(crate::window::WindowHandle, i32, i32),
+    Focused(crate::window::WindowHandle, bool),
+    Minimized(crate::window::WindowHandle),
+    Maximized(crate::window::WindowHandle),
+    Occluded(crate::window::WindowHandle, bool),
+    CloseRequested(crate::window::WindowHandle),
+    FileReceived(crate::window::WindowHandle, String),
+    KeyDown(crate::window::WindowHandle, Key, ScanCode),
+    KeyUp(crate::window::WindowHandle, Key, ScanCode),
+    Character(crate::window::WindowHandle, char),
+    CursorEntered(crate::window::WindowHandle),
+    CursorLeft(crate::window::WindowHandle),
+    CursorMoved(crate::window::WindowHandle, i32, i32),
+    MouseButtonDown(crate::window::WindowHandle, MouseButton),
+    MouseButtonRelease(crate::window::WindowHandle, MouseButton),
+    MouseWheel(crate::window::WindowHandle, f32, f32),
+    Touch(crate::window::WindowHandle, Touch, usize),
+    TouchEnd(crate::window::WindowHandle),
+    ScaleChanged(u32),
+}
+
+impl InjectedEvent {
+    /// Window the event is sent to, `None` for events of the whole display.
+    pub fn window(&self) -> Option<crate::window::WindowHandle> {
+        match *self {
+            Self::Render(w)
+            | Self::Resized(w, ..)
+            | Self::Moved(w, ..)
+            | Self::Focused(w, _)
+            | Self::Minimized(w)
+            | Self::Maximized(w)
+            | Self::Occluded(w, _)
+            | Self::CloseRequested(w)
+            | Self::FileReceived(w, _)
+            | Self::KeyDown(w, ..)
+            | Self::KeyUp(w, ..)
+            | Self::Character(w, _)
+            | Self::CursorEntered(w)
+            | Self::CursorLeft(w)
+            | Self::CursorMoved(w, ..)
+            | Self::MouseButtonDown(w, _)
+            | Self::MouseButtonRelease(w, _)
+            | Self::MouseWheel(w, ..)
+            | Self::Touch(w, ..)
+            | Self::TouchEnd(w) => Some(w),
+            Self::ScaleChanged(_) => None,
+        }
+    }
+}
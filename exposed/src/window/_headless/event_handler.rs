@@ -0,0 +1,162 @@
+use std::io::Error;
+
+use crate::{
+    destroy::Destroy,
+    window::{Event, Requests, Timers},
+};
+
+use super::{Context, InjectedEvent, ThreadContext};
+
+#[derive(Debug)]
+pub struct EventHandler<E: Event> {
+    pub user_data: *mut E,
+}
+
+impl<E: Event> EventHandler<E> {
+    pub fn poll(&mut self) -> i32 {
+        let c = unsafe { ThreadContext::current_thread() };
+        (!c.events.is_empty() || Timers::has_events() || Requests::has_events()) as i32
+    }
+
+    /// Only proxies can queue requests while the thread waits, sleeps until the next timer or returns 0 without one.
+    pub fn wait(&mut self) -> i32 {
+        if self.poll() == 0 {
+            if let Some(timeout) = Timers::timeout() {
+                std::thread::sleep(timeout);
+            }
+        }
+
+        self.poll()
+    }
+
+    pub fn dispatch(&mut self) {
+        self.dispatch_event();
+
+        let user_data = unsafe { &mut *self.user_data };
+        while let Some(timer) = Timers::pop() {
+            user_data.timer_fired(timer);
+        }
+
+        Requests::run();
+    }
+
+    fn dispatch_event(&mut self) {
+        let c = unsafe { ThreadContext::current_thread() };
+        let Some(event) = c.events.pop_front() else { return };
+
+        let user_data = unsafe { &mut *self.user_data };
+
+        if let InjectedEvent::ScaleChanged(dpi) = event {
+            c.dpi = dpi;
+            E::scale_factor_changed();
+            return;
+        }
+
+        // Events of destroyed windows are dropped like a display server would.
+        let Some(window) = event.window() else { return };
+        let Some(state) = c.windows.get_mut(&window.0) else { return };
+
+        match event {
+            InjectedEvent::Render(w) => user_data.low_render(w),
+            InjectedEvent::Resized(w, width, height) => {
+                (state.width, state.height) = (width, height);
+                (state.minimized, state.maximized) = (false, false);
+                user_data.resized(w, width, height);
+                c.events.push_back(InjectedEvent::Render(w));
+            }
+            InjectedEvent::Moved(w, x, y) => {
+                (state.x, state.y) = (x, y);
+                user_data.moved(w, x, y);
+            }
+            InjectedEvent::Focused(w, focused) => {
+                state.focused = focused;
+                user_data.focused(w, focused);
+            }
+            InjectedEvent::Minimized(w) => {
+                (state.minimized, state.maximized) = (true, false);
+                user_data.minimized(w);
+            }
+            InjectedEvent::Maximized(w) => {
+                (state.minimized, state.maximized) = (false, true);
+                user_data.maximized(w);
+            }
+            InjectedEvent::Occluded(w, occluded) => {
+                state.occluded = occluded;
+                user_data.occluded(w, occluded);
+            }
+            InjectedEvent::CloseRequested(w) => user_data.close_requested(w),
+            InjectedEvent::FileReceived(w, path) => user_data.file_received(w, path),
+            InjectedEvent::KeyDown(w, key, scancode) => user_data.key_down(w, key, scancode),
+            InjectedEvent::KeyUp(w, key, scancode) => user_data.key_up(w, key, scancode),
+            InjectedEvent::Character(w, character) => user_data.received_character(w, character),
+            InjectedEvent::CursorEntered(w) => user_data.cursor_entered(w),
+            InjectedEvent::CursorLeft(w) => user_data.cursor_left(w),
+            InjectedEvent::CursorMoved(w, x, y) => user_data.cursor_moved(w, x, y),
+            InjectedEvent::MouseButtonDown(w, button) => user_data.mouse_button_down(w, button),
+            InjectedEvent::MouseButtonRelease(w, button) => user_data.mouse_button_release(w, button),
+            InjectedEvent::MouseWheel(w, delta_x, delta_y) => user_data.mouse_wheel(w, delta_x, delta_y),
+            InjectedEvent::Touch(w, touch, pointer_count) => user_data.touch(w, touch, pointer_count),
+            InjectedEvent::TouchEnd(w) => user_data.touch_end(w),
+            InjectedEvent::ScaleChanged(_) => unreachable!(),
+        }
+    }
+
+    /// Queues `event` behind the events already waiting, `dispatch` delivers it.
+    pub fn inject(&mut self, event: InjectedEvent) {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.events.push_back(event);
+    }
+}
+
+impl<E: Event> Destroy for EventHandler<E> {
+    fn destroy(&mut self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        *c = ThreadContext::new();
+        Timers::reset();
+        Requests::reset();
+        Ok(())
+    }
+}
+
+/// Accepts the options of the other platforms so applications build unchanged, none of them has an effect.
+#[derive(Debug, Default)]
+pub struct EventHandlerBuilder {
+    pub gamepad_mappings: String,
+    pub app_name: String,
+    pub app_class: String,
+}
+
+impl EventHandlerBuilder {
+    pub fn with_app_id(&mut self, name: &str, class: &str) -> &mut Self {
+        self.app_name = name.to_string();
+        self.app_class = class.to_string();
+        self
+    }
+
+    pub fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        self.gamepad_mappings.push_str(mappings);
+        self.gamepad_mappings.push('\n');
+        self
+    }
+
+    pub(crate) unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, crate::Error> {
+        let thread_context = ThreadContext::current_thread();
+        if thread_context.event_handler {
+            return Err(crate::Error::EventHandlerExists);
+        }
+
+        match E::create(Context(thread_context)) {
+            Some(s) => user_data.write(s),
+            None => {
+                // Windows, timers and proxies of `Event::create` go with it.
+                *thread_context = ThreadContext::new();
+                Timers::reset();
+                Requests::reset();
+                return Err(crate::Error::CreateFailed);
+            }
+        }
+        thread_context.event_handler = true;
+
+        Ok(EventHandler { user_data })
+    }
+}
@@ -0,0 +1,21 @@
+//! Virtual windows without a display server, state is tracked in memory and events are injected by the application.
+
+mod window;
+pub use window::*;
+
+mod context;
+pub use context::*;
+
+mod event_handler;
+pub use event_handler::*;
+
+#[cfg(target_os = "linux")]
+pub use super::_x11::WindowVisual;
+
+mod pixel_surface;
+pub use pixel_surface::*;
+
+#[cfg(feature = "rwh_06")]
+mod raw_handle;
+
+pub struct Android<E: super::Event>(pub std::marker::PhantomData<E>);
@@ -0,0 +1,72 @@
+use std::io::Error;
+
+use crate::{
+    destroy::Destroy,
+    window::{Image, Rect},
+};
+
+use super::WindowHandle;
+
+/// Presented pixels are kept as the window's frame, `WindowHandle::capture` returns them.
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub window: WindowHandle,
+    pub width: u32,
+    pub height: u32,
+    pub buffer: Vec<u32>,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        window.state()?;
+        Ok(Self { window, width: 0, height: 0, buffer: Vec::new() })
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        let w = self.window.state()?;
+        let (width, height) = (w.width.max(0) as u32, w.height.max(0) as u32);
+
+        if (width, height) != (self.width, self.height) {
+            self.buffer = vec![0; width as usize * height as usize];
+            (self.width, self.height) = (width, height);
+        }
+
+        Ok(&mut self.buffer)
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.present_with_damage(&[Rect { left: 0, top: 0, right: self.width as i32, bottom: self.height as i32 }])
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        let w = self.window.state()?;
+
+        let frame = match &mut w.frame {
+            Some(frame) if (frame.width, frame.height) == (self.width, self.height) => frame,
+            frame => frame.insert(Image::new(self.width, self.height)),
+        };
+
+        for rect in damage {
+            let (left, right) = (rect.left.clamp(0, self.width as i32) as u32, rect.right.clamp(0, self.width as i32) as u32);
+            let (top, bottom) = (rect.top.clamp(0, self.height as i32) as u32, rect.bottom.clamp(0, self.height as i32) as u32);
+
+            for y in top..bottom {
+                for x in left..right {
+                    let pixel = self.buffer[(y * self.width + x) as usize];
+                    let a = if w.transparent { (pixel >> 24) as u8 } else { u8::MAX };
+
+                    frame.set_pixel(x, y, [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8, a]);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        self.buffer = Vec::new();
+        Ok(())
+    }
+}
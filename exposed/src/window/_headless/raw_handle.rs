@@ -0,0 +1,18 @@
+use super::{Context, WindowHandle};
+
+/// Virtual windows have nothing a graphics API could draw to, `rwh_05` has no way to say so and isn't implemented.
+impl WindowHandle {
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
+
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Err(rwh_06::HandleError::NotSupported)
+    }
+}
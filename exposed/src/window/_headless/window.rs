@@ -0,0 +1,319 @@
+use std::io::Error;
+
+use unsafe_utilities::to_ref::ToReference;
+
+use crate::{
+    destroy::Destroy,
+    window::{Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
+};
+
+use super::{Context, InjectedEvent, ThreadContext, VirtualWindow};
+
+/// Key of the `VirtualWindow` in `ThreadContext::windows`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowHandle(pub u32);
+
+impl WindowHandle {
+    pub fn show(self) -> Result<(), Error> {
+        self.state()?.visible = true;
+        self.redraw()
+    }
+
+    pub fn update(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn redraw(self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.events.push_back(InjectedEvent::Render(self.into()));
+        Ok(())
+    }
+
+    pub fn window_title(self) -> Result<String, Error> {
+        Ok(self.state()?.title.clone())
+    }
+
+    pub fn set_window_title(self, title: &str) -> Result<(), Error> {
+        self.state()?.title = title.to_string();
+        Ok(())
+    }
+
+    pub fn dpi(self) -> Result<u32, Error> {
+        self.state()?;
+        Ok(unsafe { ThreadContext::current_thread() }.dpi)
+    }
+
+    pub fn set_capture(self) {}
+
+    pub fn release_capture(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn client_size(self) -> Result<Size, Error> {
+        let w = self.state()?;
+        Ok(Size { width: w.width, height: w.height })
+    }
+
+    pub fn client_rect(self) -> Result<Rect, Error> {
+        let w = self.state()?;
+        Ok(Rect { left: 0, top: 0, right: w.width, bottom: w.height })
+    }
+
+    /// Virtual windows have no decorations, the window is as large as the client area.
+    pub fn window_rect(self) -> Result<Rect, Error> {
+        let w = self.state()?;
+        Ok(Rect { left: w.x, top: w.y, right: w.x + w.width, bottom: w.y + w.height })
+    }
+
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        self.state()?.opacity = opacity.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    /// Last frame presented with a `PixelSurface`, black if nothing was presented since the window was resized.
+    pub fn capture(self) -> Result<Image, Error> {
+        let w = self.state()?;
+
+        if !w.visible {
+            return Err(Error::other("Window is not viewable."));
+        }
+
+        match &w.frame {
+            Some(frame) if (frame.width, frame.height) == (w.width as u32, w.height as u32) => Ok(frame.clone()),
+            _ => {
+                let mut image = Image::new(w.width.max(0) as u32, w.height.max(0) as u32);
+                image.pixels.chunks_exact_mut(4).for_each(|p| p[3] = u8::MAX);
+                Ok(image)
+            }
+        }
+    }
+
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        self.state()?.decorations = decorations;
+        Ok(())
+    }
+
+    pub fn drag_move(self) -> Result<(), Error> {
+        self.state()?;
+        Ok(())
+    }
+
+    pub fn drag_resize(self, _edge: ResizeEdge) -> Result<(), Error> {
+        self.state()?;
+        Ok(())
+    }
+
+    pub fn set_always_on_top(self, always_on_top: bool) -> Result<(), Error> {
+        self.state()?.always_on_top = always_on_top;
+        Ok(())
+    }
+
+    pub fn set_skip_taskbar(self, skip_taskbar: bool) -> Result<(), Error> {
+        self.state()?.skip_taskbar = skip_taskbar;
+        Ok(())
+    }
+
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        self.state()?.user_attention = attention;
+        Ok(())
+    }
+
+    pub fn set_visible_on_all_workspaces(self, visible: bool) -> Result<(), Error> {
+        self.state()?.all_workspaces = visible;
+        Ok(())
+    }
+
+    pub fn move_to_workspace(self, workspace: u32) -> Result<(), Error> {
+        self.state()?.workspace = Some(workspace);
+        Ok(())
+    }
+
+    /// Copy of the tracked state, `None` once the window is destroyed.
+    pub fn virtual_window(self) -> Option<VirtualWindow> {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.windows.get(&self).cloned()
+    }
+
+    /// Always `None`, virtual windows have no X11 window for GLX and Xlib calls.
+    #[cfg(target_os = "linux")]
+    pub fn x11(self) -> Option<crate::window::_x11::WindowHandle> {
+        None
+    }
+
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        self.state().map(|_| ())
+    }
+
+    pub(super) fn state(self) -> Result<&'static mut VirtualWindow, Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.windows.get_mut(&self).ok_or_else(|| crate::Error::WindowDestroyed.into())
+    }
+}
+
+impl Destroy for WindowHandle {
+    fn destroy(&mut self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.windows.remove(self).ok_or(crate::Error::WindowDestroyed)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowBuilder {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub title: String,
+    pub transparent: bool,
+    pub kind: WindowKind,
+    pub owner: Option<WindowHandle>,
+    pub anchor: Option<Rect>,
+    pub decorations: bool,
+    pub always_on_top: bool,
+    pub skip_taskbar: bool,
+    pub user_attention: Option<UserAttention>,
+    pub all_workspaces: bool,
+    pub workspace: Option<u32>,
+}
+
+impl Default for WindowBuilder {
+    fn default() -> Self {
+        Self {
+            x: Default::default(),
+            y: Default::default(),
+            width: 480,
+            height: 170,
+            title: String::new(),
+            transparent: false,
+            kind: WindowKind::Normal,
+            owner: None,
+            anchor: None,
+            decorations: true,
+            always_on_top: false,
+            skip_taskbar: false,
+            user_attention: None,
+            all_workspaces: false,
+            workspace: None,
+        }
+    }
+}
+
+impl WindowBuilder {
+    pub fn with_title(&mut self, title: &str) -> &mut Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn with_size(&mut self, width: i32, height: i32) -> &mut Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, skip_taskbar: bool) -> &mut Self {
+        self.skip_taskbar = skip_taskbar;
+        self
+    }
+
+    pub fn with_user_attention(&mut self, attention: Option<UserAttention>) -> &mut Self {
+        self.user_attention = attention;
+        self
+    }
+
+    pub fn with_visible_on_all_workspaces(&mut self, visible: bool) -> &mut Self {
+        self.all_workspaces = visible;
+        self
+    }
+
+    pub fn with_workspace(&mut self, workspace: u32) -> &mut Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    /// Ignored, injected events are always delivered.
+    pub fn with_events(&mut self, _events: EventMask) -> &mut Self {
+        self
+    }
+
+    /// Popups are placed below their anchor in the owner like window managers do, without a screen to keep them on.
+    pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
+        let c = unsafe { context.0.to_ref() };
+
+        let (mut x, mut y) = (self.x, self.y);
+        if let (true, Some(owner), Some(anchor)) = (self.kind.is_popup(), self.owner, self.anchor) {
+            let owner = owner.state()?;
+            (x, y) = (owner.x + anchor.left, owner.y + anchor.bottom);
+        }
+
+        let handle = WindowHandle(c.next_window);
+        c.next_window += 1;
+
+        c.windows.insert(
+            handle,
+            VirtualWindow {
+                title: self.title.clone(),
+                x,
+                y,
+                width: self.width,
+                height: self.height,
+                visible: false,
+                focused: false,
+                minimized: false,
+                maximized: false,
+                occluded: false,
+                transparent: self.transparent,
+                decorations: self.decorations,
+                opacity: 1.0,
+                kind: self.kind,
+                owner: self.owner,
+                anchor: self.anchor,
+                always_on_top: self.always_on_top,
+                skip_taskbar: self.skip_taskbar,
+                user_attention: self.user_attention,
+                all_workspaces: self.all_workspaces,
+                workspace: self.workspace,
+                frame: None,
+            },
+        );
+
+        Ok(handle)
+    }
+
+    /// Virtual windows have no visual to pick, GLX and Vulkan surfaces need a display server.
+    #[cfg(target_os = "linux")]
+    pub fn build_with_visual<E: Event>(&self, _context: Context, _visual: super::WindowVisual) -> Result<WindowHandle, Error> {
+        Err(crate::Error::Unsupported("visuals in headless builds").into())
+    }
+}
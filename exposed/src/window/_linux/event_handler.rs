@@ -0,0 +1,120 @@
+use std::{
+    io::Error,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+};
+
+use exposed_macro::log_warn;
+
+use crate::{
+    destroy::Destroy,
+    window::{Backend, Event},
+};
+
+use super::{_wayland, _x11};
+
+#[derive(Debug)]
+pub enum EventHandler<E: Event> {
+    X11(_x11::EventHandler<E>),
+    Wayland(_wayland::EventHandler<E>),
+}
+
+impl<E: Event> EventHandler<E> {
+    pub fn poll(&mut self) -> i32 {
+        match self {
+            Self::X11(e) => e.poll(),
+            Self::Wayland(e) => e.poll(),
+        }
+    }
+
+    pub fn wait(&mut self) -> i32 {
+        match self {
+            Self::X11(e) => e.wait(),
+            Self::Wayland(e) => e.wait(),
+        }
+    }
+
+    pub fn dispatch(&mut self) {
+        match self {
+            Self::X11(e) => e.dispatch(),
+            Self::Wayland(e) => e.dispatch(),
+        }
+    }
+}
+
+impl<E: Event> AsRawFd for EventHandler<E> {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::X11(e) => e.as_raw_fd(),
+            Self::Wayland(e) => e.as_raw_fd(),
+        }
+    }
+}
+
+impl<E: Event> AsFd for EventHandler<E> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::X11(e) => e.as_fd(),
+            Self::Wayland(e) => e.as_fd(),
+        }
+    }
+}
+
+impl<E: Event> Destroy for EventHandler<E> {
+    fn destroy(&mut self) -> Result<(), Error> {
+        match self {
+            Self::X11(e) => e.destroy(),
+            Self::Wayland(e) => e.destroy(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventHandlerBuilder {
+    pub backend: Option<Backend>,
+    pub x11: _x11::EventHandlerBuilder,
+    pub wayland: _wayland::EventHandlerBuilder,
+}
+
+impl EventHandlerBuilder {
+    pub fn with_backend(&mut self, backend: Backend) -> &mut Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn with_app_id(&mut self, name: &str, class: &str) -> &mut Self {
+        self.x11.with_app_id(name, class);
+        self.wayland.with_app_id(name, class);
+        self
+    }
+
+    pub fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        self.x11.with_gamepad_mappings(mappings);
+        self.wayland.with_gamepad_mappings(mappings);
+        self
+    }
+
+    /// Without a chosen backend Wayland is tried first when `WAYLAND_DISPLAY` is set, X11 is used if it fails.
+    pub(crate) unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, crate::Error> {
+        // Both backends share the timers and requests of the thread.
+        if !_x11::ThreadContext::current_thread().display.is_null()
+            || _wayland::ThreadContext::current_thread().connection.is_some()
+        {
+            return Err(crate::Error::EventHandlerExists);
+        }
+
+        match self.backend.or_else(Backend::from_env) {
+            Some(Backend::X11) => Ok(EventHandler::X11(self.x11.build(user_data)?)),
+            Some(Backend::Wayland) => Ok(EventHandler::Wayland(self.wayland.build(user_data)?)),
+            None if std::env::var_os("WAYLAND_DISPLAY").is_some() => match self.wayland.build(user_data) {
+                Ok(event_handler) => Ok(EventHandler::Wayland(event_handler)),
+                // Only failures of the compositor, the app already ran `Event::create` for other errors.
+                Err(e @ (crate::Error::DisplayUnavailable(_) | crate::Error::Unsupported(_))) => {
+                    log_warn!("Exposed", "Falling back to X11, Wayland failed {e}");
+                    Ok(EventHandler::X11(self.x11.build(user_data)?))
+                }
+                Err(e) => Err(e),
+            },
+            None => Ok(EventHandler::X11(self.x11.build(user_data)?)),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! Picks X11 or Wayland when the event handler is built, used when the `wayland` feature compiles both.
+
+mod window;
+pub use window::*;
+
+mod event_handler;
+pub use event_handler::*;
+
+mod pixel_surface;
+pub use pixel_surface::*;
+
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
+
+use super::{_wayland, _x11};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Context {
+    X11(_x11::Context),
+    Wayland(_wayland::Context),
+}
+
+impl Context {
+    /// The X11 connection for GLX and Xlib calls, `None` on Wayland.
+    pub fn x11(self) -> Option<_x11::Context> {
+        match self {
+            Context::X11(context) => Some(context),
+            Context::Wayland(_) => None,
+        }
+    }
+}
+
+pub struct Android<E: super::Event>(pub std::marker::PhantomData<E>);
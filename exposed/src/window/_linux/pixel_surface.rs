@@ -0,0 +1,69 @@
+use std::io::Error;
+
+use crate::{destroy::Destroy, window::Rect};
+
+use super::{_wayland, _x11, WindowHandle};
+
+#[derive(Debug)]
+pub enum BackendPixelSurface {
+    X11(_x11::PixelSurface),
+    Wayland(_wayland::PixelSurface),
+}
+
+/// `width` and `height` are copied from the backend surface after `buffer_mut`.
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub width: u32,
+    pub height: u32,
+    pub surface: BackendPixelSurface,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        let surface = match window {
+            WindowHandle::X11(window) => BackendPixelSurface::X11(_x11::PixelSurface::new(window)?),
+            WindowHandle::Wayland(window) => BackendPixelSurface::Wayland(_wayland::PixelSurface::new(window)?),
+        };
+
+        Ok(Self { width: 0, height: 0, surface })
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        // The size lives outside the pixels, reading it doesn't touch the returned buffer.
+        match &mut self.surface {
+            BackendPixelSurface::X11(s) => {
+                let buffer: *mut [u32] = s.buffer_mut()?;
+                (self.width, self.height) = (s.width, s.height);
+                Ok(unsafe { &mut *buffer })
+            }
+            BackendPixelSurface::Wayland(s) => {
+                let buffer: *mut [u32] = s.buffer_mut()?;
+                (self.width, self.height) = (s.width, s.height);
+                Ok(unsafe { &mut *buffer })
+            }
+        }
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        match &mut self.surface {
+            BackendPixelSurface::X11(s) => s.present(),
+            BackendPixelSurface::Wayland(s) => s.present(),
+        }
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        match &mut self.surface {
+            BackendPixelSurface::X11(s) => s.present_with_damage(damage),
+            BackendPixelSurface::Wayland(s) => s.present_with_damage(damage),
+        }
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        match &mut self.surface {
+            BackendPixelSurface::X11(s) => s.destroy(),
+            BackendPixelSurface::Wayland(s) => s.destroy(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Context, WindowHandle};
+
+macro_rules! dispatch {
+    ($self:expr, $w:ident => $call:expr) => {
+        match $self {
+            WindowHandle::X11($w) => $call,
+            WindowHandle::Wayland($w) => $call,
+        }
+    };
+}
+
+macro_rules! dispatch_context {
+    ($self:expr, $c:ident => $call:expr) => {
+        match $self {
+            Context::X11($c) => $call,
+            Context::Wayland($c) => $call,
+        }
+    };
+}
+
+#[cfg(feature = "rwh_05")]
+impl WindowHandle {
+    pub fn raw_window_handle_05(self) -> rwh_05::RawWindowHandle {
+        dispatch!(self, w => w.raw_window_handle_05())
+    }
+
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        dispatch!(self, w => w.raw_display_handle_05())
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+impl Context {
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        dispatch_context!(self, c => c.raw_display_handle_05())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl WindowHandle {
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        dispatch!(self, w => w.raw_window_handle_06())
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        dispatch!(self, w => w.raw_display_handle_06())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        dispatch_context!(self, c => c.raw_display_handle_06())
+    }
+}
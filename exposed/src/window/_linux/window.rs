@@ -0,0 +1,236 @@
+use std::io::Error;
+
+use crate::{
+    destroy::Destroy,
+    window::{Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
+};
+
+use super::{_wayland, _x11, Context};
+
+pub use _x11::WindowVisual;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WindowHandle {
+    X11(_x11::WindowHandle),
+    Wayland(_wayland::WindowHandle),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $w:ident => $call:expr) => {
+        match $self {
+            WindowHandle::X11($w) => $call,
+            WindowHandle::Wayland($w) => $call,
+        }
+    };
+}
+
+impl WindowHandle {
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        dispatch!(self, w => w.alive())
+    }
+
+    pub fn show(self) -> Result<(), Error> {
+        dispatch!(self, w => w.show())
+    }
+
+    pub fn update(self) -> Result<(), Error> {
+        dispatch!(self, w => w.update())
+    }
+
+    pub fn redraw(self) -> Result<(), Error> {
+        dispatch!(self, w => w.redraw())
+    }
+
+    pub fn window_title(self) -> Result<String, Error> {
+        dispatch!(self, w => w.window_title())
+    }
+
+    pub fn set_window_title(self, title: &str) -> Result<(), Error> {
+        dispatch!(self, w => w.set_window_title(title))
+    }
+
+    pub fn dpi(self) -> Result<u32, Error> {
+        dispatch!(self, w => w.dpi())
+    }
+
+    pub fn set_capture(self) {
+        dispatch!(self, w => w.set_capture())
+    }
+
+    pub fn release_capture(self) -> Result<(), Error> {
+        dispatch!(self, w => w.release_capture())
+    }
+
+    pub fn client_size(self) -> Result<Size, Error> {
+        dispatch!(self, w => w.client_size())
+    }
+
+    pub fn client_rect(self) -> Result<Rect, Error> {
+        dispatch!(self, w => w.client_rect())
+    }
+
+    pub fn window_rect(self) -> Result<Rect, Error> {
+        dispatch!(self, w => w.window_rect())
+    }
+
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        dispatch!(self, w => w.set_opacity(opacity))
+    }
+
+    pub fn capture(self) -> Result<Image, Error> {
+        dispatch!(self, w => w.capture())
+    }
+
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        dispatch!(self, w => w.set_decorations(decorations))
+    }
+
+    pub fn drag_move(self) -> Result<(), Error> {
+        dispatch!(self, w => w.drag_move())
+    }
+
+    pub fn drag_resize(self, edge: ResizeEdge) -> Result<(), Error> {
+        dispatch!(self, w => w.drag_resize(edge))
+    }
+
+    pub fn set_always_on_top(self, always_on_top: bool) -> Result<(), Error> {
+        dispatch!(self, w => w.set_always_on_top(always_on_top))
+    }
+
+    pub fn set_skip_taskbar(self, skip_taskbar: bool) -> Result<(), Error> {
+        dispatch!(self, w => w.set_skip_taskbar(skip_taskbar))
+    }
+
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        dispatch!(self, w => w.request_user_attention(attention))
+    }
+
+    pub fn set_visible_on_all_workspaces(self, visible: bool) -> Result<(), Error> {
+        dispatch!(self, w => w.set_visible_on_all_workspaces(visible))
+    }
+
+    pub fn move_to_workspace(self, workspace: u32) -> Result<(), Error> {
+        dispatch!(self, w => w.move_to_workspace(workspace))
+    }
+
+    /// The X11 window for GLX and Xlib calls, `None` on Wayland.
+    pub fn x11(self) -> Option<_x11::WindowHandle> {
+        match self {
+            WindowHandle::X11(window) => Some(window),
+            WindowHandle::Wayland(_) => None,
+        }
+    }
+}
+
+impl Destroy for WindowHandle {
+    fn destroy(&mut self) -> Result<(), Error> {
+        dispatch!(self, w => w.destroy())
+    }
+}
+
+/// Holds a builder for each backend since windows are built before the context tells which one is used.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowBuilder {
+    pub x11: _x11::WindowBuilder,
+    pub wayland: _wayland::WindowBuilder,
+}
+
+impl WindowBuilder {
+    pub fn with_title(&mut self, title: &str) -> &mut Self {
+        self.x11.with_title(title);
+        self.wayland.with_title(title);
+        self
+    }
+
+    pub fn with_size(&mut self, width: i32, height: i32) -> &mut Self {
+        self.x11.with_size(width, height);
+        self.wayland.with_size(width, height);
+        self
+    }
+
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.x11.with_transparent(transparent);
+        self.wayland.with_transparent(transparent);
+        self
+    }
+
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.x11.with_kind(kind);
+        self.wayland.with_kind(kind);
+        self
+    }
+
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        match owner {
+            WindowHandle::X11(owner) => {
+                self.x11.with_owner(owner);
+            }
+            WindowHandle::Wayland(owner) => {
+                self.wayland.with_owner(owner);
+            }
+        }
+        self
+    }
+
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.x11.with_anchor(anchor);
+        self.wayland.with_anchor(anchor);
+        self
+    }
+
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.x11.with_decorations(decorations);
+        self.wayland.with_decorations(decorations);
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.x11.with_always_on_top(always_on_top);
+        self.wayland.with_always_on_top(always_on_top);
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, skip_taskbar: bool) -> &mut Self {
+        self.x11.with_skip_taskbar(skip_taskbar);
+        self.wayland.with_skip_taskbar(skip_taskbar);
+        self
+    }
+
+    pub fn with_user_attention(&mut self, attention: Option<UserAttention>) -> &mut Self {
+        self.x11.with_user_attention(attention);
+        self.wayland.with_user_attention(attention);
+        self
+    }
+
+    pub fn with_visible_on_all_workspaces(&mut self, visible: bool) -> &mut Self {
+        self.x11.with_visible_on_all_workspaces(visible);
+        self.wayland.with_visible_on_all_workspaces(visible);
+        self
+    }
+
+    pub fn with_workspace(&mut self, workspace: u32) -> &mut Self {
+        self.x11.with_workspace(workspace);
+        self.wayland.with_workspace(workspace);
+        self
+    }
+
+    pub fn with_events(&mut self, events: EventMask) -> &mut Self {
+        self.x11.with_events(events);
+        self.wayland.with_events(events);
+        self
+    }
+
+    pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
+        match context {
+            Context::X11(context) => Ok(WindowHandle::X11(self.x11.build::<E>(context)?)),
+            Context::Wayland(context) => Ok(WindowHandle::Wayland(self.wayland.build::<E>(context)?)),
+        }
+    }
+
+    pub fn build_with_visual<E: Event>(&self, context: Context, visual: WindowVisual) -> Result<WindowHandle, Error> {
+        match context {
+            Context::X11(context) => Ok(WindowHandle::X11(self.x11.build_with_visual::<E>(context, visual)?)),
+            Context::Wayland(_) => Err(crate::Error::Unsupported("visuals on Wayland").into()),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    cell::UnsafeCell,
+    collections::{HashMap, VecDeque},
+};
+
+use wayland_client::{
+    protocol::{
+        wl_compositor::WlCompositor, wl_keyboard::WlKeyboard, wl_pointer::WlPointer, wl_seat::WlSeat, wl_shm::WlShm,
+        wl_surface::WlSurface, wl_touch::WlTouch,
+    },
+    Connection, QueueHandle,
+};
+use wayland_protocols::{
+    wp::{
+        alpha_modifier::v1::client::{
+            wp_alpha_modifier_surface_v1::WpAlphaModifierSurfaceV1, wp_alpha_modifier_v1::WpAlphaModifierV1,
+        },
+        cursor_shape::v1::client::{
+            wp_cursor_shape_device_v1::WpCursorShapeDeviceV1, wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
+        },
+        fractional_scale::v1::client::{
+            wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1, wp_fractional_scale_v1::WpFractionalScaleV1,
+        },
+        viewporter::client::{wp_viewport::WpViewport, wp_viewporter::WpViewporter},
+    },
+    xdg::{
+        activation::v1::client::xdg_activation_v1::XdgActivationV1,
+        decoration::zv1::client::{
+            zxdg_decoration_manager_v1::ZxdgDecorationManagerV1, zxdg_toplevel_decoration_v1::ZxdgToplevelDecorationV1,
+        },
+        shell::client::{xdg_popup::XdgPopup, xdg_surface::XdgSurface, xdg_toplevel::XdgToplevel, xdg_wm_base::XdgWmBase},
+    },
+};
+
+use crate::window::{Key, MouseButton, ScanCode, TimerId, Timers, Touch};
+
+use super::{WindowHandle, XkbKeyboard};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Context(pub *mut ThreadContext);
+
+thread_local! {
+    static CONTEXT:UnsafeCell<ThreadContext> = UnsafeCell::new(ThreadContext::new());
+}
+
+/// Wayland objects live here instead of the `EventHandler` because `WindowHandle` methods need them.
+#[derive(Debug)]
+pub struct ThreadContext {
+    pub connection: Option<Connection>,
+    pub queue_handle: Option<QueueHandle<ThreadContext>>,
+    pub globals: Option<Globals>,
+    pub windows: HashMap<WindowHandle, WindowState>,
+    pub seat: SeatState,
+    /// Events collected while dispatching the Wayland queue, `EventHandler::dispatch` hands them to the app.
+    pub events: VecDeque<WaylandEvent>,
+    /// `xdg_toplevel.set_app_id`, desktop environments match it with the `.desktop` file name.
+    pub app_id: String,
+    /// Read from `XDG_ACTIVATION_TOKEN`, used to activate the first window that is shown.
+    /// The variable stays set, remove it before spawning children that must not activate with it.
+    pub activation_token: Option<String>,
+    /// Generations of the live windows by surface protocol id, ids are reused once a surface is destroyed.
+    pub generations: HashMap<u32, u32>,
+    pub generation: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Globals {
+    pub compositor: WlCompositor,
+    pub wm_base: XdgWmBase,
+    pub shm: Option<WlShm>,
+    pub seat: Option<WlSeat>,
+    /// Only bound together with `viewporter`, fractional scales need a viewport to map buffers to the surface.
+    pub fractional_scale: Option<WpFractionalScaleManagerV1>,
+    pub viewporter: Option<WpViewporter>,
+    pub decoration: Option<ZxdgDecorationManagerV1>,
+    pub cursor_shape: Option<WpCursorShapeManagerV1>,
+    pub activation: Option<XdgActivationV1>,
+    pub alpha_modifier: Option<WpAlphaModifierV1>,
+}
+
+#[derive(Debug)]
+pub struct WindowState {
+    pub surface: WlSurface,
+    pub xdg_surface: XdgSurface,
+    pub toplevel: Option<XdgToplevel>,
+    pub popup: Option<XdgPopup>,
+    pub decoration: Option<ZxdgToplevelDecorationV1>,
+    pub fractional_scale: Option<WpFractionalScaleV1>,
+    pub viewport: Option<WpViewport>,
+    /// Created by the first `set_opacity`.
+    pub alpha_modifier: Option<WpAlphaModifierSurfaceV1>,
+    pub title: String,
+    /// Surface size in logical pixels.
+    pub width: i32,
+    pub height: i32,
+    /// Size from the last role configure, applied when the `xdg_surface` configure arrives.
+    pub pending_size: (i32, i32),
+    /// In 120ths like `wp_fractional_scale_v1`.
+    pub scale: u32,
+    pub maximized: bool,
+    pub configured: bool,
+}
+
+impl WindowState {
+    /// Size of the buffers attached to the surface.
+    pub fn physical_size(&self) -> (i32, i32) {
+        (scale_size(self.width, self.scale), scale_size(self.height, self.scale))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SeatState {
+    pub keyboard: Option<WlKeyboard>,
+    pub pointer: Option<WlPointer>,
+    pub touch: Option<WlTouch>,
+    pub cursor_shape: Option<WpCursorShapeDeviceV1>,
+    pub xkb: Option<XkbKeyboard>,
+    pub keyboard_focus: Option<WindowHandle>,
+    pub pointer_focus: Option<WindowHandle>,
+    /// Serial of the last button press or touch down, `drag_move`, `drag_resize` and popup grabs need one.
+    pub input_serial: u32,
+    pub touches: HashMap<i32, (WindowHandle, (f32, f32))>,
+    /// Keys per second and delay in milliseconds from `wl_keyboard.repeat_info`, a rate of 0 disables repeat.
+    pub repeat_info: (i32, i32),
+    pub repeat: Option<KeyRepeat>,
+}
+
+/// Held key that `dispatch` repeats, the compositor only sends the rate and delay.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyRepeat {
+    /// evdev code from `wl_keyboard.key`.
+    pub key: u32,
+    /// Fires once after the delay, then every period of the rate once `repeating` is set.
+    pub timer: TimerId,
+    pub repeating: bool,
+}
+
+impl SeatState {
+    pub fn stop_repeat(&mut self) {
+        if let Some(repeat) = self.repeat.take() {
+            Timers::stop(repeat.timer);
+        }
+    }
+}
+
+/// Events are queued while the Wayland queue dispatches, so the app can use `WindowHandle` methods in its callbacks.
+#[derive(Debug, Clone)]
+pub enum WaylandEvent {
+    Render(WindowHandle),
+    Resized(WindowHandle, i32, i32),
+    Maximized(WindowHandle),
+    Focused(WindowHandle, bool),
+    CloseRequested(WindowHandle),
+    ScaleChanged,
+    KeyDown(WindowHandle, Key, ScanCode),
+    KeyUp(WindowHandle, Key, ScanCode),
+    Character(WindowHandle, char),
+    CursorEntered(WindowHandle),
+    CursorLeft(WindowHandle),
+    CursorMoved(WindowHandle, i32, i32),
+    MouseButtonDown(WindowHandle, MouseButton),
+    MouseButtonRelease(WindowHandle, MouseButton),
+    MouseWheel(WindowHandle, f32, f32),
+    Touch(WindowHandle, Touch, usize),
+    TouchEnd(WindowHandle),
+}
+
+impl ThreadContext {
+    pub fn new() -> Self {
+        Self {
+            connection: None,
+            queue_handle: None,
+            globals: None,
+            windows: HashMap::new(),
+            seat: SeatState::default(),
+            events: VecDeque::new(),
+            app_id: String::new(),
+            activation_token: None,
+            generations: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    /// Gives the surface `id` a new generation, handles of the previous window with the same id become stale.
+    pub fn register_window(&mut self, id: u32) -> WindowHandle {
+        self.generation = self.generation.wrapping_add(1);
+        self.generations.insert(id, self.generation);
+        WindowHandle(id, self.generation)
+    }
+
+    /// Handle of the window with the surface `id` if it's alive.
+    pub fn window_handle(&self, id: u32) -> Option<WindowHandle> {
+        self.generations.get(&id).map(|generation| WindowHandle(id, *generation))
+    }
+
+    pub unsafe fn current_thread() -> &'static mut ThreadContext {
+        CONTEXT.with(|c| &mut *c.get())
+    }
+}
+
+/// Scales a logical length by a scale in 120ths, rounding half away from zero like `wp_fractional_scale_v1` asks.
+pub fn scale_size(length: i32, scale: u32) -> i32 {
+    ((length as i64 * scale as i64 + 60) / 120) as i32
+}
+
+#[test]
+fn scale_size_test() {
+    assert_eq!(scale_size(480, 120), 480);
+    assert_eq!(scale_size(480, 180), 720);
+    assert_eq!(scale_size(481, 150), 601);
+    assert_eq!(scale_size(3, 140), 4);
+    assert_eq!(scale_size(0, 240), 0);
+}
+
+#[test]
+fn window_generation_test() {
+    let c = unsafe { ThreadContext::current_thread() };
+
+    let old = c.register_window(7);
+    assert_eq!(c.window_handle(7), Some(old));
+
+    // The compositor reuses the id of a destroyed surface.
+    c.generations.remove(&7);
+    let new = c.register_window(7);
+
+    assert_eq!(c.window_handle(7), Some(new));
+    assert_ne!(old, new);
+}
@@ -0,0 +1,551 @@
+use std::time::Duration;
+
+use wayland_client::{
+    delegate_noop,
+    globals::GlobalListContents,
+    protocol::{
+        wl_keyboard::{self, KeyState, KeymapFormat, WlKeyboard},
+        wl_pointer::{self, Axis, ButtonState, WlPointer},
+        wl_registry::WlRegistry,
+        wl_seat::{self, Capability, WlSeat},
+        wl_shm::WlShm,
+        wl_shm_pool::WlShmPool,
+        wl_surface::{self, WlSurface},
+        wl_touch::{self, WlTouch},
+    },
+    Connection, Dispatch, Proxy, QueueHandle, WEnum,
+};
+use wayland_protocols::{
+    wp::{
+        alpha_modifier::v1::client::{
+            wp_alpha_modifier_surface_v1::WpAlphaModifierSurfaceV1, wp_alpha_modifier_v1::WpAlphaModifierV1,
+        },
+        cursor_shape::v1::client::{
+            wp_cursor_shape_device_v1::{Shape, WpCursorShapeDeviceV1},
+            wp_cursor_shape_manager_v1::WpCursorShapeManagerV1,
+        },
+        fractional_scale::v1::client::{
+            wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1,
+            wp_fractional_scale_v1::{self, WpFractionalScaleV1},
+        },
+        viewporter::client::{wp_viewport::WpViewport, wp_viewporter::WpViewporter},
+    },
+    xdg::{
+        activation::v1::client::{
+            xdg_activation_token_v1::{self, XdgActivationTokenV1},
+            xdg_activation_v1::XdgActivationV1,
+        },
+        decoration::zv1::client::{
+            zxdg_decoration_manager_v1::ZxdgDecorationManagerV1, zxdg_toplevel_decoration_v1::ZxdgToplevelDecorationV1,
+        },
+        shell::client::{
+            xdg_popup::{self, XdgPopup},
+            xdg_positioner::XdgPositioner,
+            xdg_surface::{self, XdgSurface},
+            xdg_toplevel::{self, State, XdgToplevel},
+            xdg_wm_base::{self, XdgWmBase},
+        },
+    },
+};
+
+use exposed_macro::log_warn;
+
+use crate::window::{MouseButton, TimerId, Timers, Touch, TouchPhase};
+
+use super::{KeyRepeat, ThreadContext, WaylandEvent, WindowHandle, XkbKeyboard};
+
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+const BTN_SIDE: u32 = 0x113;
+const BTN_EXTRA: u32 = 0x114;
+
+/// Surface units a mouse wheel notch scrolls, `wl_pointer.axis` values are divided by it like `WHEEL_DELTA` on Windows.
+const WHEEL_STEP: f64 = 15.0;
+
+delegate_noop!(ThreadContext: ignore WlShm);
+delegate_noop!(ThreadContext: ignore WlShmPool);
+delegate_noop!(ThreadContext: ignore XdgPositioner);
+delegate_noop!(ThreadContext: ignore ZxdgToplevelDecorationV1);
+delegate_noop!(ThreadContext: wayland_client::protocol::wl_compositor::WlCompositor);
+delegate_noop!(ThreadContext: WpFractionalScaleManagerV1);
+delegate_noop!(ThreadContext: WpViewporter);
+delegate_noop!(ThreadContext: WpViewport);
+delegate_noop!(ThreadContext: ZxdgDecorationManagerV1);
+delegate_noop!(ThreadContext: WpCursorShapeManagerV1);
+delegate_noop!(ThreadContext: WpCursorShapeDeviceV1);
+delegate_noop!(ThreadContext: XdgActivationV1);
+delegate_noop!(ThreadContext: WpAlphaModifierV1);
+delegate_noop!(ThreadContext: WpAlphaModifierSurfaceV1);
+
+impl Dispatch<WlRegistry, GlobalListContents> for ThreadContext {
+    fn event(
+        _state: &mut Self, _proxy: &WlRegistry, _event: <WlRegistry as Proxy>::Event, _data: &GlobalListContents,
+        _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        // Globals are bound once when the event handler is built.
+    }
+}
+
+impl Dispatch<XdgWmBase, ()> for ThreadContext {
+    fn event(
+        _state: &mut Self, wm_base: &XdgWmBase, event: xdg_wm_base::Event, _data: &(), _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let xdg_wm_base::Event::Ping { serial } = event {
+            wm_base.pong(serial);
+        }
+    }
+}
+
+impl Dispatch<WlSurface, ()> for ThreadContext {
+    fn event(
+        state: &mut Self, surface: &WlSurface, event: wl_surface::Event, _data: &(), _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        // Integer scales are only used when the compositor has no fractional scale.
+        if let wl_surface::Event::PreferredBufferScale { factor } = event {
+            let Some(window) = state.window_handle(surface.id().protocol_id()) else { return };
+
+            if let Some(w) = state.windows.get_mut(&window) {
+                if w.fractional_scale.is_none() {
+                    surface.set_buffer_scale(factor);
+                    state.set_scale(window, factor as u32 * 120);
+                }
+            }
+        }
+    }
+}
+
+impl Dispatch<WpFractionalScaleV1, WindowHandle> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &WpFractionalScaleV1, event: wp_fractional_scale_v1::Event, window: &WindowHandle,
+        _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        if let wp_fractional_scale_v1::Event::PreferredScale { scale } = event {
+            state.set_scale(*window, scale);
+        }
+    }
+}
+
+impl Dispatch<XdgSurface, WindowHandle> for ThreadContext {
+    fn event(
+        state: &mut Self, xdg_surface: &XdgSurface, event: xdg_surface::Event, window: &WindowHandle, _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        let xdg_surface::Event::Configure { serial } = event else {
+            return;
+        };
+
+        xdg_surface.ack_configure(serial);
+
+        let w = match state.windows.get_mut(window) {
+            Some(w) => w,
+            None => return,
+        };
+
+        let (width, height) = w.pending_size;
+        let resized = (width > 0 && width != w.width) || (height > 0 && height != w.height);
+
+        if width > 0 {
+            w.width = width;
+        }
+        if height > 0 {
+            w.height = height;
+        }
+
+        if resized || !w.configured {
+            w.configured = true;
+
+            if let Some(viewport) = &w.viewport {
+                viewport.set_destination(w.width.max(1), w.height.max(1));
+            }
+
+            let (width, height) = w.physical_size();
+            state.events.push_back(WaylandEvent::Resized(*window, width, height));
+        }
+
+        state.events.push_back(WaylandEvent::Render(*window));
+    }
+}
+
+impl Dispatch<XdgToplevel, WindowHandle> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &XdgToplevel, event: xdg_toplevel::Event, window: &WindowHandle, _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            xdg_toplevel::Event::Configure { width, height, states } => {
+                let w = match state.windows.get_mut(window) {
+                    Some(w) => w,
+                    None => return,
+                };
+
+                w.pending_size = (width, height);
+
+                // States are an array of native endian u32.
+                let maximized =
+                    states.chunks_exact(4).any(|s| u32::from_ne_bytes([s[0], s[1], s[2], s[3]]) == State::Maximized as u32);
+
+                if maximized && !w.maximized {
+                    state.events.push_back(WaylandEvent::Maximized(*window));
+                }
+                w.maximized = maximized;
+            }
+
+            xdg_toplevel::Event::Close => state.events.push_back(WaylandEvent::CloseRequested(*window)),
+
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<XdgPopup, WindowHandle> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &XdgPopup, event: xdg_popup::Event, window: &WindowHandle, _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        match event {
+            xdg_popup::Event::Configure { width, height, .. } => {
+                if let Some(w) = state.windows.get_mut(window) {
+                    w.pending_size = (width, height);
+                }
+            }
+
+            // Dismissed by the compositor, usually a click outside of the popup.
+            xdg_popup::Event::PopupDone => state.events.push_back(WaylandEvent::CloseRequested(*window)),
+
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<XdgActivationTokenV1, WindowHandle> for ThreadContext {
+    fn event(
+        state: &mut Self, token: &XdgActivationTokenV1, event: xdg_activation_token_v1::Event, window: &WindowHandle,
+        _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        if let xdg_activation_token_v1::Event::Done { token: value } = event {
+            let activation = state.globals.as_ref().and_then(|g| g.activation.as_ref());
+
+            if let (Some(activation), Some(w)) = (activation, state.windows.get(window)) {
+                activation.activate(value, &w.surface);
+            }
+
+            token.destroy();
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ()> for ThreadContext {
+    fn event(state: &mut Self, seat: &WlSeat, event: wl_seat::Event, _data: &(), _conn: &Connection, qh: &QueueHandle<Self>) {
+        let capabilities = match event {
+            wl_seat::Event::Capabilities { capabilities: WEnum::Value(capabilities) } => capabilities,
+            _ => return,
+        };
+
+        let s = &mut state.seat;
+
+        if capabilities.contains(Capability::Keyboard) {
+            s.keyboard.get_or_insert_with(|| seat.get_keyboard(qh, ()));
+        } else if let Some(keyboard) = s.keyboard.take() {
+            keyboard.release();
+            s.xkb = None;
+        }
+
+        if capabilities.contains(Capability::Pointer) {
+            if s.pointer.is_none() {
+                let pointer = seat.get_pointer(qh, ());
+
+                let cursor_shape = state.globals.as_ref().and_then(|g| g.cursor_shape.as_ref());
+                s.cursor_shape = cursor_shape.map(|manager| manager.get_pointer(&pointer, qh, ()));
+                s.pointer = Some(pointer);
+            }
+        } else if let Some(pointer) = s.pointer.take() {
+            if let Some(cursor_shape) = s.cursor_shape.take() {
+                cursor_shape.destroy();
+            }
+            pointer.release();
+        }
+
+        if capabilities.contains(Capability::Touch) {
+            s.touch.get_or_insert_with(|| seat.get_touch(qh, ()));
+        } else if let Some(touch) = s.touch.take() {
+            touch.release();
+        }
+    }
+}
+
+impl Dispatch<WlKeyboard, ()> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &WlKeyboard, event: wl_keyboard::Event, _data: &(), _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        let s = &mut state.seat;
+
+        match event {
+            wl_keyboard::Event::Keymap { format: WEnum::Value(KeymapFormat::XkbV1), fd, size } => {
+                s.xkb = XkbKeyboard::from_fd(fd, size);
+                if s.xkb.is_none() {
+                    log_warn!("Exposed", "Failed to load the keymap, libxkbcommon may be missing. Keyboard input is disabled.");
+                }
+            }
+
+            wl_keyboard::Event::Enter { surface, .. } => {
+                let Some(window) = state.window_handle(surface.id().protocol_id()) else { return };
+                let s = &mut state.seat;
+                s.keyboard_focus = Some(window);
+                state.events.push_back(WaylandEvent::Focused(window, true));
+            }
+
+            wl_keyboard::Event::Leave { .. } => {
+                s.stop_repeat();
+                if let Some(window) = s.keyboard_focus.take() {
+                    state.events.push_back(WaylandEvent::Focused(window, false));
+                }
+            }
+
+            wl_keyboard::Event::Modifiers { mods_depressed, mods_latched, mods_locked, group, .. } => {
+                if let Some(xkb) = &mut s.xkb {
+                    xkb.update_modifiers(mods_depressed, mods_latched, mods_locked, group);
+                }
+            }
+
+            wl_keyboard::Event::Key { key, state: key_state, .. } => {
+                let (window, xkb) = match (s.keyboard_focus, &s.xkb) {
+                    (Some(window), Some(xkb)) => (window, xkb),
+                    _ => return,
+                };
+
+                let (k, scancode) = xkb.key(key);
+
+                if key_state == WEnum::Value(KeyState::Pressed) {
+                    state.events.push_back(WaylandEvent::KeyDown(window, k, scancode));
+                    for c in xkb.text(key).chars() {
+                        state.events.push_back(WaylandEvent::Character(window, c));
+                    }
+
+                    // Only the last pressed key repeats, like on X11.
+                    let repeats = xkb.repeats(key);
+                    s.stop_repeat();
+                    let (rate, delay) = s.repeat_info;
+                    if rate > 0 && repeats {
+                        let timer = Timers::start(Duration::from_millis(delay.max(0) as _), false);
+                        s.repeat = Some(KeyRepeat { key, timer, repeating: false });
+                    }
+                } else {
+                    if s.repeat.is_some_and(|r| r.key == key) {
+                        s.stop_repeat();
+                    }
+                    state.events.push_back(WaylandEvent::KeyUp(window, k, scancode));
+                }
+            }
+
+            wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                s.repeat_info = (rate, delay);
+                if rate <= 0 {
+                    s.stop_repeat();
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlPointer, ()> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &WlPointer, event: wl_pointer::Event, _data: &(), _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        let s = &mut state.seat;
+
+        match event {
+            wl_pointer::Event::Enter { serial, surface, surface_x, surface_y } => {
+                let Some(window) = state.window_handle(surface.id().protocol_id()) else { return };
+                let s = &mut state.seat;
+                s.pointer_focus = Some(window);
+
+                // Without a cursor image the compositor may leave the previous one or hide it.
+                if let Some(cursor_shape) = &s.cursor_shape {
+                    cursor_shape.set_shape(serial, Shape::Default);
+                }
+
+                let (x, y) = state.to_physical(window, surface_x, surface_y);
+                state.events.push_back(WaylandEvent::CursorEntered(window));
+                state.events.push_back(WaylandEvent::CursorMoved(window, x as _, y as _));
+            }
+
+            wl_pointer::Event::Leave { .. } => {
+                if let Some(window) = s.pointer_focus.take() {
+                    state.events.push_back(WaylandEvent::CursorLeft(window));
+                }
+            }
+
+            wl_pointer::Event::Motion { surface_x, surface_y, .. } => {
+                if let Some(window) = s.pointer_focus {
+                    let (x, y) = state.to_physical(window, surface_x, surface_y);
+                    state.events.push_back(WaylandEvent::CursorMoved(window, x as _, y as _));
+                }
+            }
+
+            wl_pointer::Event::Button { serial, button, state: button_state, .. } => {
+                let window = match s.pointer_focus {
+                    Some(window) => window,
+                    None => return,
+                };
+
+                let button = match button {
+                    BTN_LEFT => MouseButton::LEFT,
+                    BTN_RIGHT => MouseButton::RIGHT,
+                    BTN_MIDDLE => MouseButton::MIDDLE,
+                    BTN_SIDE => MouseButton::X1,
+                    BTN_EXTRA => MouseButton::X2,
+                    button => MouseButton(button),
+                };
+
+                if button_state == WEnum::Value(ButtonState::Pressed) {
+                    s.input_serial = serial;
+                    state.events.push_back(WaylandEvent::MouseButtonDown(window, button));
+                } else {
+                    state.events.push_back(WaylandEvent::MouseButtonRelease(window, button));
+                }
+            }
+
+            wl_pointer::Event::Axis { axis, value, .. } => {
+                let window = match s.pointer_focus {
+                    Some(window) => window,
+                    None => return,
+                };
+
+                // Wayland scrolls down with positive values, Windows with negative ones.
+                let value = (-value / WHEEL_STEP) as f32;
+                let event = match axis {
+                    WEnum::Value(Axis::VerticalScroll) => WaylandEvent::MouseWheel(window, 0.0, value),
+                    WEnum::Value(Axis::HorizontalScroll) => WaylandEvent::MouseWheel(window, value, 0.0),
+                    _ => return,
+                };
+                state.events.push_back(event);
+            }
+
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlTouch, ()> for ThreadContext {
+    fn event(
+        state: &mut Self, _proxy: &WlTouch, event: wl_touch::Event, _data: &(), _conn: &Connection, _qh: &QueueHandle<Self>,
+    ) {
+        let (id, phase) = match event {
+            wl_touch::Event::Down { serial, surface, id, x, y, .. } => {
+                let Some(window) = state.window_handle(surface.id().protocol_id()) else { return };
+                let (x, y) = state.to_physical(window, x, y);
+
+                state.seat.input_serial = serial;
+                state.seat.touches.insert(id, (window, (x as f32, y as f32)));
+                (id, TouchPhase::Started)
+            }
+
+            wl_touch::Event::Motion { id, x, y, .. } => {
+                let window = match state.seat.touches.get(&id) {
+                    Some((window, _)) => *window,
+                    None => return,
+                };
+                let (x, y) = state.to_physical(window, x, y);
+
+                state.seat.touches.insert(id, (window, (x as f32, y as f32)));
+                (id, TouchPhase::Moved)
+            }
+
+            wl_touch::Event::Up { id, .. } => (id, TouchPhase::Ended),
+
+            wl_touch::Event::Cancel => {
+                let touches: Vec<_> = state.seat.touches.drain().collect();
+                let count = touches.len();
+
+                for (id, (window, location)) in &touches {
+                    let touch = Touch { phase: TouchPhase::Cancelled, location: *location, id: *id as _, ..Default::default() };
+                    state.events.push_back(WaylandEvent::Touch(*window, touch, count));
+                }
+
+                if let Some((_, (window, _))) = touches.first() {
+                    state.events.push_back(WaylandEvent::TouchEnd(*window));
+                }
+                return;
+            }
+
+            _ => return,
+        };
+
+        let (window, location) = match state.seat.touches.get(&id) {
+            Some(touch) => *touch,
+            None => return,
+        };
+
+        let count = state.seat.touches.len();
+        let pointer_index = state.seat.touches.keys().filter(|other| **other < id).count();
+        let touch = Touch { phase, location, pointer_index, id: id as _, os_data: 0 };
+        state.events.push_back(WaylandEvent::Touch(window, touch, count));
+
+        if phase == TouchPhase::Ended {
+            state.seat.touches.remove(&id);
+            if state.seat.touches.is_empty() {
+                state.events.push_back(WaylandEvent::TouchEnd(window));
+            }
+        }
+    }
+}
+
+impl ThreadContext {
+    /// Queues the repeated key if `timer` belongs to it, returns false for the timers of the app.
+    pub(super) fn repeat_key(&mut self, timer: TimerId) -> bool {
+        let s = &mut self.seat;
+        let repeat = match &mut s.repeat {
+            Some(repeat) if repeat.timer == timer => repeat,
+            _ => return false,
+        };
+
+        // The delay passed, the key repeats at the rate from now on.
+        if !repeat.repeating {
+            let period = Duration::from_secs(1) / s.repeat_info.0.max(1) as u32;
+            repeat.timer = Timers::start(period, true);
+            repeat.repeating = true;
+        }
+
+        let key = repeat.key;
+        if let (Some(window), Some(xkb)) = (s.keyboard_focus, &s.xkb) {
+            let (k, scancode) = xkb.key(key);
+
+            self.events.push_back(WaylandEvent::KeyDown(window, k, scancode));
+            for c in xkb.text(key).chars() {
+                self.events.push_back(WaylandEvent::Character(window, c));
+            }
+        }
+
+        true
+    }
+
+    /// Surface coordinates are logical, events report the same pixels as `resized`.
+    fn to_physical(&self, window: WindowHandle, x: f64, y: f64) -> (f64, f64) {
+        let scale = self.windows.get(&window).map(|w| w.scale).unwrap_or(120) as f64 / 120.0;
+        (x * scale, y * scale)
+    }
+
+    fn set_scale(&mut self, window: WindowHandle, scale: u32) {
+        let w = match self.windows.get_mut(&window) {
+            Some(w) => w,
+            None => return,
+        };
+
+        if w.scale == scale {
+            return;
+        }
+        w.scale = scale;
+
+        self.events.push_back(WaylandEvent::ScaleChanged);
+
+        if w.configured {
+            let (width, height) = w.physical_size();
+            self.events.push_back(WaylandEvent::Resized(window, width, height));
+            self.events.push_back(WaylandEvent::Render(window));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::{
+    ffi::c_int,
+    io::{Error, ErrorKind},
+    mem::zeroed,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+};
+
+use exposed_macro::log_warn;
+use libc::{close, epoll_create1, epoll_ctl, epoll_event, epoll_wait, EPOLLIN, EPOLL_CLOEXEC, EPOLL_CTL_ADD};
+use wayland_client::{globals::registry_queue_init, Connection, EventQueue};
+
+use crate::{
+    destroy::Destroy,
+    window::{Event, GamepadEvent, GamepadMappings, GamepadUpdate, Gamepads, Requests, Timers},
+};
+
+use super::{Context, Globals, ThreadContext, WaylandEvent};
+
+#[derive(Debug)]
+pub struct EventHandler<E: Event> {
+    pub connection: Connection,
+    pub queue: EventQueue<ThreadContext>,
+    pub user_data: *mut E,
+    pub epoll: c_int,
+    pub gamepads: Gamepads,
+}
+
+impl<E: Event> EventHandler<E> {
+    pub fn poll(&mut self) -> i32 {
+        self.epoll_wait(0);
+        self.read_events();
+
+        if self.has_events() {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn wait(&mut self) -> i32 {
+        loop {
+            self.dispatch_queue();
+            if self.has_events() {
+                return 1;
+            }
+
+            // Events read by another thread would be missed without the guard.
+            let guard = match self.queue.prepare_read() {
+                Some(guard) => guard,
+                None => continue,
+            };
+
+            if let Err(e) = self.connection.flush() {
+                log_warn!("Exposed", "Failed to flush the Wayland connection {e}");
+                return 0;
+            }
+
+            self.epoll_wait(-1);
+
+            if let Err(e) = guard.read() {
+                if !matches!(e, wayland_client::backend::WaylandError::Io(ref e) if e.kind() == ErrorKind::WouldBlock) {
+                    log_warn!("Exposed", "Failed to read Wayland events {e}");
+                    return 0;
+                }
+            }
+        }
+    }
+
+    /// Reads whatever the compositor already sent without blocking.
+    fn read_events(&mut self) {
+        let _ = self.connection.flush();
+
+        if let Some(guard) = self.queue.prepare_read() {
+            // WouldBlock only means there is nothing to read.
+            let _ = guard.read();
+        }
+
+        self.dispatch_queue();
+    }
+
+    fn dispatch_queue(&mut self) {
+        let c = unsafe { ThreadContext::current_thread() };
+
+        if let Err(e) = self.queue.dispatch_pending(c) {
+            log_warn!("Exposed", "Failed to dispatch Wayland events {e}");
+        }
+    }
+
+    fn has_events(&self) -> bool {
+        let c = unsafe { ThreadContext::current_thread() };
+        !c.events.is_empty() || self.gamepads.has_events() || Timers::has_events() || Requests::has_events()
+    }
+
+    /// Reads every ready source other than the Wayland connection.
+    fn epoll_wait(&mut self, timeout: c_int) {
+        let mut events: [epoll_event; 16] = unsafe { zeroed() };
+
+        let count = unsafe { epoll_wait(self.epoll, events.as_mut_ptr(), events.len() as _, timeout) };
+
+        for event in events.iter().take(count.max(0) as usize) {
+            if !Timers::ready(event.u64 as c_int) && !Requests::ready(event.u64 as c_int) {
+                self.gamepads.ready(event.u64 as c_int);
+            }
+        }
+    }
+
+    pub fn dispatch(&mut self) {
+        self.dispatch_wayland_events();
+        self.dispatch_gamepads();
+        self.dispatch_timers();
+        Requests::run();
+    }
+
+    fn dispatch_timers(&mut self) {
+        let app = unsafe { &mut *self.user_data };
+        let mut repeated = false;
+
+        while let Some(timer) = Timers::pop() {
+            if unsafe { ThreadContext::current_thread() }.repeat_key(timer) {
+                repeated = true;
+            } else {
+                app.timer_fired(timer);
+            }
+        }
+
+        // Repeated keys are delivered in the same dispatch as the timer.
+        if repeated {
+            self.dispatch_wayland_events();
+        }
+    }
+
+    fn dispatch_gamepads(&mut self) {
+        let app = unsafe { &mut *self.user_data };
+
+        while let Some((gamepad, update)) = self.gamepads.pop() {
+            match update {
+                GamepadUpdate::Connected(name) => app.gamepad_connected(gamepad, name),
+                GamepadUpdate::Disconnected => app.gamepad_disconnected(gamepad),
+                GamepadUpdate::Event(GamepadEvent::Button(button, true)) => app.gamepad_button_down(gamepad, button),
+                GamepadUpdate::Event(GamepadEvent::Button(button, false)) => app.gamepad_button_up(gamepad, button),
+                GamepadUpdate::Event(GamepadEvent::Axis(axis, value)) => app.axis_motion(gamepad, axis, value),
+            }
+        }
+    }
+
+    fn dispatch_wayland_events(&mut self) {
+        let app = unsafe { &mut *self.user_data };
+
+        // Popped one at a time, callbacks may queue more.
+        while let Some(event) = unsafe { ThreadContext::current_thread() }.events.pop_front() {
+            match event {
+                WaylandEvent::Render(window) => app.low_render(window.into()),
+                WaylandEvent::Resized(window, width, height) => app.resized(window.into(), width, height),
+                WaylandEvent::Maximized(window) => app.maximized(window.into()),
+                WaylandEvent::Focused(window, focused) => app.focused(window.into(), focused),
+                WaylandEvent::CloseRequested(window) => app.close_requested(window.into()),
+                WaylandEvent::ScaleChanged => E::scale_factor_changed(),
+                WaylandEvent::KeyDown(window, key, scancode) => app.key_down(window.into(), key, scancode),
+                WaylandEvent::KeyUp(window, key, scancode) => app.key_up(window.into(), key, scancode),
+                WaylandEvent::Character(window, c) => app.received_character(window.into(), c),
+                WaylandEvent::CursorEntered(window) => app.cursor_entered(window.into()),
+                WaylandEvent::CursorLeft(window) => app.cursor_left(window.into()),
+                WaylandEvent::CursorMoved(window, x, y) => app.cursor_moved(window.into(), x, y),
+                WaylandEvent::MouseButtonDown(window, button) => app.mouse_button_down(window.into(), button),
+                WaylandEvent::MouseButtonRelease(window, button) => app.mouse_button_release(window.into(), button),
+                WaylandEvent::MouseWheel(window, x, y) => app.mouse_wheel(window.into(), x, y),
+                WaylandEvent::Touch(window, touch, count) => app.touch(window.into(), touch, count),
+                WaylandEvent::TouchEnd(window) => app.touch_end(window.into()),
+            }
+        }
+    }
+}
+
+/// The epoll that waits for the Wayland connection and the gamepads.
+impl<E: Event> AsRawFd for EventHandler<E> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.epoll
+    }
+}
+
+impl<E: Event> AsFd for EventHandler<E> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.epoll) }
+    }
+}
+
+impl<E: Event> Destroy for EventHandler<E> {
+    fn destroy(&mut self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+
+        self.gamepads.destroy()?;
+        Timers::reset();
+        Requests::reset();
+        unsafe { close(self.epoll) };
+
+        // Dropping the last proxies and the connection closes the socket.
+        *c = ThreadContext::new();
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EventHandlerBuilder {
+    pub gamepad_mappings: String,
+    pub app_name: String,
+    pub app_class: String,
+}
+
+impl EventHandlerBuilder {
+    /// Wayland has a single app id, `class` is used when it's set since it names the `.desktop` file.
+    pub fn with_app_id(&mut self, name: &str, class: &str) -> &mut Self {
+        self.app_name = name.to_string();
+        self.app_class = class.to_string();
+        self
+    }
+
+    pub fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        self.gamepad_mappings.push_str(mappings);
+        self.gamepad_mappings.push('\n');
+        self
+    }
+
+    pub(crate) unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, crate::Error> {
+        if ThreadContext::current_thread().connection.is_some() {
+            return Err(crate::Error::EventHandlerExists);
+        }
+
+        let connection = Connection::connect_to_env().map_err(|e| crate::Error::DisplayUnavailable(Some(e.into())))?;
+
+        let (globals, mut queue) =
+            registry_queue_init::<ThreadContext>(&connection).map_err(|e| crate::Error::DisplayUnavailable(Some(e.into())))?;
+        let qh = queue.handle();
+
+        let compositor = globals.bind(&qh, 1..=6, ()).map_err(|_| crate::Error::Unsupported("wl_compositor"))?;
+        let wm_base = globals.bind(&qh, 1..=6, ()).map_err(|_| crate::Error::Unsupported("xdg_wm_base"))?;
+
+        // Fractional scales are useless without a viewport to map the larger buffer to the surface.
+        let viewporter = globals.bind(&qh, 1..=1, ()).ok();
+        let fractional_scale = viewporter.as_ref().and_then(|_| globals.bind(&qh, 1..=1, ()).ok());
+
+        let globals = Globals {
+            compositor,
+            wm_base,
+            shm: globals.bind(&qh, 1..=1, ()).ok(),
+            seat: globals.bind(&qh, 1..=8, ()).ok(),
+            fractional_scale,
+            viewporter,
+            decoration: globals.bind(&qh, 1..=1, ()).ok(),
+            cursor_shape: globals.bind(&qh, 1..=1, ()).ok(),
+            activation: globals.bind(&qh, 1..=1, ()).ok(),
+            alpha_modifier: globals.bind(&qh, 1..=1, ()).ok(),
+        };
+
+        if globals.seat.is_none() {
+            log_warn!("Exposed", "The compositor has no seat, input is disabled.");
+        }
+
+        let epoll = epoll_create1(EPOLL_CLOEXEC);
+        if epoll == -1 {
+            return Err(Error::last_os_error().into());
+        }
+
+        let fd = connection.as_fd().as_raw_fd();
+        let mut connection_event = epoll_event { events: EPOLLIN as _, u64: fd as _ };
+        epoll_ctl(epoll, EPOLL_CTL_ADD, fd, &mut connection_event);
+
+        let mut mappings = GamepadMappings::default();
+        if let Ok(config) = std::env::var("SDL_GAMECONTROLLERCONFIG") {
+            mappings.add(&config);
+        }
+        mappings.add(&self.gamepad_mappings);
+
+        let thread_context = ThreadContext::current_thread();
+
+        thread_context.connection = Some(connection.clone());
+        thread_context.queue_handle = Some(qh);
+        thread_context.globals = Some(globals);
+        thread_context.app_id = app_id(&self.app_name, &self.app_class);
+
+        // Left in the environment, changing it while other threads may read it is unsound.
+        thread_context.activation_token = std::env::var("XDG_ACTIVATION_TOKEN").ok().filter(|t| !t.is_empty());
+
+        // Seat capabilities and the keymap arrive after binding.
+        if let Err(e) = queue.roundtrip(thread_context) {
+            close(epoll);
+            *thread_context = ThreadContext::new();
+            return Err(crate::Error::DisplayUnavailable(Some(e.into())));
+        }
+
+        if let Some(s) = E::create(crate::window::Context::Wayland(Context(thread_context))) {
+            user_data.write(s);
+        } else {
+            Timers::reset();
+            Requests::reset();
+            close(epoll);
+            *thread_context = ThreadContext::new();
+            return Err(crate::Error::CreateFailed);
+        }
+
+        let gamepads = Gamepads::new(epoll, mappings);
+        Timers::watch(epoll);
+        Requests::watch(epoll);
+
+        Ok(EventHandler { connection, queue, user_data, epoll, gamepads })
+    }
+}
+
+/// Prefers the class like desktop environments do with `WM_CLASS`, empty names default to the executable name.
+fn app_id(name: &str, class: &str) -> String {
+    if !class.is_empty() {
+        return class.to_string();
+    }
+    if !name.is_empty() {
+        return name.to_string();
+    }
+
+    std::env::args_os()
+        .next()
+        .and_then(|arg| std::path::Path::new(&arg).file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_default()
+}
@@ -0,0 +1,104 @@
+use std::{
+    os::fd::{AsRawFd, OwnedFd},
+    ptr::null_mut,
+};
+
+use libc::{mmap, munmap, MAP_FAILED, MAP_PRIVATE, PROT_READ};
+use xkbcommon_dl::{
+    xkb_context, xkb_context_flags, xkb_keymap, xkb_keymap_compile_flags, xkb_keymap_format, xkb_state, xkbcommon_handle,
+    xkbcommon_option,
+};
+
+use crate::window::{Key, ScanCode};
+
+/// Keymap and modifier state of the seat keyboard, libxkbcommon is loaded at runtime.
+#[derive(Debug)]
+pub struct XkbKeyboard {
+    pub context: *mut xkb_context,
+    pub keymap: *mut xkb_keymap,
+    pub state: *mut xkb_state,
+}
+
+impl XkbKeyboard {
+    /// Compiles the `xkb_v1` keymap the compositor sent, `None` if libxkbcommon is missing or the keymap is invalid.
+    pub fn from_fd(fd: OwnedFd, size: u32) -> Option<Self> {
+        let xkb = xkbcommon_option()?;
+
+        unsafe {
+            let map = mmap(null_mut(), size as usize, PROT_READ, MAP_PRIVATE, fd.as_raw_fd(), 0);
+            if map == MAP_FAILED {
+                return None;
+            }
+
+            let context = (xkb.xkb_context_new)(xkb_context_flags::XKB_CONTEXT_NO_FLAGS);
+            // The keymap string is nul terminated.
+            let keymap = (xkb.xkb_keymap_new_from_string)(
+                context,
+                map.cast(),
+                xkb_keymap_format::XKB_KEYMAP_FORMAT_TEXT_V1,
+                xkb_keymap_compile_flags::XKB_KEYMAP_COMPILE_NO_FLAGS,
+            );
+            munmap(map, size as usize);
+
+            if keymap.is_null() {
+                (xkb.xkb_context_unref)(context);
+                return None;
+            }
+
+            let state = (xkb.xkb_state_new)(keymap);
+
+            Some(Self { context, keymap, state })
+        }
+    }
+
+    pub fn update_modifiers(&mut self, depressed: u32, latched: u32, locked: u32, group: u32) {
+        unsafe { (xkbcommon_handle().xkb_state_update_mask)(self.state, depressed, latched, locked, 0, 0, group) };
+    }
+
+    /// `key` is the evdev code from `wl_keyboard.key`. Keys are the unshifted keysym like on X11 and scan codes are
+    /// X keycodes, so both backends report the same values.
+    pub fn key(&self, key: u32) -> (Key, ScanCode) {
+        let xkb = xkbcommon_handle();
+        let keycode = key + 8;
+
+        unsafe {
+            let layout = (xkb.xkb_state_key_get_layout)(self.state, keycode);
+
+            let mut syms = std::ptr::null();
+            let count = (xkb.xkb_keymap_key_get_syms_by_level)(self.keymap, keycode, layout, 0, &mut syms);
+            let keysym = if count > 0 { *syms } else { (xkb.xkb_state_key_get_one_sym)(self.state, keycode) };
+
+            (Key(keysym as _), keycode)
+        }
+    }
+
+    /// False for keys like modifiers that the keymap says don't repeat.
+    pub fn repeats(&self, key: u32) -> bool {
+        unsafe { (xkbcommon_handle().xkb_keymap_key_repeats)(self.keymap, key + 8) != 0 }
+    }
+
+    /// Text the key produces with the current modifiers.
+    pub fn text(&self, key: u32) -> String {
+        let xkb = xkbcommon_handle();
+        let mut buffer = [0u8; 64];
+
+        let count = unsafe { (xkb.xkb_state_key_get_utf8)(self.state, key + 8, buffer.as_mut_ptr().cast(), buffer.len()) };
+        if count <= 0 {
+            return String::new();
+        }
+
+        String::from_utf8_lossy(&buffer[..(count as usize).min(buffer.len() - 1)]).into_owned()
+    }
+}
+
+impl Drop for XkbKeyboard {
+    fn drop(&mut self) {
+        let xkb = xkbcommon_handle();
+
+        unsafe {
+            (xkb.xkb_state_unref)(self.state);
+            (xkb.xkb_keymap_unref)(self.keymap);
+            (xkb.xkb_context_unref)(self.context);
+        }
+    }
+}
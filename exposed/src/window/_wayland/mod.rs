@@ -0,0 +1,21 @@
+mod window;
+pub use window::*;
+
+mod context;
+pub use context::*;
+
+mod event_handler;
+pub use event_handler::*;
+
+mod dispatch;
+
+mod keyboard;
+pub use keyboard::*;
+
+mod pixel_surface;
+pub use pixel_surface::*;
+
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
+
+pub struct Android<E: super::Event>(pub std::marker::PhantomData<E>);
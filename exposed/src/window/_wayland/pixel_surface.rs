@@ -0,0 +1,170 @@
+use std::{
+    io::Error,
+    os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd},
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use exposed_macro::cstr;
+use libc::{ftruncate, memfd_create, mmap, munmap, MAP_FAILED, MAP_SHARED, MFD_CLOEXEC, PROT_READ, PROT_WRITE};
+use wayland_client::{
+    protocol::{
+        wl_buffer::{self, WlBuffer},
+        wl_shm::{Format, WlShm},
+    },
+    Connection, Dispatch, QueueHandle,
+};
+
+use crate::{destroy::Destroy, window::Rect};
+
+use super::{flush, queue_handle, ThreadContext, WindowHandle};
+
+/// Pixels are drawn into memory and copied into a `wl_shm` buffer the compositor isn't reading when presenting.
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub window: WindowHandle,
+    pub width: u32,
+    pub height: u32,
+    pub buffer: Vec<u32>,
+    pub shm_buffers: Vec<ShmBuffer>,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        Ok(Self { window, width: 0, height: 0, buffer: Vec::new(), shm_buffers: Vec::new() })
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        let size = self.window.client_size()?;
+        let (width, height) = (size.width.max(0) as u32, size.height.max(0) as u32);
+
+        if (width, height) != (self.width, self.height) {
+            self.destroy_shm_buffers();
+
+            self.width = width;
+            self.height = height;
+            self.buffer.resize((width * height) as usize, 0);
+        }
+
+        Ok(&mut self.buffer)
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.present_with_damage(&[Rect { left: 0, top: 0, right: self.width as _, bottom: self.height as _ }])
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        if self.buffer.is_empty() || damage.is_empty() {
+            return Ok(());
+        }
+
+        let c = unsafe { ThreadContext::current_thread() };
+        let w = c.windows.get(&self.window).ok_or(crate::Error::WindowDestroyed)?;
+
+        let index = match self.shm_buffers.iter().position(|b| !b.busy.load(Ordering::Acquire)) {
+            Some(index) => index,
+            None => {
+                let shm = c.globals.as_ref().and_then(|g| g.shm.as_ref()).ok_or(crate::Error::Unsupported("wl_shm"))?;
+                self.shm_buffers.push(ShmBuffer::new(shm, queue_handle(c)?, self.width, self.height)?);
+                self.shm_buffers.len() - 1
+            }
+        };
+
+        // The whole frame is copied since the free buffer may hold an older one.
+        let shm_buffer = &self.shm_buffers[index];
+        unsafe { std::ptr::copy_nonoverlapping(self.buffer.as_ptr(), shm_buffer.data, self.buffer.len()) };
+        shm_buffer.busy.store(true, Ordering::Release);
+
+        w.surface.attach(Some(&shm_buffer.buffer), 0, 0);
+        for rect in damage {
+            let left = rect.left.clamp(0, self.width as _);
+            let top = rect.top.clamp(0, self.height as _);
+            let right = rect.right.clamp(left, self.width as _);
+            let bottom = rect.bottom.clamp(top, self.height as _);
+
+            w.surface.damage_buffer(left, top, right - left, bottom - top);
+        }
+        w.surface.commit();
+
+        flush(c)
+    }
+
+    fn destroy_shm_buffers(&mut self) {
+        for mut shm_buffer in self.shm_buffers.drain(..) {
+            let _ = shm_buffer.destroy();
+        }
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        self.destroy_shm_buffers();
+        Ok(())
+    }
+}
+
+/// `Xrgb8888` buffer in its own shared memory file, the compositor sets `busy` back when it releases the buffer.
+#[derive(Debug)]
+pub struct ShmBuffer {
+    pub buffer: WlBuffer,
+    pub busy: Arc<AtomicBool>,
+    pub data: *mut u32,
+    pub size: usize,
+}
+
+impl ShmBuffer {
+    pub fn new(shm: &WlShm, qh: &QueueHandle<ThreadContext>, width: u32, height: u32) -> Result<Self, Error> {
+        let size = width as usize * height as usize * 4;
+
+        unsafe {
+            let fd = memfd_create(cstr!("exposed-pixels"), MFD_CLOEXEC);
+            if fd == -1 {
+                return Err(Error::last_os_error());
+            }
+            let fd = OwnedFd::from_raw_fd(fd);
+
+            if ftruncate(fd.as_raw_fd(), size as _) == -1 {
+                return Err(Error::last_os_error());
+            }
+
+            let data = mmap(null_mut(), size, PROT_READ | PROT_WRITE, MAP_SHARED, fd.as_raw_fd(), 0);
+            if data == MAP_FAILED {
+                return Err(Error::last_os_error());
+            }
+
+            // The pool is only needed to create the buffer, the memory stays alive until the buffer is destroyed.
+            let pool = shm.create_pool(fd.as_fd(), size as _, qh, ());
+            let busy = Arc::new(AtomicBool::new(false));
+            let buffer = pool.create_buffer(0, width as _, height as _, width as i32 * 4, Format::Xrgb8888, qh, busy.clone());
+            pool.destroy();
+
+            Ok(Self { buffer, busy, data: data.cast(), size })
+        }
+    }
+}
+
+impl Destroy for ShmBuffer {
+    fn destroy(&mut self) -> Result<(), Error> {
+        self.buffer.destroy();
+
+        if unsafe { munmap(self.data.cast(), self.size) } == -1 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
+
+impl Dispatch<WlBuffer, Arc<AtomicBool>> for ThreadContext {
+    fn event(
+        _state: &mut Self, _proxy: &WlBuffer, event: wl_buffer::Event, busy: &Arc<AtomicBool>, _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+    ) {
+        if let wl_buffer::Event::Release = event {
+            busy.store(false, Ordering::Release);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::ptr::NonNull;
+
+use wayland_client::Proxy;
+
+use super::{Context, ThreadContext, WindowHandle};
+
+/// Pointers of the `wl_display` and the `wl_surface` in libwayland-client, null if the window is destroyed.
+unsafe fn pointers(c: &ThreadContext, window: Option<WindowHandle>) -> (*mut std::ffi::c_void, *mut std::ffi::c_void) {
+    let display = c.connection.as_ref().map_or(std::ptr::null_mut(), |c| c.backend().display_ptr().cast());
+    let surface = window.and_then(|w| c.windows.get(&w)).map_or(std::ptr::null_mut(), |w| w.surface.id().as_ptr().cast());
+    (display, surface)
+}
+
+#[cfg(feature = "rwh_05")]
+impl WindowHandle {
+    pub fn raw_window_handle_05(self) -> rwh_05::RawWindowHandle {
+        let mut handle = rwh_05::WaylandWindowHandle::empty();
+        handle.surface = unsafe { pointers(ThreadContext::current_thread(), Some(self)) }.1;
+        rwh_05::RawWindowHandle::Wayland(handle)
+    }
+
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        Context(unsafe { ThreadContext::current_thread() }).raw_display_handle_05()
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+impl Context {
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        let mut handle = rwh_05::WaylandDisplayHandle::empty();
+        handle.display = unsafe { pointers(&*self.0, None) }.0;
+        rwh_05::RawDisplayHandle::Wayland(handle)
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl WindowHandle {
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        let surface = unsafe { pointers(ThreadContext::current_thread(), Some(self)) }.1;
+        let surface = NonNull::new(surface).ok_or(rwh_06::HandleError::Unavailable)?;
+        Ok(rwh_06::WaylandWindowHandle::new(surface).into())
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Context(unsafe { ThreadContext::current_thread() }).raw_display_handle_06()
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        let display = unsafe { pointers(&*self.0, None) }.0;
+        let display = NonNull::new(display).ok_or(rwh_06::HandleError::Unavailable)?;
+        Ok(rwh_06::WaylandDisplayHandle::new(display).into())
+    }
+}
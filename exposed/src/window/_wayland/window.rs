@@ -0,0 +1,480 @@
+use std::io::Error;
+
+use unsafe_utilities::to_ref::ToReference;
+
+use wayland_client::Proxy;
+use wayland_protocols::xdg::{
+    decoration::zv1::client::zxdg_toplevel_decoration_v1::Mode,
+    shell::client::{
+        xdg_positioner::{Anchor, ConstraintAdjustment, Gravity},
+        xdg_toplevel,
+    },
+};
+
+use crate::{
+    destroy::Destroy,
+    window::{Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
+};
+
+use super::{Context, ThreadContext, WaylandEvent, WindowState};
+
+/// `wl_surface` protocol id and generation, the surface and its roles are kept in `ThreadContext::windows`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowHandle(pub u32, pub u32);
+
+impl WindowHandle {
+    /// Wayland maps the window when the first buffer is committed after the compositor configures it.
+    pub fn show(self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        let w = self.state()?;
+
+        w.surface.commit();
+
+        let activation = c.globals.as_ref().and_then(|g| g.activation.as_ref());
+        if let (Some(activation), Some(token)) = (activation, c.activation_token.take()) {
+            activation.activate(token, &w.surface);
+        }
+
+        flush(c)
+    }
+
+    pub fn update(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    pub fn redraw(self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        c.events.push_back(WaylandEvent::Render(self));
+        Ok(())
+    }
+
+    pub fn window_title(self) -> Result<String, Error> {
+        Ok(self.state()?.title.clone())
+    }
+
+    pub fn set_window_title(self, title: &str) -> Result<(), Error> {
+        let w = self.state()?;
+
+        if let Some(toplevel) = &w.toplevel {
+            toplevel.set_title(title.to_string());
+        }
+        w.title = title.to_string();
+
+        flush(unsafe { ThreadContext::current_thread() })
+    }
+
+    pub fn dpi(self) -> Result<u32, Error> {
+        Ok(96 * self.state()?.scale / 120)
+    }
+
+    pub fn set_capture(self) {
+        // Wayland grabs the pointer implicitly while a button is held.
+    }
+
+    pub fn release_capture(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Size in buffer pixels, the same size `Event::resized` reports.
+    pub fn client_size(self) -> Result<Size, Error> {
+        let (width, height) = self.state()?.physical_size();
+        Ok(Size { width, height })
+    }
+
+    pub fn client_rect(self) -> Result<Rect, Error> {
+        let (width, height) = self.state()?.physical_size();
+        Ok(Rect { left: 0, top: 0, right: width, bottom: height })
+    }
+
+    /// Wayland doesn't tell clients where their windows are.
+    pub fn window_rect(self) -> Result<Rect, Error> {
+        Err(crate::Error::Unsupported("window position on Wayland").into())
+    }
+
+    /// Needs `wp_alpha_modifier_v1`, `opacity` is clamped to `0.0..=1.0`.
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        let w = self.state()?;
+
+        if w.alpha_modifier.is_none() {
+            let manager = c
+                .globals
+                .as_ref()
+                .and_then(|g| g.alpha_modifier.as_ref())
+                .ok_or(crate::Error::Unsupported("wp_alpha_modifier_v1"))?;
+            w.alpha_modifier = Some(manager.get_surface(&w.surface, queue_handle(c)?, ()));
+        }
+
+        if let Some(alpha_modifier) = &w.alpha_modifier {
+            alpha_modifier.set_multiplier((opacity.clamp(0.0, 1.0) as f64 * u32::MAX as f64) as u32);
+        }
+        w.surface.commit();
+
+        flush(c)
+    }
+
+    /// Wayland clients can't read back what the compositor shows.
+    pub fn capture(self) -> Result<Image, Error> {
+        Err(crate::Error::Unsupported("capturing windows on Wayland").into())
+    }
+
+    /// Needs `zxdg_decoration_manager_v1`, compositors without it expect clients to draw their own decorations.
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        let decoration = self.state()?.decoration.as_ref().ok_or(crate::Error::Unsupported("zxdg_decoration_manager_v1"))?;
+        decoration.set_mode(if decorations { Mode::ServerSide } else { Mode::ClientSide });
+
+        flush(unsafe { ThreadContext::current_thread() })
+    }
+
+    /// Must be called while a button or touch is held.
+    pub fn drag_move(self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        let toplevel = self.state()?.toplevel.as_ref().ok_or(crate::Error::Unsupported("xdg_toplevel"))?;
+        let seat = c.globals.as_ref().and_then(|g| g.seat.as_ref()).ok_or(crate::Error::Unsupported("wl_seat"))?;
+
+        toplevel._move(seat, c.seat.input_serial);
+
+        flush(c)
+    }
+
+    /// Must be called while a button or touch is held.
+    pub fn drag_resize(self, edge: ResizeEdge) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+        let toplevel = self.state()?.toplevel.as_ref().ok_or(crate::Error::Unsupported("xdg_toplevel"))?;
+        let seat = c.globals.as_ref().and_then(|g| g.seat.as_ref()).ok_or(crate::Error::Unsupported("wl_seat"))?;
+
+        let edge = match edge {
+            ResizeEdge::TopLeft => xdg_toplevel::ResizeEdge::TopLeft,
+            ResizeEdge::Top => xdg_toplevel::ResizeEdge::Top,
+            ResizeEdge::TopRight => xdg_toplevel::ResizeEdge::TopRight,
+            ResizeEdge::Right => xdg_toplevel::ResizeEdge::Right,
+            ResizeEdge::BottomRight => xdg_toplevel::ResizeEdge::BottomRight,
+            ResizeEdge::Bottom => xdg_toplevel::ResizeEdge::Bottom,
+            ResizeEdge::BottomLeft => xdg_toplevel::ResizeEdge::BottomLeft,
+            ResizeEdge::Left => xdg_toplevel::ResizeEdge::Left,
+        };
+
+        toplevel.resize(seat, c.seat.input_serial, edge);
+
+        flush(c)
+    }
+
+    /// Wayland leaves stacking to the compositor.
+    pub fn set_always_on_top(self, _always_on_top: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("always on top windows on Wayland").into())
+    }
+
+    /// Wayland leaves the taskbar to the compositor.
+    pub fn set_skip_taskbar(self, _skip_taskbar: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("hiding from the taskbar on Wayland").into())
+    }
+
+    /// Needs `xdg_activation_v1`. Compositors either focus the window or mark it as demanding attention, both levels
+    /// of `UserAttention` behave the same.
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        if attention.is_none() {
+            return Ok(());
+        }
+
+        let c = unsafe { ThreadContext::current_thread() };
+        let w = self.state()?;
+        let activation =
+            c.globals.as_ref().and_then(|g| g.activation.as_ref()).ok_or(crate::Error::Unsupported("xdg_activation_v1"))?;
+
+        // The window is activated with the token once the compositor sends it.
+        let token = activation.get_activation_token(queue_handle(c)?, self);
+        token.set_surface(&w.surface);
+        if !c.app_id.is_empty() {
+            token.set_app_id(c.app_id.clone());
+        }
+        token.commit();
+
+        flush(c)
+    }
+
+    /// Wayland leaves workspaces to the compositor.
+    pub fn set_visible_on_all_workspaces(self, _visible: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("workspaces on Wayland").into())
+    }
+
+    /// Wayland leaves workspaces to the compositor.
+    pub fn move_to_workspace(self, _workspace: u32) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("workspaces on Wayland").into())
+    }
+
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        self.state().map(|_| ())
+    }
+
+    fn state(self) -> Result<&'static mut WindowState, Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+
+        if c.connection.is_none() {
+            return Err(crate::Error::WrongThread.into());
+        }
+
+        c.windows.get_mut(&self).ok_or_else(|| crate::Error::WindowDestroyed.into())
+    }
+}
+
+pub(super) fn flush(c: &ThreadContext) -> Result<(), Error> {
+    match &c.connection {
+        Some(connection) => connection.flush().map_err(Error::other),
+        None => Ok(()),
+    }
+}
+
+pub(super) fn queue_handle(c: &ThreadContext) -> Result<&wayland_client::QueueHandle<ThreadContext>, Error> {
+    // Only the thread of the event handler has the connection.
+    c.queue_handle.as_ref().ok_or_else(|| crate::Error::WrongThread.into())
+}
+
+impl Into<crate::window::WindowHandle> for WindowHandle {
+    fn into(self) -> crate::window::WindowHandle {
+        crate::window::platform::WindowHandle::Wayland(self).into()
+    }
+}
+
+impl Destroy for WindowHandle {
+    fn destroy(&mut self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+
+        let w = match c.windows.remove(self) {
+            Some(w) => w,
+            None => return Ok(()),
+        };
+        c.generations.remove(&self.0);
+
+        if c.seat.keyboard_focus == Some(*self) {
+            c.seat.keyboard_focus = None;
+            c.seat.stop_repeat();
+        }
+        if c.seat.pointer_focus == Some(*self) {
+            c.seat.pointer_focus = None;
+        }
+        c.seat.touches.retain(|_, (window, _)| window != self);
+
+        // Role objects go before the surface.
+        if let Some(alpha_modifier) = w.alpha_modifier {
+            alpha_modifier.destroy();
+        }
+        if let Some(viewport) = w.viewport {
+            viewport.destroy();
+        }
+        if let Some(fractional_scale) = w.fractional_scale {
+            fractional_scale.destroy();
+        }
+        if let Some(decoration) = w.decoration {
+            decoration.destroy();
+        }
+        if let Some(toplevel) = w.toplevel {
+            toplevel.destroy();
+        }
+        if let Some(popup) = w.popup {
+            popup.destroy();
+        }
+        w.xdg_surface.destroy();
+        w.surface.destroy();
+
+        flush(c)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowBuilder {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub title: String,
+    pub transparent: bool,
+    pub kind: WindowKind,
+    pub owner: Option<WindowHandle>,
+    pub anchor: Option<Rect>,
+    pub decorations: bool,
+}
+
+impl Default for WindowBuilder {
+    fn default() -> Self {
+        Self {
+            x: Default::default(),
+            y: Default::default(),
+            width: 480,
+            height: 170,
+            title: String::new(),
+            transparent: false,
+            kind: WindowKind::Normal,
+            owner: None,
+            anchor: None,
+            decorations: true,
+        }
+    }
+}
+
+impl WindowBuilder {
+    pub fn with_title(&mut self, title: &str) -> &mut Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Size in logical pixels, Wayland scales it by the output scale.
+    pub fn with_size(&mut self, width: i32, height: i32) -> &mut Self {
+        self.width = width as _;
+        self.height = height as _;
+        self
+    }
+
+    /// Surfaces are blended with their alpha channel, `PixelSurface` is always opaque.
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Popup kinds need an owner, without one they are created as toplevels.
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.decorations = decorations;
+        self
+    }
+
+    /// Ignored, see `WindowHandle::set_always_on_top`.
+    pub fn with_always_on_top(&mut self, _always_on_top: bool) -> &mut Self {
+        self
+    }
+
+    /// Ignored, see `WindowHandle::set_skip_taskbar`.
+    pub fn with_skip_taskbar(&mut self, _skip_taskbar: bool) -> &mut Self {
+        self
+    }
+
+    /// Ignored, Wayland activates windows when they are shown.
+    pub fn with_user_attention(&mut self, _attention: Option<UserAttention>) -> &mut Self {
+        self
+    }
+
+    /// Ignored, see `WindowHandle::set_visible_on_all_workspaces`.
+    pub fn with_visible_on_all_workspaces(&mut self, _visible: bool) -> &mut Self {
+        self
+    }
+
+    /// Ignored, see `WindowHandle::move_to_workspace`.
+    pub fn with_workspace(&mut self, _workspace: u32) -> &mut Self {
+        self
+    }
+
+    /// Ignored, the compositor sends every event.
+    pub fn with_events(&mut self, _events: EventMask) -> &mut Self {
+        self
+    }
+
+    /// The window has no role until it's shown, so it can still be configured before the compositor sees it.
+    pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
+        let c = unsafe { context.0.to_ref() };
+        let globals = c.globals.clone().ok_or(crate::Error::WrongThread)?;
+        let qh = &queue_handle(c)?.clone();
+
+        let surface = globals.compositor.create_surface(qh, ());
+        let window = c.register_window(surface.id().protocol_id());
+
+        let xdg_surface = globals.wm_base.get_xdg_surface(&surface, qh, window);
+
+        let mut state = WindowState {
+            surface,
+            xdg_surface,
+            toplevel: None,
+            popup: None,
+            decoration: None,
+            fractional_scale: None,
+            viewport: None,
+            alpha_modifier: None,
+            title: self.title.clone(),
+            width: self.width as _,
+            height: self.height as _,
+            pending_size: (0, 0),
+            scale: 120,
+            maximized: false,
+            configured: false,
+        };
+
+        if let (Some(manager), Some(viewporter)) = (&globals.fractional_scale, &globals.viewporter) {
+            state.fractional_scale = Some(manager.get_fractional_scale(&state.surface, qh, window));
+            state.viewport = Some(viewporter.get_viewport(&state.surface, qh, ()));
+        }
+
+        match self.owner.and_then(|owner| c.windows.get(&owner)) {
+            Some(parent) if self.kind.is_popup() => {
+                let positioner = globals.wm_base.create_positioner(qh, ());
+                positioner.set_size(self.width.max(1) as _, self.height.max(1) as _);
+
+                // Surface coordinates of the parent are logical.
+                let anchor = self.anchor.unwrap_or(Rect { left: self.x, top: self.y, right: self.x, bottom: self.y });
+                let to_logical = |v: i32| (v as i64 * 120 / parent.scale as i64) as i32;
+                let (left, top) = (to_logical(anchor.left), to_logical(anchor.top));
+                let (right, bottom) = (to_logical(anchor.right), to_logical(anchor.bottom));
+                positioner.set_anchor_rect(left, top, (right - left).max(1), (bottom - top).max(1));
+
+                // Same placement as `popup_position`, below the anchor and flipped above it when there is no room.
+                positioner.set_anchor(Anchor::BottomLeft);
+                positioner.set_gravity(Gravity::BottomRight);
+                positioner.set_constraint_adjustment(
+                    ConstraintAdjustment::FlipY | ConstraintAdjustment::SlideX | ConstraintAdjustment::SlideY,
+                );
+
+                let popup = state.xdg_surface.get_popup(Some(&parent.xdg_surface), &positioner, qh, window);
+                positioner.destroy();
+
+                // Menus close when clicking elsewhere, which needs the serial of the click that opened them.
+                let seat = globals.seat.as_ref();
+                if let (Some(seat), true) = (seat, self.kind != WindowKind::Tooltip && c.seat.input_serial != 0) {
+                    popup.grab(seat, c.seat.input_serial);
+                }
+
+                state.popup = Some(popup);
+            }
+
+            parent => {
+                let toplevel = state.xdg_surface.get_toplevel(qh, window);
+
+                if !self.title.is_empty() {
+                    toplevel.set_title(self.title.clone());
+                }
+                if !c.app_id.is_empty() {
+                    toplevel.set_app_id(c.app_id.clone());
+                }
+                if let Some(parent) = parent.and_then(|p| p.toplevel.as_ref()) {
+                    toplevel.set_parent(Some(parent));
+                }
+
+                if let Some(manager) = &globals.decoration {
+                    let decoration = manager.get_toplevel_decoration(&toplevel, qh, ());
+                    decoration.set_mode(if self.decorations { Mode::ServerSide } else { Mode::ClientSide });
+                    state.decoration = Some(decoration);
+                }
+
+                state.toplevel = Some(toplevel);
+            }
+        }
+
+        c.windows.insert(window, state);
+
+        flush(c)?;
+
+        Ok(window)
+    }
+}
@@ -0,0 +1,55 @@
+use super::xlib::XInternAtoms;
+use std::ffi::c_char;
+
+use x11::xlib::{Atom, Display, False};
+
+macro_rules! atoms {
+    ($($name:ident),* $(,)?) => {
+        /// Atoms interned once per connection.
+        #[allow(non_snake_case)]
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct Atoms {
+            $(pub $name: Atom,)*
+        }
+
+        impl Atoms {
+            /// # Safety
+            /// `display` must be an open connection.
+            pub unsafe fn intern(display: *mut Display) -> Self {
+                let mut names = [$(concat!(stringify!($name), "\0").as_ptr() as *mut c_char,)*];
+                let mut atoms = [0; [$(stringify!($name),)*].len()];
+
+                XInternAtoms(display, names.as_mut_ptr(), names.len() as _, False, atoms.as_mut_ptr());
+
+                let mut atoms = atoms.into_iter();
+                Self { $($name: atoms.next().unwrap_or(0),)* }
+            }
+        }
+    };
+}
+
+atoms!(
+    _NET_WM_WINDOW_OPACITY,
+    _NET_WM_WINDOW_TYPE,
+    _NET_WM_WINDOW_TYPE_NORMAL,
+    _NET_WM_WINDOW_TYPE_DIALOG,
+    _NET_WM_WINDOW_TYPE_UTILITY,
+    _NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+    _NET_WM_WINDOW_TYPE_POPUP_MENU,
+    _NET_WM_WINDOW_TYPE_TOOLTIP,
+    _MOTIF_WM_HINTS,
+    _NET_WM_MOVERESIZE,
+    _NET_WM_STATE,
+    _NET_WM_STATE_ABOVE,
+    _NET_WM_STATE_SKIP_TASKBAR,
+    _NET_WM_STATE_STICKY,
+    _NET_WM_STATE_DEMANDS_ATTENTION,
+    _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MAXIMIZED_HORZ,
+    _NET_WM_DESKTOP,
+    _NET_WM_PID,
+    _NET_STARTUP_ID,
+    _NET_STARTUP_INFO_BEGIN,
+    _NET_STARTUP_INFO,
+    UTF8_STRING,
+);
@@ -1,11 +1,25 @@
-use std::{cell::UnsafeCell, collections::HashMap, ffi::c_int, ptr::null_mut};
+use std::{
+    cell::UnsafeCell,
+    collections::{HashMap, HashSet},
+    ffi::c_int,
+    ptr::null_mut,
+};
 
 use libc::c_ulong;
-use x11::xlib::{Display, Screen, _XIC, _XIM};
+use x11::xlib::{Display, Screen, XButtonEvent, _XIC, _XIM};
+
+use super::{AppIdentity, Atoms, WindowHandle, XInput};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Context(pub *mut ThreadContext);
 
+impl Context {
+    /// Same as on the `wayland` and `headless` features' contexts, so crates like exposed-gl build with any.
+    pub fn x11(self) -> Option<Context> {
+        Some(self)
+    }
+}
+
 thread_local! {
     static CONTEXT:UnsafeCell<ThreadContext> = UnsafeCell::new(ThreadContext::new());
 }
@@ -19,14 +33,106 @@ pub struct ThreadContext {
     pub im: *mut _XIM,
     pub screen_id: c_int,
     pub window_map: HashMap<c_ulong, *mut _XIC>,
+    /// Generation of every alive window, the server reuses the ids of destroyed windows.
+    pub windows: HashMap<c_ulong, u32>,
+    pub generation: u32,
+    /// Windows the window manager last reported maximized.
+    pub maximized: HashSet<c_ulong>,
+    /// Size each window was last configured with.
+    pub sizes: HashMap<c_ulong, (c_int, c_int)>,
+    pub xinput: Option<XInput>,
+    pub atoms: Atoms,
+    /// Held button that `drag_move` and `drag_resize` hand over to the window manager.
+    pub button_press: Option<XButtonEvent>,
+    pub identity: AppIdentity,
 }
 
 impl ThreadContext {
     pub fn new() -> Self {
-        Self { display: null_mut(), screen: null_mut(), screen_id: 0, wm_delete: 0, im: null_mut(), window_map: HashMap::new() }
+        Self {
+            display: null_mut(),
+            screen: null_mut(),
+            screen_id: 0,
+            wm_delete: 0,
+            im: null_mut(),
+            window_map: HashMap::new(),
+            windows: HashMap::new(),
+            generation: 0,
+            maximized: HashSet::new(),
+            sizes: HashMap::new(),
+            xinput: None,
+            atoms: Atoms::default(),
+            button_press: None,
+            identity: AppIdentity::default(),
+        }
+    }
+
+    /// Gives `window` a new generation, handles of the previous window with the same id become stale.
+    pub fn register_window(&mut self, window: c_ulong) -> WindowHandle {
+        self.generation = self.generation.wrapping_add(1);
+        self.windows.insert(window, self.generation);
+        WindowHandle(window, self.display, self.generation)
+    }
+
+    /// Remembers the state read from `_NET_WM_STATE`, true only when `window` wasn't maximized before.
+    pub fn set_maximized(&mut self, window: c_ulong, maximized: bool) -> bool {
+        if maximized {
+            self.maximized.insert(window)
+        } else {
+            self.maximized.remove(&window);
+            false
+        }
+    }
+
+    /// Remembers the size of a `ConfigureNotify`, true if it differs from the previous one.
+    pub fn set_size(&mut self, window: c_ulong, width: c_int, height: c_int) -> bool {
+        self.sizes.insert(window, (width, height)) != Some((width, height))
+    }
+
+    /// Handle of `window` if it's alive.
+    pub fn window_handle(&self, window: c_ulong) -> Option<WindowHandle> {
+        self.windows.get(&window).map(|generation| WindowHandle(window, self.display, *generation))
     }
 
     pub unsafe fn current_thread() -> &'static mut ThreadContext {
         CONTEXT.with(|c| &mut *c.get())
     }
 }
+
+#[test]
+fn window_generation_test() {
+    let c = unsafe { ThreadContext::current_thread() };
+
+    let old = c.register_window(7);
+    assert!(old.alive().is_ok());
+
+    // The server reuses the id of a destroyed window.
+    c.windows.remove(&7);
+    let new = c.register_window(7);
+
+    assert_eq!(c.window_handle(7), Some(new));
+    assert!(new.alive().is_ok());
+    assert!(matches!(crate::Error::from(old.alive().unwrap_err()), crate::Error::WindowDestroyed));
+}
+
+#[test]
+fn maximized_transition_test() {
+    let mut c = ThreadContext::new();
+
+    // The window manager rewrites `_NET_WM_STATE` for focus and other states too.
+    assert!(c.set_maximized(7, true));
+    assert!(!c.set_maximized(7, true));
+    assert!(!c.set_maximized(7, false));
+    assert!(!c.set_maximized(7, false));
+    assert!(c.set_maximized(7, true));
+}
+
+#[test]
+fn size_change_test() {
+    let mut c = ThreadContext::new();
+
+    // Moving or restacking a window configures it with the same size.
+    assert!(c.set_size(7, 640, 480));
+    assert!(!c.set_size(7, 640, 480));
+    assert!(c.set_size(7, 640, 481));
+}
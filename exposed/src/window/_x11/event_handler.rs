@@ -1,25 +1,36 @@
 use std::{
     ffi::{c_int, c_long},
-    io::{Error, ErrorKind},
+    io::Error,
     mem::zeroed,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
     ptr::{null, null_mut},
     sync::atomic::{AtomicUsize, Ordering},
 };
 
 use exposed_macro::{cstr, log_warn};
-use libc::{c_ulong, setlocale, LC_ALL};
+use libc::{
+    c_ulong, close, epoll_create1, epoll_ctl, epoll_event, epoll_wait, setlocale, EPOLLIN, EPOLL_CLOEXEC, EPOLL_CTL_ADD, LC_ALL,
+};
 use x11::xlib::{
-    self, Display, Expose, Screen, True, XCheckIfEvent, XCheckTypedEvent, XCloseDisplay, XCloseIM, XConvertCase, XDefaultScreen,
-    XDefaultScreenOfDisplay, XEvent, XInternAtom, XKeysymToKeycode, XLookupBoth, XLookupChars, XLookupKeySym, XNextEvent,
-    XOpenDisplay, XOpenIM, XSetLocaleModifiers, XSupportsLocale, Xutf8LookupString, _XDisplay, _XIM,
+    self, _XDisplay, Display, Expose, Screen, True, VisibilityFullyObscured, XEvent, XLookupBoth, XLookupChars, XLookupKeySym,
+    _XIM,
 };
 
+use super::xlib::{
+    load_xlib, XCheckIfEvent, XCheckTypedEvent, XCloseDisplay, XCloseIM, XConnectionNumber, XConvertCase, XDefaultScreen,
+    XDefaultScreenOfDisplay, XFreeEventData, XGetEventData, XInternAtom, XKeysymToKeycode, XNextEvent, XOpenDisplay, XOpenIM,
+    XPending, XRootWindowOfScreen, XSetLocaleModifiers, XSupportsLocale, XTranslateCoordinates, Xutf8LookupString,
+};
+use super::{Context, ThreadContext};
 use crate::{
     destroy::Destroy,
-    window::{platform::ThreadContext, Context, Event, Key, MouseButton},
+    window::{Backend, Event, GamepadEvent, GamepadMappings, GamepadUpdate, Gamepads, Key, MouseButton, Requests, Timers},
 };
 
-use super::WindowHandle;
+use super::{
+    AppIdentity, Atoms, XIGesturePinchEvent, XIGestureSwipeEvent, XInput, XI_GESTURE_PINCH_BEGIN, XI_GESTURE_PINCH_END,
+    XI_GESTURE_PINCH_UPDATE, XI_GESTURE_SWIPE_BEGIN, XI_GESTURE_SWIPE_END, XI_GESTURE_SWIPE_UPDATE,
+};
 
 #[derive(Debug)]
 pub struct EventHandler<E: Event> {
@@ -30,48 +41,136 @@ pub struct EventHandler<E: Event> {
     pub wm_delete: c_ulong,
     pub user_data: *mut E,
     pub event: XEvent,
+    pub epoll: c_int,
+    pub gamepads: Gamepads,
 }
 
+/// `XEvent::type_` when `EventHandler::event` holds no X event, X never sends type 0.
+const NO_EVENT: c_int = 0;
+
 impl<E: Event> EventHandler<E> {
     pub fn poll(&mut self) -> i32 {
+        self.epoll_wait(0);
+
         unsafe {
             extern "C" fn predicate(_display: *mut _XDisplay, _event: *mut XEvent, _arg: *mut i8) -> i32 {
                 1
             }
 
-            XCheckIfEvent(self.display, &mut self.event, Some(predicate), null_mut())
+            if XCheckIfEvent(self.display, &mut self.event, Some(predicate), null_mut()) != 0 {
+                return 1;
+            }
         }
+
+        if self.gamepads.has_events() || Timers::has_events() || Requests::has_events() {
+            self.event.type_ = NO_EVENT;
+            return 1;
+        }
+
+        0
     }
 
     pub fn wait(&mut self) -> i32 {
-        unsafe { XNextEvent(self.display, &mut self.event) }
+        unsafe {
+            while XPending(self.display) == 0 {
+                if self.gamepads.has_events() || Timers::has_events() || Requests::has_events() {
+                    self.event.type_ = NO_EVENT;
+                    return 1;
+                }
+
+                self.epoll_wait(-1);
+            }
+
+            XNextEvent(self.display, &mut self.event)
+        }
+    }
+
+    /// Reads every ready source other than the X connection.
+    fn epoll_wait(&mut self, timeout: c_int) {
+        let mut events: [epoll_event; 16] = unsafe { zeroed() };
+
+        let count = unsafe { epoll_wait(self.epoll, events.as_mut_ptr(), events.len() as _, timeout) };
+
+        for event in events.iter().take(count.max(0) as usize) {
+            if !Timers::ready(event.u64 as c_int) && !Requests::ready(event.u64 as c_int) {
+                self.gamepads.ready(event.u64 as c_int);
+            }
+        }
     }
 
     pub fn dispatch(&mut self) {
+        self.dispatch_x_event();
+        self.dispatch_gamepads();
+        self.dispatch_timers();
+        Requests::run();
+    }
+
+    fn dispatch_timers(&mut self) {
+        let app = unsafe { &mut *self.user_data };
+
+        while let Some(timer) = Timers::pop() {
+            app.timer_fired(timer);
+        }
+    }
+
+    fn dispatch_gamepads(&mut self) {
+        let app = unsafe { &mut *self.user_data };
+
+        while let Some((gamepad, update)) = self.gamepads.pop() {
+            match update {
+                GamepadUpdate::Connected(name) => app.gamepad_connected(gamepad, name),
+                GamepadUpdate::Disconnected => app.gamepad_disconnected(gamepad),
+                GamepadUpdate::Event(GamepadEvent::Button(button, true)) => app.gamepad_button_down(gamepad, button),
+                GamepadUpdate::Event(GamepadEvent::Button(button, false)) => app.gamepad_button_up(gamepad, button),
+                GamepadUpdate::Event(GamepadEvent::Axis(axis, value)) => app.axis_motion(gamepad, axis, value),
+            }
+        }
+    }
+
+    fn dispatch_x_event(&mut self) {
         unsafe {
             let app = &mut *self.user_data;
             let event = &mut self.event;
 
+            // Events of destroyed windows are dropped, their ids may belong to new windows.
+            let handle =
+                |window| ThreadContext::current_thread().window_handle(window).map(Into::<crate::window::WindowHandle>::into);
+
             match event.type_ {
                 xlib::Expose => {
                     if XCheckTypedEvent(self.display, Expose, event) != 0 {}
 
-                    app.low_render(WindowHandle(event.expose.window, self.display).into());
+                    let Some(window) = handle(event.expose.window) else { return };
+                    app.low_render(window);
                 }
 
                 xlib::ConfigureNotify => {
-                    let window = WindowHandle(event.expose.window, self.display).into();
-                    app.resized(window, event.configure.width, event.configure.height);
-                    app.moved(window, event.configure.x, event.configure.y);
+                    let configure = event.configure;
+                    let Some(window) = handle(configure.window) else { return };
+
+                    // Real events are relative to the parent, the frame of reparenting window managers.
+                    // Synthetic ones from the window manager are in root coordinates already.
+                    let (mut x, mut y) = (configure.x, configure.y);
+                    if configure.send_event == 0 {
+                        let root = XRootWindowOfScreen(self.screen);
+                        let mut child = 0;
+                        XTranslateCoordinates(self.display, configure.window, root, 0, 0, &mut x, &mut y, &mut child);
+                    }
+
+                    // Moves and restacking configure the window too.
+                    if ThreadContext::current_thread().set_size(configure.window, configure.width, configure.height) {
+                        app.resized(window, configure.width, configure.height);
+                    }
+                    app.moved(window, x, y);
                 }
 
                 xlib::FocusIn => {
-                    let window = WindowHandle(event.focus_change.window, self.display).into();
+                    let Some(window) = handle(event.focus_change.window) else { return };
                     app.focused(window, true);
                 }
 
                 xlib::FocusOut => {
-                    let window = WindowHandle(event.focus_change.window, self.display).into();
+                    let Some(window) = handle(event.focus_change.window) else { return };
                     app.focused(window, false);
                 }
 
@@ -89,7 +188,7 @@ impl<E: Event> EventHandler<E> {
                     let count =
                         Xutf8LookupString(ic, &mut event.key, key_event_buffer.as_mut_ptr().cast(), 24, &mut keysym, &mut status);
 
-                    let window = WindowHandle(event.key.window, self.display).into();
+                    let Some(window) = handle(event.key.window) else { return };
 
                     if status == XLookupBoth || status == XLookupKeySym {
                         let mut lower = 0;
@@ -125,7 +224,7 @@ impl<E: Event> EventHandler<E> {
                     let _count =
                         Xutf8LookupString(ic, &mut event.key, key_event_buffer.as_mut_ptr().cast(), 24, &mut keysym, &mut status);
 
-                    let window = WindowHandle(event.key.window, self.display).into();
+                    let Some(window) = handle(event.key.window) else { return };
 
                     if status == XLookupBoth || status == XLookupKeySym {
                         let mut lower = 0;
@@ -137,46 +236,109 @@ impl<E: Event> EventHandler<E> {
                 }
 
                 xlib::ButtonPress => {
-                    let window = WindowHandle(event.button.window, self.display).into();
+                    ThreadContext::current_thread().button_press = Some(event.button);
+
+                    let Some(window) = handle(event.button.window) else { return };
                     app.mouse_button_down(window, MouseButton(event.button.button));
                 }
 
                 xlib::ButtonRelease => {
-                    let window = WindowHandle(event.button.window, self.display).into();
+                    ThreadContext::current_thread().button_press = None;
+
+                    let Some(window) = handle(event.button.window) else { return };
                     app.mouse_button_release(window, MouseButton(event.button.button));
                 }
 
                 xlib::MotionNotify => {
-                    let window = WindowHandle(event.motion.window, self.display).into();
+                    let Some(window) = handle(event.motion.window) else { return };
                     app.cursor_moved(window, event.motion.x_root, event.motion.y_root)
                 }
 
                 xlib::EnterNotify => {
-                    let window = WindowHandle(event.crossing.window, self.display).into();
+                    let Some(window) = handle(event.crossing.window) else { return };
                     app.cursor_entered(window)
                 }
 
                 xlib::LeaveNotify => {
-                    let window = WindowHandle(event.crossing.window, self.display).into();
+                    let Some(window) = handle(event.crossing.window) else { return };
                     app.cursor_left(window)
                 }
 
                 xlib::MapNotify => {
-                    // TODO
+                    let Some(window) = handle(event.map.window) else { return };
+                    app.show(window);
                 }
 
+                // Window managers unmap windows when they are iconified.
                 xlib::UnmapNotify => {
-                    // TODO
+                    let Some(window) = handle(event.unmap.window) else { return };
+                    app.minimized(window);
+                }
+
+                xlib::PropertyNotify => {
+                    let c = ThreadContext::current_thread();
+                    if event.property.atom != c.atoms._NET_WM_STATE {
+                        return;
+                    }
+
+                    let Some(window) = c.window_handle(event.property.window) else { return };
+                    if c.set_maximized(event.property.window, window.is_maximized()) {
+                        app.maximized(window.into());
+                    }
                 }
 
                 xlib::VisibilityNotify => {
-                    // TODO
+                    let Some(window) = handle(event.visibility.window) else { return };
+                    app.occluded(window, event.visibility.state == VisibilityFullyObscured);
+                }
+
+                xlib::GenericEvent => {
+                    let xinput = match ThreadContext::current_thread().xinput {
+                        Some(xinput) => xinput,
+                        None => return,
+                    };
+
+                    let cookie = &mut event.generic_event_cookie;
+
+                    if cookie.extension != xinput.opcode || XGetEventData(self.display, cookie) == 0 {
+                        return;
+                    }
+
+                    match cookie.evtype {
+                        XI_GESTURE_PINCH_BEGIN | XI_GESTURE_PINCH_UPDATE | XI_GESTURE_PINCH_END => {
+                            let pinch = &*(cookie.data as *const XIGesturePinchEvent);
+
+                            if let Some(window) = handle(pinch.event) {
+                                match cookie.evtype {
+                                    XI_GESTURE_PINCH_BEGIN => app.gesture_begin(window, pinch.gesture()),
+                                    XI_GESTURE_PINCH_UPDATE => app.gesture_update(window, pinch.gesture()),
+                                    _ => app.gesture_end(window, pinch.gesture()),
+                                }
+                            }
+                        }
+
+                        XI_GESTURE_SWIPE_BEGIN | XI_GESTURE_SWIPE_UPDATE | XI_GESTURE_SWIPE_END => {
+                            let swipe = &*(cookie.data as *const XIGestureSwipeEvent);
+
+                            if let Some(window) = handle(swipe.event) {
+                                match cookie.evtype {
+                                    XI_GESTURE_SWIPE_BEGIN => app.gesture_begin(window, swipe.gesture()),
+                                    XI_GESTURE_SWIPE_UPDATE => app.gesture_update(window, swipe.gesture()),
+                                    _ => app.gesture_end(window, swipe.gesture()),
+                                }
+                            }
+                        }
+
+                        _ => {}
+                    }
+
+                    XFreeEventData(self.display, cookie);
                 }
 
                 xlib::ClientMessage => {
                     if event.client_message.format == 32 {
                         if *event.client_message.data.as_longs().get_unchecked(0) == self.wm_delete as c_long {
-                            let window = WindowHandle(event.focus_change.window, self.display).into();
+                            let Some(window) = handle(event.focus_change.window) else { return };
                             app.close_requested(window);
                         }
                     }
@@ -188,52 +350,100 @@ impl<E: Event> EventHandler<E> {
     }
 }
 
-impl<E: Event> Into<crate::window::EventHandler<E>> for EventHandler<E> {
-    fn into(self) -> crate::window::EventHandler<E> {
-        crate::window::EventHandler(self)
+/// The epoll that waits for the X connection and the gamepads.
+impl<E: Event> AsRawFd for EventHandler<E> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.epoll
+    }
+}
+
+impl<E: Event> AsFd for EventHandler<E> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.epoll) }
     }
 }
 
 impl<E: Event> Destroy for EventHandler<E> {
     fn destroy(&mut self) -> Result<(), Error> {
         unsafe {
+            if let Some(xinput) = ThreadContext::current_thread().xinput.take() {
+                xinput.unload();
+            }
+
+            self.gamepads.destroy()?;
+            Timers::reset();
+            Requests::reset();
+            close(self.epoll);
+
             XCloseIM(self.im);
             XCloseDisplay(self.display);
+
+            // The windows died with the display, their handles must not find them anymore.
+            *ThreadContext::current_thread() = ThreadContext::new();
             Ok(())
         }
     }
 }
 
 #[derive(Debug, Default)]
-pub struct EventHandlerBuilder {}
+pub struct EventHandlerBuilder {
+    pub gamepad_mappings: String,
+    pub app_name: String,
+    pub app_class: String,
+    pub backend: Option<Backend>,
+}
 
 impl EventHandlerBuilder {
-    pub unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, Error> {
+    pub fn with_backend(&mut self, backend: Backend) -> &mut Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn with_app_id(&mut self, name: &str, class: &str) -> &mut Self {
+        self.app_name = name.to_string();
+        self.app_class = class.to_string();
+        self
+    }
+
+    pub fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        self.gamepad_mappings.push_str(mappings);
+        self.gamepad_mappings.push('\n');
+        self
+    }
+
+    pub(crate) unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, crate::Error> {
+        #[cfg(not(feature = "wayland"))]
+        if let Some(Backend::Wayland) = self.backend.or_else(Backend::from_env) {
+            return Err(crate::Error::Unsupported("the Wayland backend without the `wayland` feature"));
+        }
+
+        if !ThreadContext::current_thread().display.is_null() {
+            return Err(crate::Error::EventHandlerExists);
+        }
+
+        load_xlib()?;
+
         static mut ONCE: AtomicUsize = AtomicUsize::new(0);
 
         if ONCE.fetch_add(1, Ordering::SeqCst) == 0 {
-            if setlocale(LC_ALL, cstr!("")).is_null() {
-                return Err(ErrorKind::Other.into());
-            }
-
-            if XSupportsLocale() == 0 {
-                return Err(ErrorKind::Other.into());
+            if setlocale(LC_ALL, cstr!("")).is_null() || XSupportsLocale() == 0 {
+                return Err(crate::Error::Unsupported("the locale of the environment"));
             }
 
             if XSetLocaleModifiers(cstr!("@im=none")).is_null() {
-                return Err(ErrorKind::Other.into());
+                return Err(crate::Error::BackendError { function: "XSetLocaleModifiers", code: 0 });
             }
         }
 
         let display = XOpenDisplay(null());
         if display.is_null() {
-            return Err(ErrorKind::Other.into());
+            return Err(crate::Error::DisplayUnavailable(Some("XOpenDisplay failed, check DISPLAY.".into())));
         }
 
         let screen = XDefaultScreenOfDisplay(display);
         if screen.is_null() {
             XCloseDisplay(display);
-            return Err(ErrorKind::Other.into());
+            return Err(crate::Error::BackendError { function: "XDefaultScreenOfDisplay", code: 0 });
         }
 
         let screen_id = XDefaultScreen(display);
@@ -241,11 +451,37 @@ impl EventHandlerBuilder {
         let im = XOpenIM(display, null_mut(), null_mut(), null_mut());
         if im.is_null() {
             XCloseDisplay(display);
-            return Err(ErrorKind::Other.into());
+            return Err(crate::Error::BackendError { function: "XOpenIM", code: 0 });
         }
 
         let wm_delete = XInternAtom(display, cstr!("WM_DELETE_WINDOW"), True);
 
+        let xinput = XInput::load(display);
+        if xinput.is_none() {
+            log_warn!("Exposed", "XInput 2.4 is not available, touchpad gestures are disabled.");
+        }
+
+        let epoll = epoll_create1(EPOLL_CLOEXEC);
+        if epoll == -1 {
+            let error = Error::last_os_error();
+            if let Some(xinput) = xinput {
+                xinput.unload();
+            }
+            XCloseIM(im);
+            XCloseDisplay(display);
+            return Err(error.into());
+        }
+
+        let connection = XConnectionNumber(display);
+        let mut connection_event = epoll_event { events: EPOLLIN as _, u64: connection as _ };
+        epoll_ctl(epoll, EPOLL_CTL_ADD, connection, &mut connection_event);
+
+        let mut mappings = GamepadMappings::default();
+        if let Ok(config) = std::env::var("SDL_GAMECONTROLLERCONFIG") {
+            mappings.add(&config);
+        }
+        mappings.add(&self.gamepad_mappings);
+
         let thread_context = ThreadContext::current_thread();
 
         thread_context.display = display;
@@ -253,16 +489,36 @@ impl EventHandlerBuilder {
         thread_context.screen_id = screen_id;
         thread_context.wm_delete = wm_delete;
         thread_context.im = im;
+        thread_context.xinput = xinput;
+        thread_context.atoms = Atoms::intern(display);
+        thread_context.identity = AppIdentity::new(&self.app_name, &self.app_class);
 
-        if let Some(s) = E::create(Context(thread_context)) {
+        #[cfg(not(feature = "wayland"))]
+        let context = Context(thread_context);
+        #[cfg(feature = "wayland")]
+        let context = crate::window::Context::X11(Context(thread_context));
+
+        if let Some(s) = E::create(context) {
             user_data.write(s);
         } else {
+            if let Some(xinput) = thread_context.xinput.take() {
+                xinput.unload();
+            }
+            Timers::reset();
+            Requests::reset();
+            close(epoll);
             XCloseIM(im);
             XCloseDisplay(display);
-            return Err(ErrorKind::Other.into());
+            *thread_context = ThreadContext::new();
+            return Err(crate::Error::CreateFailed);
         }
 
-        let event_handler = EventHandler { user_data, wm_delete, screen, screen_id, im, display, event: zeroed() };
+        let gamepads = Gamepads::new(epoll, mappings);
+        Timers::watch(epoll);
+        Requests::watch(epoll);
+
+        let event_handler =
+            EventHandler { user_data, wm_delete, screen, screen_id, im, display, event: zeroed(), epoll, gamepads };
 
         Ok(event_handler)
     }
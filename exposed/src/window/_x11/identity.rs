@@ -0,0 +1,140 @@
+use std::ffi::{c_ulong, CString};
+
+use libc::{gethostname, getpid};
+use x11::xlib::{
+    self, ClientMessage, Display, False, PropModeReplace, PropertyChangeMask, XClassHint, XEvent, XA_CARDINAL, XA_STRING,
+    XA_WM_CLIENT_MACHINE,
+};
+
+use super::xlib::{XChangeProperty, XSendEvent, XSetClassHint};
+use super::Atoms;
+
+/// Lets desktop environments group windows, match them to `.desktop` files and finish launch feedback.
+#[derive(Debug, Default, Clone)]
+pub struct AppIdentity {
+    /// `res_name` of `WM_CLASS`.
+    pub name: CString,
+    /// `res_class` of `WM_CLASS`, desktop environments match it with `StartupWMClass` or the `.desktop` file name.
+    pub class: CString,
+    /// Read from `DESKTOP_STARTUP_ID`, cleared once the first window is shown.
+    /// The variable stays set, remove it before spawning children that must not complete the startup of this process.
+    pub startup_id: Option<String>,
+}
+
+impl AppIdentity {
+    /// Empty `name` and `class` default to the executable name, the class is capitalized like Xt does.
+    pub fn new(name: &str, class: &str) -> Self {
+        let executable = std::env::args_os()
+            .next()
+            .and_then(|arg| std::path::Path::new(&arg).file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "exposed".to_string());
+
+        let name = if name.is_empty() { executable.clone() } else { name.to_string() };
+        let class = if class.is_empty() {
+            let mut chars = executable.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default()
+        } else {
+            class.to_string()
+        };
+
+        // Left in the environment, changing it while other threads may read it is unsound.
+        let startup_id = std::env::var("DESKTOP_STARTUP_ID").ok().filter(|id| !id.is_empty());
+
+        let to_cstring = |s: String| CString::new(s.replace('\0', "")).unwrap_or_default();
+
+        Self { name: to_cstring(name), class: to_cstring(class), startup_id }
+    }
+
+    /// Sets `WM_CLASS`, `_NET_WM_PID`, `WM_CLIENT_MACHINE` and `_NET_STARTUP_ID`.
+    ///
+    /// # Safety
+    /// `window` must belong to `display`.
+    pub unsafe fn set_window_properties(&self, display: *mut Display, atoms: &Atoms, window: xlib::Window) {
+        let mut class_hint = XClassHint { res_name: self.name.as_ptr() as _, res_class: self.class.as_ptr() as _ };
+        XSetClassHint(display, window, &mut class_hint);
+
+        let pid = getpid() as c_ulong;
+        XChangeProperty(display, window, atoms._NET_WM_PID, XA_CARDINAL, 32, PropModeReplace, (&pid as *const c_ulong).cast(), 1);
+
+        // _NET_WM_PID is only meaningful with the machine it belongs to.
+        let mut host = [0u8; 256];
+        if gethostname(host.as_mut_ptr().cast(), host.len()) == 0 {
+            let len = host.iter().position(|b| *b == 0).unwrap_or(host.len());
+            XChangeProperty(display, window, XA_WM_CLIENT_MACHINE, XA_STRING, 8, PropModeReplace, host.as_ptr(), len as _);
+        }
+
+        if let Some(id) = &self.startup_id {
+            let atom = atoms._NET_STARTUP_ID;
+            XChangeProperty(display, window, atom, atoms.UTF8_STRING, 8, PropModeReplace, id.as_ptr(), id.len() as _);
+        }
+    }
+
+    /// Tells the launcher that startup is complete, only the first call does anything.
+    ///
+    /// # Safety
+    /// `window` must belong to `display` and `root` must be its root window.
+    pub unsafe fn complete_startup(&mut self, display: *mut Display, atoms: &Atoms, root: xlib::Window, window: xlib::Window) {
+        let id = match self.startup_id.take() {
+            Some(id) => id,
+            None => return,
+        };
+
+        for (i, chunk) in startup_messages(&format!("remove: ID={}", quote(&id))).iter().enumerate() {
+            let mut e: XEvent = std::mem::zeroed();
+            e.client_message.type_ = ClientMessage;
+            e.client_message.window = window;
+            e.client_message.message_type = if i == 0 { atoms._NET_STARTUP_INFO_BEGIN } else { atoms._NET_STARTUP_INFO };
+            e.client_message.format = 8;
+            for (j, byte) in chunk.iter().enumerate() {
+                e.client_message.data.set_byte(j, *byte as _);
+            }
+
+            XSendEvent(display, root, False, PropertyChangeMask, &mut e);
+        }
+    }
+}
+
+/// Quotes a startup notification value, escaping quotes and backslashes.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Splits a nul terminated startup notification message into the 20 bytes that fit in a client message.
+pub fn startup_messages(message: &str) -> Vec<[u8; 20]> {
+    let mut bytes = message.as_bytes().to_vec();
+    bytes.push(0);
+
+    bytes
+        .chunks(20)
+        .map(|chunk| {
+            let mut data = [0; 20];
+            data[..chunk.len()].copy_from_slice(chunk);
+            data
+        })
+        .collect()
+}
+
+#[test]
+fn startup_messages_test() {
+    let message = format!("remove: ID={}", quote("launcher/app \"1\"_TIME1234"));
+    assert_eq!(message, r#"remove: ID="launcher/app \"1\"_TIME1234""#);
+
+    let chunks = startup_messages(&message);
+    assert_eq!(chunks.len(), 3);
+
+    let joined: Vec<u8> = chunks.concat();
+    assert_eq!(&joined[..message.len()], message.as_bytes());
+    assert!(joined[message.len()..].iter().all(|b| *b == 0));
+
+    assert_eq!(startup_messages("0123456789012345678").len(), 1);
+    assert_eq!(startup_messages("01234567890123456789").len(), 2);
+}
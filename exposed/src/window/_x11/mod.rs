@@ -4,7 +4,27 @@ pub use window::*;
 mod context;
 pub use context::*;
 
+// Headless builds keep the X11 types for crates like exposed-gl, only the event handler needs the platform.
+#[cfg(not(feature = "headless"))]
 mod event_handler;
+#[cfg(not(feature = "headless"))]
 pub use event_handler::*;
 
+mod xinput;
+pub use xinput::*;
+
+mod atoms;
+pub use atoms::*;
+
+mod pixel_surface;
+pub use pixel_surface::*;
+
+mod identity;
+pub use identity::*;
+
+pub mod xlib;
+
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
+
 pub struct Android<E: super::Event>(pub std::marker::PhantomData<E>);
@@ -0,0 +1,372 @@
+use std::{
+    ffi::{c_char, c_int, c_uint, c_void},
+    io::Error,
+    mem::{transmute, zeroed},
+    ptr::null_mut,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use exposed_macro::cstr;
+use libc::{dlclose, dlopen, dlsym, shmat, shmctl, shmdt, shmget, IPC_CREAT, IPC_PRIVATE, IPC_RMID, RTLD_LAZY, RTLD_LOCAL};
+use x11::{
+    xlib::{Bool, Display, Drawable, False, LSBFirst, Visual, XErrorEvent, XImage, ZPixmap, GC},
+    xshm::XShmSegmentInfo,
+};
+
+use super::xlib::{XCreateGC, XFlush, XFree, XFreeGC, XGetWindowAttributes, XInitImage, XPutImage, XSetErrorHandler, XSync};
+use crate::{destroy::Destroy, window::Rect};
+
+use super::WindowHandle;
+
+type XShmQueryExtension = unsafe extern "C" fn(*mut Display) -> Bool;
+type XShmAttach = unsafe extern "C" fn(*mut Display, *mut XShmSegmentInfo) -> Bool;
+type XShmCreateImage = unsafe extern "C" fn(
+    *mut Display,
+    *mut Visual,
+    c_uint,
+    c_int,
+    *mut c_char,
+    *mut XShmSegmentInfo,
+    c_uint,
+    c_uint,
+) -> *mut XImage;
+type XShmPutImage =
+    unsafe extern "C" fn(*mut Display, Drawable, GC, *mut XImage, c_int, c_int, c_int, c_int, c_uint, c_uint, Bool) -> Bool;
+
+/// `libXext` loaded at runtime. Exists only if the server supports MIT-SHM.
+#[derive(Debug, Clone, Copy)]
+pub struct XShm {
+    pub lib: *mut c_void,
+    pub attach: XShmAttach,
+    pub detach: XShmAttach,
+    pub create_image: XShmCreateImage,
+    pub put_image: XShmPutImage,
+}
+
+impl XShm {
+    /// # Safety
+    /// `display` must be an open connection.
+    pub unsafe fn load(display: *mut Display) -> Option<Self> {
+        let mut lib = null_mut();
+        for path in [cstr!("libXext.so.6"), cstr!("libXext.so")] {
+            lib = dlopen(path, RTLD_LAZY | RTLD_LOCAL);
+            if !lib.is_null() {
+                break;
+            }
+        }
+
+        if lib.is_null() {
+            return None;
+        }
+
+        let symbols = [
+            dlsym(lib, cstr!("XShmQueryExtension")),
+            dlsym(lib, cstr!("XShmAttach")),
+            dlsym(lib, cstr!("XShmDetach")),
+            dlsym(lib, cstr!("XShmCreateImage")),
+            dlsym(lib, cstr!("XShmPutImage")),
+        ];
+
+        if symbols.iter().any(|s| s.is_null()) {
+            dlclose(lib);
+            return None;
+        }
+
+        if transmute::<*mut c_void, XShmQueryExtension>(symbols[0])(display) == 0 {
+            dlclose(lib);
+            return None;
+        }
+
+        Some(Self {
+            lib,
+            attach: transmute::<*mut c_void, XShmAttach>(symbols[1]),
+            detach: transmute::<*mut c_void, XShmAttach>(symbols[2]),
+            create_image: transmute::<*mut c_void, XShmCreateImage>(symbols[3]),
+            put_image: transmute::<*mut c_void, XShmPutImage>(symbols[4]),
+        })
+    }
+
+    /// # Safety
+    /// No function of this instance can be used afterwards.
+    pub unsafe fn unload(self) {
+        dlclose(self.lib);
+    }
+}
+
+static ATTACH_FAILED: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "C" fn attach_error_handler(_display: *mut Display, _event: *mut XErrorEvent) -> c_int {
+    ATTACH_FAILED.store(true, Ordering::Relaxed);
+    0
+}
+
+/// Shared memory image, the server reads the pixels without copying them through the socket.
+#[derive(Debug)]
+pub struct ShmImage {
+    pub image: *mut XImage,
+    /// Boxed because the image points to it.
+    pub info: Box<XShmSegmentInfo>,
+}
+
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub window: WindowHandle,
+    pub gc: GC,
+    pub visual: *mut Visual,
+    pub depth: c_int,
+    pub width: u32,
+    pub height: u32,
+    pub shm: Option<XShm>,
+    pub shm_image: Option<ShmImage>,
+    /// Pixels when MIT-SHM isn't available.
+    pub buffer: Vec<u32>,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        window.alive()?;
+
+        unsafe {
+            let mut attr = zeroed();
+            XGetWindowAttributes(window.1, window.0, &mut attr);
+
+            if attr.depth != 24 && attr.depth != 32 {
+                return Err(Error::other(format!("Windows with {}-bit depth are not supported.", attr.depth)));
+            }
+
+            let gc = XCreateGC(window.1, window.0, 0, null_mut());
+
+            Ok(Self {
+                window,
+                gc,
+                visual: attr.visual,
+                depth: attr.depth,
+                width: 0,
+                height: 0,
+                shm: XShm::load(window.1),
+                shm_image: None,
+                buffer: Vec::new(),
+            })
+        }
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        let size = self.window.client_size()?;
+        let (width, height) = (size.width.max(0) as u32, size.height.max(0) as u32);
+
+        if (width, height) != (self.width, self.height) {
+            self.resize(width, height);
+        }
+
+        let len = (self.width * self.height) as usize;
+
+        Ok(match &self.shm_image {
+            Some(shm_image) => unsafe { std::slice::from_raw_parts_mut((*shm_image.image).data.cast(), len) },
+            None => &mut self.buffer[..len],
+        })
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.present_with_damage(&[Rect { left: 0, top: 0, right: self.width as _, bottom: self.height as _ }])
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        unsafe {
+            let mut local_image = if self.shm_image.is_none() { Some(self.local_image()) } else { None };
+
+            for rect in damage {
+                let left = rect.left.clamp(0, self.width as _);
+                let top = rect.top.clamp(0, self.height as _);
+                let right = rect.right.clamp(left, self.width as _);
+                let bottom = rect.bottom.clamp(top, self.height as _);
+
+                if left == right || top == bottom {
+                    continue;
+                }
+
+                let (w, h) = ((right - left) as c_uint, (bottom - top) as c_uint);
+
+                match (&self.shm, &self.shm_image, &mut local_image) {
+                    (Some(shm), Some(shm_image), _) => {
+                        (shm.put_image)(
+                            self.window.1,
+                            self.window.0,
+                            self.gc,
+                            shm_image.image,
+                            left,
+                            top,
+                            left,
+                            top,
+                            w,
+                            h,
+                            False,
+                        );
+                    }
+                    (_, _, Some(image)) => {
+                        XPutImage(self.window.1, self.window.0, self.gc, image, left, top, left, top, w, h);
+                    }
+                    _ => {}
+                }
+            }
+
+            if self.shm_image.is_some() {
+                // The server must be done reading before the buffer is written again.
+                XSync(self.window.1, False);
+            } else {
+                XFlush(self.window.1);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wraps `buffer` without copying, the image isn't owned by Xlib so it's never destroyed.
+    unsafe fn local_image(&mut self) -> XImage {
+        let mut image: XImage = zeroed();
+        image.width = self.width as _;
+        image.height = self.height as _;
+        image.format = ZPixmap;
+        image.data = self.buffer.as_mut_ptr().cast();
+        image.byte_order = LSBFirst;
+        image.bitmap_unit = 32;
+        image.bitmap_bit_order = LSBFirst;
+        image.bitmap_pad = 32;
+        image.depth = self.depth;
+        image.bytes_per_line = (self.width * 4) as _;
+        image.bits_per_pixel = 32;
+
+        let visual = &*self.visual;
+        image.red_mask = visual.red_mask;
+        image.green_mask = visual.green_mask;
+        image.blue_mask = visual.blue_mask;
+
+        XInitImage(&mut image);
+
+        image
+    }
+
+    fn resize(&mut self, width: u32, height: u32) {
+        unsafe { self.destroy_shm_image() };
+
+        self.width = width;
+        self.height = height;
+
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        if self.shm.is_some() {
+            self.shm_image = unsafe { self.create_shm_image() };
+
+            if self.shm_image.is_some() {
+                self.buffer = Vec::new();
+                return;
+            }
+
+            // Remote connections can't attach shared memory.
+            if let Some(shm) = self.shm.take() {
+                unsafe { shm.unload() };
+            }
+        }
+
+        self.buffer.resize((width * height) as usize, 0);
+    }
+
+    unsafe fn create_shm_image(&mut self) -> Option<ShmImage> {
+        let shm = self.shm?;
+
+        let mut info: Box<XShmSegmentInfo> = Box::new(zeroed());
+        let image = (shm.create_image)(
+            self.window.1,
+            self.visual,
+            self.depth as _,
+            ZPixmap,
+            null_mut(),
+            &mut *info,
+            self.width,
+            self.height,
+        );
+
+        if image.is_null() {
+            return None;
+        }
+
+        let size = (*image).bytes_per_line as usize * self.height as usize;
+
+        info.shmid = shmget(IPC_PRIVATE, size, IPC_CREAT | 0o600);
+        if info.shmid == -1 {
+            XFree(image.cast());
+            return None;
+        }
+
+        info.shmaddr = shmat(info.shmid, null_mut(), 0).cast();
+
+        if info.shmaddr as isize == -1 {
+            shmctl(info.shmid, IPC_RMID, null_mut());
+            XFree(image.cast());
+            return None;
+        }
+
+        (*image).data = info.shmaddr;
+
+        ATTACH_FAILED.store(false, Ordering::Relaxed);
+        let previous = XSetErrorHandler(Some(attach_error_handler));
+
+        (shm.attach)(self.window.1, &mut *info);
+        XSync(self.window.1, False);
+
+        XSetErrorHandler(previous);
+
+        // The segment is freed once both sides detach.
+        shmctl(info.shmid, IPC_RMID, null_mut());
+
+        if ATTACH_FAILED.load(Ordering::Relaxed) {
+            shmdt(info.shmaddr.cast());
+            XFree(image.cast());
+            return None;
+        }
+
+        let shm_image = ShmImage { image, info };
+
+        // The buffer is handed out without a stride.
+        if (*image).bytes_per_line as u32 != self.width * 4 {
+            self.shm_image = Some(shm_image);
+            self.destroy_shm_image();
+            return None;
+        }
+
+        Some(shm_image)
+    }
+
+    unsafe fn destroy_shm_image(&mut self) {
+        let Some(mut shm_image) = self.shm_image.take() else {
+            return;
+        };
+
+        if let Some(shm) = &self.shm {
+            (shm.detach)(self.window.1, &mut *shm_image.info);
+            XSync(self.window.1, False);
+        }
+
+        shmdt(shm_image.info.shmaddr.cast());
+
+        // XDestroyImage would free the shared memory and the segment info.
+        XFree(shm_image.image.cast());
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        unsafe {
+            self.destroy_shm_image();
+
+            if let Some(shm) = self.shm.take() {
+                shm.unload();
+            }
+
+            XFreeGC(self.window.1, self.gc);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use super::{Context, ThreadContext, WindowHandle};
+
+/// Handles are empty or `HandleError::Unavailable` for destroyed windows and windows of another thread, the ids of
+/// destroyed windows are reused by the server.
+#[cfg(feature = "rwh_05")]
+impl WindowHandle {
+    pub fn raw_window_handle_05(self) -> rwh_05::RawWindowHandle {
+        let mut handle = rwh_05::XlibWindowHandle::empty();
+        if self.alive().is_ok() {
+            handle.window = self.0;
+        }
+        rwh_05::RawWindowHandle::Xlib(handle)
+    }
+
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        let mut handle = rwh_05::XlibDisplayHandle::empty();
+        if self.alive().is_ok() {
+            handle.display = self.1.cast();
+            handle.screen = unsafe { ThreadContext::current_thread() }.screen_id;
+        }
+        rwh_05::RawDisplayHandle::Xlib(handle)
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+impl Context {
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        let c = unsafe { &*self.0 };
+
+        let mut handle = rwh_05::XlibDisplayHandle::empty();
+        handle.display = c.display.cast();
+        handle.screen = c.screen_id;
+        rwh_05::RawDisplayHandle::Xlib(handle)
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl WindowHandle {
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        self.alive().map_err(|_| rwh_06::HandleError::Unavailable)?;
+        Ok(rwh_06::XlibWindowHandle::new(self.0).into())
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        self.alive().map_err(|_| rwh_06::HandleError::Unavailable)?;
+        let screen = unsafe { ThreadContext::current_thread() }.screen_id;
+        Ok(rwh_06::XlibDisplayHandle::new(std::ptr::NonNull::new(self.1.cast()), screen).into())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        let c = unsafe { &*self.0 };
+        Ok(rwh_06::XlibDisplayHandle::new(std::ptr::NonNull::new(c.display.cast()), c.screen_id).into())
+    }
+}
@@ -1,46 +1,74 @@
 use std::{
-    io::{Error, ErrorKind},
+    ffi::{c_int, c_long, c_ulong},
+    io::Error,
     mem::zeroed,
+    ptr::null_mut,
 };
 
+use exposed_macro::log_warn;
+
 use unsafe_utilities::to_ref::ToReference;
 
 use x11::xlib::{
-    self, ButtonMotionMask, ButtonPressMask, ButtonReleaseMask, EnterWindowMask, Expose, ExposureMask, FocusChangeMask,
-    KeyPressMask, KeyReleaseMask, LeaveWindowMask, PointerMotionMask, ResizeRedirectMask, XBlackPixel, XClearWindow, XCreateIC,
-    XCreateSimpleWindow, XDestroyIC, XDestroyWindow, XEvent, XGetWindowAttributes, XIMPreeditNothing, XIMStatusNothing,
-    XMapWindow, XNClientWindow_0, XNInputStyle_0, XRootWindowOfScreen, XSelectInput, XSendEvent, XSetICFocus, XSetWMProtocols,
-    XStoreName, XWhitePixel,
+    self, AllocNone, Atom, ButtonMotionMask, ButtonPressMask, ButtonReleaseMask, CWBackPixel, CWBorderPixel, CWColormap,
+    CWOverrideRedirect, CWSaveUnder, ClientMessage, CopyFromParent, CurrentTime, EnterWindowMask, Expose, ExposureMask, False,
+    FocusChangeMask, InputOutput, IsUnmapped, IsViewable, KeyPressMask, KeyReleaseMask, LeaveWindowMask, PointerMotionMask,
+    PropModeReplace, PropertyChangeMask, StructureNotifyMask, SubstructureNotifyMask, SubstructureRedirectMask, Success, True,
+    TrueColor, VisibilityChangeMask, XEvent, XIMPreeditNothing, XIMStatusNothing, XNClientWindow_0, XNInputStyle_0,
+    XSetWindowAttributes, XUrgencyHint, XWindowAttributes, ZPixmap, XA_ATOM, XA_CARDINAL,
 };
 
+use super::xlib::{
+    XAllocWMHints, XBlackPixel, XChangeProperty, XClearWindow, XCreateColormap, XCreateWindow, XDefaultColormap, XDeleteProperty,
+    XDestroyIC, XDestroyImage, XDestroyWindow, XFlush, XFree, XFreeColormap, XGetImage, XGetPixel, XGetWMHints,
+    XGetWindowAttributes, XGetWindowProperty, XHeightOfScreen, XMapWindow, XMatchVisualInfo, XRootWindowOfScreen, XSelectInput,
+    XSendEvent, XSetICFocus, XSetTransientForHint, XSetWMHints, XSetWMProtocols, XStoreName, XTranslateCoordinates,
+    XUngrabPointer, XWhitePixel, XWidthOfScreen,
+};
 use crate::{
     destroy::Destroy,
-    window::{Event, Rect, Size},
+    window::{mask_channel, popup_position, Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
 };
 
 use super::{Context, ThreadContext};
 
+/// The window, its display and its generation, methods fail with `Error::WindowDestroyed` once it's destroyed
+/// and with `Error::WrongThread` on other threads.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct WindowHandle(pub xlib::Window, pub *mut xlib::Display);
+pub struct WindowHandle(pub xlib::Window, pub *mut xlib::Display, pub u32);
 
 impl WindowHandle {
     pub fn show(self) -> Result<(), Error> {
-        unsafe { XMapWindow(ThreadContext::current_thread().display, self.0) };
+        self.alive()?;
+
+        unsafe {
+            let c = ThreadContext::current_thread();
+
+            XMapWindow(c.display, self.0);
+
+            let root = XRootWindowOfScreen(c.screen);
+            c.identity.complete_startup(c.display, &c.atoms, root, self.0);
+        }
+
         Ok(())
     }
 
     pub fn update(self) -> Result<(), Error> {
+        self.alive()?;
+
         Ok(())
     }
 
     pub fn redraw(self) -> Result<(), Error> {
+        self.alive()?;
+
         unsafe {
             let mut e: XEvent = zeroed();
             e.type_ = Expose;
             e.expose.window = self.0;
             if XSendEvent(self.1, self.0, 0, ExposureMask, &mut e) == 0 {
-                return Err(Error::new(ErrorKind::Other, "Failed at XSendEvent."));
+                return Err(crate::Error::BackendError { function: "XSendEvent", code: 0 }.into());
             }
         }
 
@@ -48,15 +76,21 @@ impl WindowHandle {
     }
 
     pub fn window_title(self) -> Result<String, Error> {
+        self.alive()?;
+
         todo!("https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XFetchName.html")
     }
 
     pub fn set_window_title(self, title: &str) -> Result<(), Error> {
+        self.alive()?;
+
         unsafe { XStoreName(self.1, self.0, format!("{title}\0").as_ptr().cast()) };
         Ok(())
     }
 
     pub fn dpi(self) -> Result<u32, Error> {
+        self.alive()?;
+
         todo!()
     }
 
@@ -65,11 +99,15 @@ impl WindowHandle {
     }
 
     pub fn release_capture(self) -> Result<(), Error> {
+        self.alive()?;
+
         // TODO https://www.x.org/releases/current/doc/man/man3/XSelectInput.3.xhtml
         Ok(())
     }
 
     pub fn client_size(self) -> Result<Size, Error> {
+        self.alive()?;
+
         unsafe {
             let mut attr = zeroed();
             XGetWindowAttributes(self.1, self.0, &mut attr);
@@ -79,30 +117,356 @@ impl WindowHandle {
     }
 
     pub fn client_rect(self) -> Result<Rect, Error> {
+        self.alive()?;
+
         todo!()
     }
 
     pub fn window_rect(self) -> Result<Rect, Error> {
+        self.alive()?;
+
         todo!()
     }
+
+    /// Needs a compositing window manager, `opacity` is clamped to `0.0..=1.0`.
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let atom = ThreadContext::current_thread().atoms._NET_WM_WINDOW_OPACITY;
+
+            if opacity >= 1.0 {
+                XDeleteProperty(self.1, self.0, atom);
+                return Ok(());
+            }
+
+            let value = (opacity.max(0.0) as f64 * u32::MAX as f64) as c_ulong;
+            XChangeProperty(self.1, self.0, atom, XA_CARDINAL, 32, PropModeReplace, (&value as *const c_ulong).cast(), 1);
+        }
+
+        Ok(())
+    }
+
+    pub fn capture(self) -> Result<Image, Error> {
+        self.alive()?;
+
+        unsafe {
+            let mut attr = zeroed::<XWindowAttributes>();
+            XGetWindowAttributes(self.1, self.0, &mut attr);
+
+            // XGetImage fails with BadMatch on windows that aren't viewable.
+            if attr.map_state != IsViewable {
+                return Err(crate::Error::Unsupported("capturing windows that aren't viewable").into());
+            }
+
+            let (width, height) = (attr.width as u32, attr.height as u32);
+
+            let image = XGetImage(self.1, self.0, 0, 0, width, height, !0, ZPixmap);
+            if image.is_null() {
+                return Err(crate::Error::BackendError { function: "XGetImage", code: 0 }.into());
+            }
+
+            let visual = &*attr.visual;
+            let (red, green, blue) = (visual.red_mask as u64, visual.green_mask as u64, visual.blue_mask as u64);
+            let alpha = if attr.depth == 32 { !(red | green | blue) & 0xFFFFFFFF } else { 0 };
+
+            let mut result = Image::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    let pixel = XGetPixel(image, x as _, y as _) as u64;
+                    let a = if alpha == 0 { u8::MAX } else { mask_channel(pixel, alpha) };
+
+                    result.set_pixel(x, y, [mask_channel(pixel, red), mask_channel(pixel, green), mask_channel(pixel, blue), a]);
+                }
+            }
+
+            XDestroyImage(image);
+
+            Ok(result)
+        }
+    }
+
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe { set_motif_decorations(ThreadContext::current_thread(), self.0, decorations) };
+        Ok(())
+    }
+
+    pub fn drag_move(self) -> Result<(), Error> {
+        self.alive()?;
+
+        self.move_resize(_NET_WM_MOVERESIZE_MOVE)
+    }
+
+    pub fn drag_resize(self, edge: ResizeEdge) -> Result<(), Error> {
+        self.alive()?;
+
+        // _NET_WM_MOVERESIZE_SIZE_* directions go clockwise from the top left like `ResizeEdge`.
+        self.move_resize(edge as c_long)
+    }
+
+    pub fn set_always_on_top(self, always_on_top: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe { self.set_wm_state(ThreadContext::current_thread().atoms._NET_WM_STATE_ABOVE, always_on_top) }
+    }
+
+    pub fn set_skip_taskbar(self, skip_taskbar: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe { self.set_wm_state(ThreadContext::current_thread().atoms._NET_WM_STATE_SKIP_TASKBAR, skip_taskbar) }
+    }
+
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let c = ThreadContext::current_thread();
+
+            self.set_wm_state(c.atoms._NET_WM_STATE_DEMANDS_ATTENTION, attention.is_some())?;
+
+            let mut hints = XGetWMHints(self.1, self.0);
+            if hints.is_null() {
+                hints = XAllocWMHints();
+                if hints.is_null() {
+                    return Err(crate::Error::BackendError { function: "XAllocWMHints", code: 0 }.into());
+                }
+            }
+
+            if attention == Some(UserAttention::Critical) {
+                (*hints).flags |= XUrgencyHint;
+            } else {
+                (*hints).flags &= !XUrgencyHint;
+            }
+
+            XSetWMHints(self.1, self.0, hints);
+            XFree(hints.cast());
+        }
+
+        Ok(())
+    }
+
+    pub fn set_visible_on_all_workspaces(self, visible: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let c = ThreadContext::current_thread();
+
+            self.set_wm_state(c.atoms._NET_WM_STATE_STICKY, visible)?;
+
+            if visible {
+                self.set_desktop(ALL_DESKTOPS)
+            } else {
+                let desktop = self.current_desktop().unwrap_or(0);
+                self.set_desktop(if desktop == ALL_DESKTOPS { 0 } else { desktop })
+            }
+        }
+    }
+
+    /// Workspaces are counted from 0.
+    pub fn move_to_workspace(self, workspace: u32) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe { self.set_desktop(workspace as _) }
+    }
+
+    /// Same as on the `wayland` and `headless` features' handles, so crates like exposed-gl build with any.
+    pub fn x11(self) -> Option<WindowHandle> {
+        Some(self)
+    }
+
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        let c = unsafe { ThreadContext::current_thread() };
+
+        // Each thread has its own connection, the window isn't known to the others.
+        if self.1 != c.display {
+            return Err(crate::Error::WrongThread.into());
+        }
+
+        match c.windows.get(&self.0) {
+            Some(generation) if *generation == self.2 => Ok(()),
+            _ => Err(crate::Error::WindowDestroyed.into()),
+        }
+    }
+
+    /// True if the window manager maximized the window both ways.
+    #[cfg(not(feature = "headless"))]
+    pub(super) unsafe fn is_maximized(self) -> bool {
+        let atoms = ThreadContext::current_thread().atoms;
+        let state = self.property::<Atom>(atoms._NET_WM_STATE, XA_ATOM);
+
+        state.contains(&atoms._NET_WM_STATE_MAXIMIZED_VERT) && state.contains(&atoms._NET_WM_STATE_MAXIMIZED_HORZ)
+    }
+
+    fn is_mapped(self) -> bool {
+        unsafe {
+            let mut attr = zeroed::<XWindowAttributes>();
+            XGetWindowAttributes(self.1, self.0, &mut attr);
+            attr.map_state != IsUnmapped
+        }
+    }
+
+    /// Mapped windows must ask the window manager, unmapped ones set the property it reads when mapping.
+    unsafe fn set_wm_state(self, state: Atom, enable: bool) -> Result<(), Error> {
+        let atom = ThreadContext::current_thread().atoms._NET_WM_STATE;
+
+        if self.is_mapped() {
+            let action = if enable { _NET_WM_STATE_ADD } else { _NET_WM_STATE_REMOVE };
+            return self.send_to_window_manager(atom, [action, state as _, 0, 1, 0]);
+        }
+
+        let mut states = self.property::<Atom>(atom, XA_ATOM);
+        states.retain(|s| *s != state);
+        if enable {
+            states.push(state);
+        }
+
+        XChangeProperty(self.1, self.0, atom, XA_ATOM, 32, PropModeReplace, states.as_ptr().cast(), states.len() as _);
+
+        Ok(())
+    }
+
+    unsafe fn set_desktop(self, desktop: c_ulong) -> Result<(), Error> {
+        let atom = ThreadContext::current_thread().atoms._NET_WM_DESKTOP;
+
+        if self.is_mapped() {
+            return self.send_to_window_manager(atom, [desktop as _, 1, 0, 0, 0]);
+        }
+
+        XChangeProperty(self.1, self.0, atom, XA_CARDINAL, 32, PropModeReplace, (&desktop as *const c_ulong).cast(), 1);
+
+        Ok(())
+    }
+
+    unsafe fn current_desktop(self) -> Option<c_ulong> {
+        let atom = ThreadContext::current_thread().atoms._NET_WM_DESKTOP;
+        self.property::<c_ulong>(atom, XA_CARDINAL).first().copied()
+    }
+
+    /// Reads a 32-bit format property, which Xlib returns as an array of longs.
+    unsafe fn property<T: Copy>(self, property: Atom, kind: Atom) -> Vec<T> {
+        let mut actual_type = 0;
+        let mut actual_format = 0;
+        let mut count = 0;
+        let mut bytes_after = 0;
+        let mut data = null_mut();
+
+        let result = XGetWindowProperty(
+            self.1,
+            self.0,
+            property,
+            0,
+            c_long::MAX,
+            False,
+            kind,
+            &mut actual_type,
+            &mut actual_format,
+            &mut count,
+            &mut bytes_after,
+            &mut data,
+        );
+
+        if result != Success as c_int || data.is_null() {
+            return Vec::new();
+        }
+
+        let values =
+            if actual_format == 32 { std::slice::from_raw_parts(data.cast::<T>(), count as usize).to_vec() } else { Vec::new() };
+
+        XFree(data.cast());
+
+        values
+    }
+
+    unsafe fn send_to_window_manager(self, message_type: Atom, data: [c_long; 5]) -> Result<(), Error> {
+        let c = ThreadContext::current_thread();
+
+        let mut e: XEvent = zeroed();
+        e.client_message.type_ = ClientMessage;
+        e.client_message.window = self.0;
+        e.client_message.message_type = message_type;
+        e.client_message.format = 32;
+        for (i, value) in data.into_iter().enumerate() {
+            e.client_message.data.set_long(i, value);
+        }
+
+        let mask = SubstructureRedirectMask | SubstructureNotifyMask;
+        if XSendEvent(self.1, XRootWindowOfScreen(c.screen), False, mask, &mut e) == 0 {
+            return Err(crate::Error::BackendError { function: "XSendEvent", code: 0 }.into());
+        }
+
+        XFlush(self.1);
+
+        Ok(())
+    }
+
+    fn move_resize(self, direction: c_long) -> Result<(), Error> {
+        unsafe {
+            let c = ThreadContext::current_thread();
+
+            let press = match c.button_press.take() {
+                Some(press) => press,
+                None => return Err(crate::Error::Unsupported("dragging without a held mouse button").into()),
+            };
+
+            // The window manager can't grab the pointer while the implicit grab of the press is active.
+            XUngrabPointer(self.1, CurrentTime);
+
+            let atom = c.atoms._NET_WM_MOVERESIZE;
+            self.send_to_window_manager(atom, [press.x_root as _, press.y_root as _, direction, press.button as _, 1])
+        }
+    }
 }
 
+const _NET_WM_MOVERESIZE_MOVE: c_long = 8;
+
+const _NET_WM_STATE_REMOVE: c_long = 0;
+const _NET_WM_STATE_ADD: c_long = 1;
+
+/// `_NET_WM_DESKTOP` of windows on every workspace.
+const ALL_DESKTOPS: c_ulong = 0xFFFFFFFF;
+
+const MWM_HINTS_DECORATIONS: c_ulong = 1 << 1;
+
+unsafe fn set_motif_decorations(c: &ThreadContext, window: xlib::Window, decorations: bool) {
+    // flags, functions, decorations, input_mode, status
+    let hints: [c_ulong; 5] = [MWM_HINTS_DECORATIONS, 0, decorations as _, 0, 0];
+
+    let atom = c.atoms._MOTIF_WM_HINTS;
+    XChangeProperty(c.display, window, atom, atom, 32, PropModeReplace, hints.as_ptr().cast(), hints.len() as _);
+}
+
+#[cfg(feature = "wayland")]
 impl Into<crate::window::WindowHandle> for WindowHandle {
     fn into(self) -> crate::window::WindowHandle {
-        crate::window::WindowHandle(self)
+        crate::window::platform::WindowHandle::X11(self).into()
     }
 }
 
 impl Destroy for WindowHandle {
     fn destroy(&mut self) -> Result<(), std::io::Error> {
+        self.alive()?;
+
         unsafe {
             let c = ThreadContext::current_thread();
+            c.windows.remove(&self.0);
+            c.maximized.remove(&self.0);
+            c.sizes.remove(&self.0);
 
             if let Some(ic) = c.window_map.remove(&self.0) {
                 XDestroyIC(ic);
             }
 
+            let mut attr = zeroed();
+            XGetWindowAttributes(self.1, self.0, &mut attr);
+
             XDestroyWindow(self.1, self.0);
+
+            // Windows with a visual other than the default own their colormap.
+            if attr.colormap != XDefaultColormap(self.1, c.screen_id) {
+                XFreeColormap(self.1, attr.colormap);
+            }
         }
 
         Ok(())
@@ -116,17 +480,75 @@ pub struct WindowBuilder {
     pub y: i32,
     pub width: u32,
     pub height: u32,
+    pub title: String,
+    pub transparent: bool,
+    pub kind: WindowKind,
+    pub owner: xlib::Window,
+    pub anchor: Option<Rect>,
+    pub decorations: bool,
+    pub always_on_top: bool,
+    pub skip_taskbar: bool,
+    pub user_attention: Option<UserAttention>,
+    pub all_workspaces: bool,
+    pub workspace: Option<u32>,
+    /// Events selected with `XSelectInput`.
+    pub event_mask: c_long,
+}
+
+/// Visual a window is created with, GLX and Vulkan pick one that matches their framebuffer config.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowVisual {
+    pub visual: *mut xlib::Visual,
+    pub depth: c_int,
+    /// Created from `visual` when `0`. The window owns the colormap and frees it when destroyed.
+    pub colormap: xlib::Colormap,
+}
+
+/// X events that deliver the callbacks of `events`.
+fn x11_event_mask(events: EventMask) -> c_long {
+    let masks = [
+        (EventMask::KEYBOARD, KeyPressMask | KeyReleaseMask),
+        (EventMask::FOCUS, FocusChangeMask),
+        (EventMask::BUTTONS, ButtonPressMask | ButtonReleaseMask),
+        (EventMask::POINTER_MOTION, PointerMotionMask | ButtonMotionMask),
+        (EventMask::CROSSING, EnterWindowMask | LeaveWindowMask),
+        (EventMask::STRUCTURE, StructureNotifyMask),
+        (EventMask::EXPOSURE, ExposureMask),
+        (EventMask::VISIBILITY, VisibilityChangeMask),
+        (EventMask::PROPERTY, PropertyChangeMask),
+    ];
+
+    masks.iter().filter(|(event, _)| events.contains(*event)).fold(0, |mask, (_, x11)| mask | x11)
 }
 
 impl Default for WindowBuilder {
     fn default() -> Self {
-        Self { x: Default::default(), y: Default::default(), width: 480, height: 170 }
+        Self {
+            x: Default::default(),
+            y: Default::default(),
+            width: 480,
+            height: 170,
+            title: String::new(),
+            transparent: false,
+            kind: WindowKind::Normal,
+            owner: 0,
+            anchor: None,
+            decorations: true,
+            always_on_top: false,
+            skip_taskbar: false,
+            user_attention: None,
+            all_workspaces: false,
+            workspace: None,
+            event_mask: x11_event_mask(EventMask::DEFAULT),
+        }
     }
 }
 
 impl WindowBuilder {
-    pub fn with_title(&mut self, _title: &str) -> &mut Self {
-        todo!()
+    pub fn with_title(&mut self, title: &str) -> &mut Self {
+        self.title = title.to_string();
+        self
     }
 
     pub fn with_size(&mut self, width: i32, height: i32) -> &mut Self {
@@ -135,43 +557,233 @@ impl WindowBuilder {
         self
     }
 
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        self.owner = owner.0;
+        self
+    }
+
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, skip_taskbar: bool) -> &mut Self {
+        self.skip_taskbar = skip_taskbar;
+        self
+    }
+
+    pub fn with_user_attention(&mut self, attention: Option<UserAttention>) -> &mut Self {
+        self.user_attention = attention;
+        self
+    }
+
+    pub fn with_visible_on_all_workspaces(&mut self, visible: bool) -> &mut Self {
+        self.all_workspaces = visible;
+        self
+    }
+
+    pub fn with_workspace(&mut self, workspace: u32) -> &mut Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    pub fn with_events(&mut self, events: EventMask) -> &mut Self {
+        self.event_mask = x11_event_mask(events);
+        self
+    }
+
+    /// The window is not mapped yet, so these only set the properties the window manager reads when mapping it.
+    unsafe fn set_wm_hints(&self, handle: WindowHandle) -> Result<(), Error> {
+        if self.always_on_top {
+            handle.set_always_on_top(true)?;
+        }
+
+        if self.skip_taskbar {
+            handle.set_skip_taskbar(true)?;
+        }
+
+        if self.user_attention.is_some() {
+            handle.request_user_attention(self.user_attention)?;
+        }
+
+        if self.all_workspaces {
+            handle.set_visible_on_all_workspaces(true)?;
+        } else if let Some(workspace) = self.workspace {
+            handle.move_to_workspace(workspace)?;
+        }
+
+        Ok(())
+    }
+
+    /// Translates the anchor from the owner to the root window and keeps the window on screen.
+    unsafe fn popup_position(&self, c: &ThreadContext, root: xlib::Window, anchor: Rect) -> (i32, i32) {
+        let mut left = anchor.left;
+        let mut top = anchor.top;
+        let mut child = 0;
+
+        if self.owner != 0 {
+            XTranslateCoordinates(c.display, self.owner, root, anchor.left, anchor.top, &mut left, &mut top, &mut child);
+        }
+
+        let anchor = Rect { left, top, right: left + anchor.right - anchor.left, bottom: top + anchor.bottom - anchor.top };
+        let bounds = Rect { left: 0, top: 0, right: XWidthOfScreen(c.screen), bottom: XHeightOfScreen(c.screen) };
+
+        popup_position(anchor, self.width as _, self.height as _, bounds)
+    }
+
+    unsafe fn set_window_type(&self, c: &ThreadContext, window: xlib::Window) {
+        let atoms = &c.atoms;
+        let window_type = match self.kind {
+            WindowKind::Normal => atoms._NET_WM_WINDOW_TYPE_NORMAL,
+            WindowKind::Dialog => atoms._NET_WM_WINDOW_TYPE_DIALOG,
+            WindowKind::Utility => atoms._NET_WM_WINDOW_TYPE_UTILITY,
+            WindowKind::Popup => atoms._NET_WM_WINDOW_TYPE_DROPDOWN_MENU,
+            WindowKind::Tooltip => atoms._NET_WM_WINDOW_TYPE_TOOLTIP,
+            WindowKind::Menu => atoms._NET_WM_WINDOW_TYPE_POPUP_MENU,
+        };
+
+        XChangeProperty(
+            c.display,
+            window,
+            atoms._NET_WM_WINDOW_TYPE,
+            XA_ATOM,
+            32,
+            PropModeReplace,
+            (&window_type as *const xlib::Atom).cast(),
+            1,
+        );
+    }
+
     #[inline]
     pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
+        self.create_window(context, None)
+    }
+
+    /// Like `build` but with `visual` instead of the default one, `transparent` is ignored.
+    pub fn build_with_visual<E: Event>(&self, context: Context, visual: WindowVisual) -> Result<WindowHandle, Error> {
+        self.create_window(context, Some(visual))
+    }
+
+    fn create_window(&self, context: Context, window_visual: Option<WindowVisual>) -> Result<WindowHandle, Error> {
         unsafe {
             let c = context.0.to_ref();
 
-            let window = XCreateSimpleWindow(
+            let root = XRootWindowOfScreen(c.screen);
+
+            let mut attributes: XSetWindowAttributes = zeroed();
+            attributes.border_pixel = XBlackPixel(c.display, c.screen_id);
+            attributes.background_pixel = XWhitePixel(c.display, c.screen_id);
+
+            let mut value_mask = CWBackPixel | CWBorderPixel;
+            let mut visual = null_mut();
+            let mut depth = CopyFromParent;
+
+            if let Some(window_visual) = window_visual {
+                visual = window_visual.visual;
+                depth = window_visual.depth;
+
+                attributes.colormap = match window_visual.colormap {
+                    0 => XCreateColormap(c.display, root, visual, AllocNone),
+                    colormap => colormap,
+                };
+                attributes.border_pixel = 0;
+                attributes.background_pixel = 0;
+                value_mask |= CWColormap;
+            } else if self.transparent {
+                let mut info = zeroed();
+                if XMatchVisualInfo(c.display, c.screen_id, 32, TrueColor, &mut info) != 0 {
+                    visual = info.visual;
+                    depth = info.depth;
+
+                    // A visual other than the parent's needs its own colormap.
+                    attributes.colormap = XCreateColormap(c.display, root, visual, AllocNone);
+                    attributes.border_pixel = 0;
+                    attributes.background_pixel = 0;
+                    value_mask |= CWColormap;
+                } else {
+                    log_warn!("Exposed", "No 32-bit TrueColor visual, the window won't be transparent.");
+                }
+            }
+
+            let (x, y) = match self.anchor {
+                Some(anchor) if self.kind.is_popup() => self.popup_position(c, root, anchor),
+                _ => (self.x, self.y),
+            };
+
+            if self.kind.is_popup() {
+                attributes.override_redirect = True;
+                attributes.save_under = True;
+                value_mask |= CWOverrideRedirect | CWSaveUnder;
+            }
+
+            let window = XCreateWindow(
                 c.display,
-                XRootWindowOfScreen(c.screen),
-                self.x,
-                self.y,
+                root,
+                x,
+                y,
                 self.width,
                 self.height,
                 1,
-                XBlackPixel(c.display, c.screen_id),
-                XWhitePixel(c.display, c.screen_id),
+                depth,
+                InputOutput as _,
+                visual,
+                value_mask,
+                &mut attributes,
             );
 
             // TODO:(fraclysis) Check for window error
 
-            let event_masks = KeyPressMask
-                | KeyReleaseMask
-                | FocusChangeMask
-                | ResizeRedirectMask
-                | PointerMotionMask
-                | ButtonMotionMask
-                | ButtonPressMask
-                | ButtonReleaseMask
-                | EnterWindowMask
-                | LeaveWindowMask;
+            let handle = c.register_window(window);
 
-            XSelectInput(c.display, window, event_masks);
+            XSelectInput(c.display, window, self.event_mask);
 
             XClearWindow(c.display, window);
 
             XSetWMProtocols(c.display, window, &mut c.wm_delete, 1);
 
-            let ic = XCreateIC(
+            if !self.title.is_empty() {
+                handle.set_window_title(&self.title)?;
+            }
+
+            self.set_window_type(c, window);
+
+            c.identity.set_window_properties(c.display, &c.atoms, window);
+
+            if !self.decorations {
+                set_motif_decorations(c, window, false);
+            }
+
+            if self.owner != 0 {
+                XSetTransientForHint(c.display, window, self.owner);
+            }
+
+            self.set_wm_hints(handle)?;
+
+            if let Some(xinput) = &c.xinput {
+                xinput.select_gestures(c.display, window);
+            }
+
+            let ic = (super::xlib::get().XCreateIC)(
                 c.im,
                 XNInputStyle_0.as_ptr(),
                 XIMPreeditNothing | XIMStatusNothing,
@@ -182,9 +794,24 @@ impl WindowBuilder {
 
             XSetICFocus(ic);
 
+            // The input context lives and dies with the window, its leaks are the window's.
             c.window_map.insert(window, ic);
 
-            Ok(WindowHandle(window, c.display))
+            Ok(handle)
         }
     }
 }
+
+#[test]
+fn x11_event_mask_test() {
+    let mask = WindowBuilder::default().event_mask;
+
+    let wanted = StructureNotifyMask | ExposureMask | PropertyChangeMask;
+    assert_eq!(mask & wanted, wanted);
+    // Redirecting resizes would keep the window manager from resizing the window.
+    assert_eq!(mask & xlib::ResizeRedirectMask, 0);
+
+    let mask = x11_event_mask(EventMask::DEFAULT & !(EventMask::POINTER_MOTION | EventMask::PROPERTY));
+    assert_eq!(mask & (PointerMotionMask | ButtonMotionMask | PropertyChangeMask), 0);
+    assert_eq!(mask & ButtonPressMask, ButtonPressMask);
+}
@@ -0,0 +1,192 @@
+use std::{
+    ffi::{c_double, c_int, c_uchar, c_ulong, c_void},
+    mem::transmute,
+    ptr::null_mut,
+};
+
+use exposed_macro::cstr;
+use libc::{dlclose, dlopen, dlsym, RTLD_LAZY, RTLD_LOCAL};
+use x11::{
+    xinput2::{XIAllMasterDevices, XIEventMask, XIGroupState, XIModifierState, XISetMask},
+    xlib::{Bool, Display, Time, Window},
+};
+
+use super::xlib::XQueryExtension;
+use crate::window::{Gesture, GestureKind};
+
+// Gesture events are newer than the `x11` crate, values are from XI2.h.
+pub const XI_GESTURE_PINCH_BEGIN: c_int = 27;
+pub const XI_GESTURE_PINCH_UPDATE: c_int = 28;
+pub const XI_GESTURE_PINCH_END: c_int = 29;
+pub const XI_GESTURE_SWIPE_BEGIN: c_int = 30;
+pub const XI_GESTURE_SWIPE_UPDATE: c_int = 31;
+pub const XI_GESTURE_SWIPE_END: c_int = 32;
+
+pub const XI_GESTURE_PINCH_EVENT_CANCELLED: c_int = 1;
+pub const XI_GESTURE_SWIPE_EVENT_CANCELLED: c_int = 1;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XIGesturePinchEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: c_double,
+    pub root_y: c_double,
+    pub event_x: c_double,
+    pub event_y: c_double,
+    pub delta_x: c_double,
+    pub delta_y: c_double,
+    pub delta_unaccel_x: c_double,
+    pub delta_unaccel_y: c_double,
+    pub scale: c_double,
+    pub delta_angle: c_double,
+    pub flags: c_int,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+
+impl XIGesturePinchEvent {
+    pub fn gesture(&self) -> Gesture {
+        Gesture {
+            kind: GestureKind::Pinch,
+            fingers: self.detail as _,
+            location: (self.event_x as _, self.event_y as _),
+            delta: (self.delta_x as _, self.delta_y as _),
+            scale: self.scale as _,
+            rotation: self.delta_angle as _,
+            cancelled: self.flags & XI_GESTURE_PINCH_EVENT_CANCELLED != 0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct XIGestureSwipeEvent {
+    pub type_: c_int,
+    pub serial: c_ulong,
+    pub send_event: Bool,
+    pub display: *mut Display,
+    pub extension: c_int,
+    pub evtype: c_int,
+    pub time: Time,
+    pub deviceid: c_int,
+    pub sourceid: c_int,
+    pub detail: c_int,
+    pub root: Window,
+    pub event: Window,
+    pub child: Window,
+    pub root_x: c_double,
+    pub root_y: c_double,
+    pub event_x: c_double,
+    pub event_y: c_double,
+    pub delta_x: c_double,
+    pub delta_y: c_double,
+    pub delta_unaccel_x: c_double,
+    pub delta_unaccel_y: c_double,
+    pub flags: c_int,
+    pub mods: XIModifierState,
+    pub group: XIGroupState,
+}
+
+impl XIGestureSwipeEvent {
+    pub fn gesture(&self) -> Gesture {
+        Gesture {
+            kind: GestureKind::Swipe,
+            fingers: self.detail as _,
+            location: (self.event_x as _, self.event_y as _),
+            delta: (self.delta_x as _, self.delta_y as _),
+            scale: 1.0,
+            rotation: 0.0,
+            cancelled: self.flags & XI_GESTURE_SWIPE_EVENT_CANCELLED != 0,
+        }
+    }
+}
+
+type XIQueryVersion = unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> c_int;
+type XISelectEvents = unsafe extern "C" fn(*mut Display, Window, *mut XIEventMask, c_int) -> c_int;
+
+/// `libXi` loaded at runtime. Exists only if the server supports XInput 2.4.
+#[derive(Debug, Clone, Copy)]
+pub struct XInput {
+    pub lib: *mut c_void,
+    pub opcode: c_int,
+    pub select_events: XISelectEvents,
+}
+
+impl XInput {
+    /// Returns `None` if `libXi` is missing or the server is older than XInput 2.4, which introduced gestures.
+    ///
+    /// # Safety
+    /// `display` must be an open connection.
+    pub unsafe fn load(display: *mut Display) -> Option<Self> {
+        let mut opcode = 0;
+        let mut event = 0;
+        let mut error = 0;
+        if XQueryExtension(display, cstr!("XInputExtension"), &mut opcode, &mut event, &mut error) == 0 {
+            return None;
+        }
+
+        let paths = [cstr!("libXi.so.6"), cstr!("libXi.so")];
+
+        let mut lib = null_mut();
+        for path in paths {
+            lib = dlopen(path, RTLD_LAZY | RTLD_LOCAL);
+            if !lib.is_null() {
+                break;
+            }
+        }
+
+        if lib.is_null() {
+            return None;
+        }
+
+        let query_version = dlsym(lib, cstr!("XIQueryVersion"));
+        let select_events = dlsym(lib, cstr!("XISelectEvents"));
+        if query_version.is_null() || select_events.is_null() {
+            dlclose(lib);
+            return None;
+        }
+
+        let query_version = transmute::<*mut c_void, XIQueryVersion>(query_version);
+
+        let mut major = 2;
+        let mut minor = 4;
+        if query_version(display, &mut major, &mut minor) != 0 || (major, minor) < (2, 4) {
+            dlclose(lib);
+            return None;
+        }
+
+        Some(Self { lib, opcode, select_events: transmute::<*mut c_void, XISelectEvents>(select_events) })
+    }
+
+    /// # Safety
+    /// `display` must be the connection this was loaded with and `window` must belong to it.
+    pub unsafe fn select_gestures(&self, display: *mut Display, window: Window) {
+        let mut mask = [0 as c_uchar; (XI_GESTURE_SWIPE_END as usize >> 3) + 1];
+
+        for event in XI_GESTURE_PINCH_BEGIN..=XI_GESTURE_SWIPE_END {
+            XISetMask(&mut mask, event);
+        }
+
+        let mut event_mask = XIEventMask { deviceid: XIAllMasterDevices, mask_len: mask.len() as _, mask: mask.as_mut_ptr() };
+
+        (self.select_events)(display, window, &mut event_mask, 1);
+    }
+
+    /// # Safety
+    /// No function of this instance can be used afterwards.
+    pub unsafe fn unload(self) {
+        dlclose(self.lib);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    ffi::{c_char, c_int, c_long, c_uchar, c_uint, c_ulong, c_void},
+    mem::transmute,
+    ptr::null_mut,
+    sync::OnceLock,
+};
+
+use exposed_macro::cstr;
+use libc::{dlclose, dlopen, dlsym, RTLD_LAZY, RTLD_LOCAL};
+use x11::xlib::{
+    Atom, Bool, Colormap, Display, Drawable, KeyCode, KeySym, Screen, Status, Time, Visual, Window, XClassHint, XErrorEvent,
+    XEvent, XGCValues, XGenericEventCookie, XImage, XKeyEvent, XPointer, XSetWindowAttributes, XVisualInfo, XWMHints,
+    XWindowAttributes, XrmDatabase, GC, XIC, XIM,
+};
+
+type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+type XPredicate = Option<unsafe extern "C" fn(*mut Display, *mut XEvent, XPointer) -> Bool>;
+
+static XLIB: OnceLock<Result<Xlib, String>> = OnceLock::new();
+
+/// Loads `libX11` once per process, the library is never unloaded since Xlib keeps global state.
+pub fn load_xlib() -> Result<&'static Xlib, crate::Error> {
+    match XLIB.get_or_init(|| unsafe { Xlib::load() }) {
+        Ok(xlib) => Ok(xlib),
+        Err(e) => Err(crate::Error::LibraryNotLoaded { library: "libX11", source: Some(e.clone().into()) }),
+    }
+}
+
+/// # Panics
+/// If `libX11` wasn't loaded by building an `EventHandler` first.
+pub fn get() -> &'static Xlib {
+    match XLIB.get() {
+        Some(Ok(xlib)) => xlib,
+        _ => panic!("libX11 is used before EventHandlerBuilder::build loaded it."),
+    }
+}
+
+macro_rules! xlib {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        /// Functions of `libX11` loaded at runtime. The free functions with the same names call through `get()`.
+        #[allow(non_snake_case)]
+        #[derive(Clone, Copy)]
+        pub struct Xlib {
+            $(pub $name: unsafe extern "C" fn($($ty),*) $(-> $ret)?,)*
+            /// Variadic, called through the field since Rust can't define variadic functions.
+            pub XCreateIC: unsafe extern "C" fn(XIM, ...) -> XIC,
+        }
+
+        impl Xlib {
+            unsafe fn load() -> Result<Self, String> {
+                let paths = [cstr!("libX11.so.6"), cstr!("libX11.so")];
+
+                let mut lib = null_mut();
+                for path in paths {
+                    lib = dlopen(path, RTLD_LAZY | RTLD_LOCAL);
+                    if !lib.is_null() {
+                        break;
+                    }
+                }
+
+                if lib.is_null() {
+                    return Err("libX11.so.6 is missing, install the X11 client libraries or select another backend.".into());
+                }
+
+                let symbol = |name: &str| {
+                    let c_name = format!("{name}\0");
+                    let symbol = dlsym(lib, c_name.as_ptr().cast());
+                    if symbol.is_null() {
+                        Err(format!("libX11 has no {name}."))
+                    } else {
+                        Ok(symbol)
+                    }
+                };
+
+                let load = || -> Result<Self, String> {
+                    Ok(Self {
+                        $($name: transmute::<*mut c_void, unsafe extern "C" fn($($ty),*) $(-> $ret)?>(symbol(stringify!($name))?),)*
+                        XCreateIC: transmute::<*mut c_void, unsafe extern "C" fn(XIM, ...) -> XIC>(symbol("XCreateIC")?),
+                    })
+                };
+
+                load().inspect_err(|_| {
+                    dlclose(lib);
+                })
+            }
+        }
+
+        $(
+            #[allow(non_snake_case, clippy::missing_safety_doc, clippy::too_many_arguments)]
+            #[inline]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)? {
+                (get().$name)($($arg),*)
+            }
+        )*
+    };
+}
+
+xlib! {
+    fn XAllocWMHints() -> *mut XWMHints;
+    fn XBlackPixel(display: *mut Display, screen: c_int) -> c_ulong;
+    fn XChangeProperty(
+        display: *mut Display, window: Window, property: Atom, kind: Atom, format: c_int, mode: c_int, data: *const c_uchar,
+        len: c_int
+    ) -> c_int;
+    fn XCheckIfEvent(display: *mut Display, event: *mut XEvent, predicate: XPredicate, arg: XPointer) -> Bool;
+    fn XCheckTypedEvent(display: *mut Display, kind: c_int, event: *mut XEvent) -> Bool;
+    fn XClearWindow(display: *mut Display, window: Window) -> c_int;
+    fn XCloseDisplay(display: *mut Display) -> c_int;
+    fn XCloseIM(im: XIM) -> Status;
+    fn XConnectionNumber(display: *mut Display) -> c_int;
+    fn XConvertCase(keysym: KeySym, lower: *mut KeySym, upper: *mut KeySym);
+    fn XCreateColormap(display: *mut Display, window: Window, visual: *mut Visual, alloc: c_int) -> Colormap;
+    fn XCreateGC(display: *mut Display, drawable: Drawable, mask: c_ulong, values: *mut XGCValues) -> GC;
+    fn XCreateWindow(
+        display: *mut Display, parent: Window, x: c_int, y: c_int, width: c_uint, height: c_uint, border_width: c_uint,
+        depth: c_int, class: c_uint, visual: *mut Visual, mask: c_ulong, attributes: *mut XSetWindowAttributes
+    ) -> Window;
+    fn XDefaultColormap(display: *mut Display, screen: c_int) -> Colormap;
+    fn XDefaultScreen(display: *mut Display) -> c_int;
+    fn XDefaultScreenOfDisplay(display: *mut Display) -> *mut Screen;
+    fn XDeleteProperty(display: *mut Display, window: Window, property: Atom) -> c_int;
+    fn XDestroyIC(ic: XIC);
+    fn XDestroyImage(image: *mut XImage) -> c_int;
+    fn XDestroyWindow(display: *mut Display, window: Window) -> c_int;
+    fn XFlush(display: *mut Display) -> c_int;
+    fn XFree(data: *mut c_void) -> c_int;
+    fn XFreeColormap(display: *mut Display, colormap: Colormap) -> c_int;
+    fn XFreeEventData(display: *mut Display, cookie: *mut XGenericEventCookie);
+    fn XFreeGC(display: *mut Display, gc: GC) -> c_int;
+    fn XGetEventData(display: *mut Display, cookie: *mut XGenericEventCookie) -> Bool;
+    fn XGetImage(
+        display: *mut Display, drawable: Drawable, x: c_int, y: c_int, width: c_uint, height: c_uint, plane_mask: c_ulong,
+        format: c_int
+    ) -> *mut XImage;
+    fn XGetPixel(image: *mut XImage, x: c_int, y: c_int) -> c_ulong;
+    fn XGetWMHints(display: *mut Display, window: Window) -> *mut XWMHints;
+    fn XGetWindowAttributes(display: *mut Display, window: Window, attributes: *mut XWindowAttributes) -> Status;
+    fn XGetWindowProperty(
+        display: *mut Display, window: Window, property: Atom, offset: c_long, len: c_long, delete: Bool, kind: Atom,
+        actual_kind: *mut Atom, actual_format: *mut c_int, items: *mut c_ulong, bytes_after: *mut c_ulong,
+        data: *mut *mut c_uchar
+    ) -> c_int;
+    fn XHeightOfScreen(screen: *mut Screen) -> c_int;
+    fn XInitImage(image: *mut XImage) -> Status;
+    fn XInternAtom(display: *mut Display, name: *const c_char, only_if_exists: Bool) -> Atom;
+    fn XInternAtoms(display: *mut Display, names: *mut *mut c_char, count: c_int, only_if_exists: Bool, atoms: *mut Atom) -> Status;
+    fn XKeysymToKeycode(display: *mut Display, keysym: KeySym) -> KeyCode;
+    fn XMapWindow(display: *mut Display, window: Window) -> c_int;
+    fn XMatchVisualInfo(display: *mut Display, screen: c_int, depth: c_int, class: c_int, info: *mut XVisualInfo) -> Status;
+    fn XNextEvent(display: *mut Display, event: *mut XEvent) -> c_int;
+    fn XOpenDisplay(name: *const c_char) -> *mut Display;
+    fn XOpenIM(display: *mut Display, database: XrmDatabase, name: *mut c_char, class: *mut c_char) -> XIM;
+    fn XPending(display: *mut Display) -> c_int;
+    fn XPutImage(
+        display: *mut Display, drawable: Drawable, gc: GC, image: *mut XImage, src_x: c_int, src_y: c_int, dest_x: c_int,
+        dest_y: c_int, width: c_uint, height: c_uint
+    ) -> c_int;
+    fn XQueryExtension(
+        display: *mut Display, name: *const c_char, opcode: *mut c_int, event: *mut c_int, error: *mut c_int
+    ) -> Bool;
+    fn XRootWindowOfScreen(screen: *mut Screen) -> Window;
+    fn XSelectInput(display: *mut Display, window: Window, mask: c_long) -> c_int;
+    fn XSendEvent(display: *mut Display, window: Window, propagate: Bool, mask: c_long, event: *mut XEvent) -> Status;
+    fn XSetClassHint(display: *mut Display, window: Window, hint: *mut XClassHint) -> c_int;
+    fn XSetErrorHandler(handler: XErrorHandler) -> XErrorHandler;
+    fn XSetICFocus(ic: XIC);
+    fn XSetLocaleModifiers(modifiers: *const c_char) -> *mut c_char;
+    fn XSetTransientForHint(display: *mut Display, window: Window, owner: Window) -> c_int;
+    fn XSetWMHints(display: *mut Display, window: Window, hints: *mut XWMHints) -> c_int;
+    fn XSetWMProtocols(display: *mut Display, window: Window, protocols: *mut Atom, count: c_int) -> Status;
+    fn XStoreName(display: *mut Display, window: Window, name: *const c_char) -> c_int;
+    fn XSupportsLocale() -> Bool;
+    fn XSync(display: *mut Display, discard: Bool) -> c_int;
+    fn XTranslateCoordinates(
+        display: *mut Display, src: Window, dest: Window, src_x: c_int, src_y: c_int, dest_x: *mut c_int, dest_y: *mut c_int,
+        child: *mut Window
+    ) -> Bool;
+    fn XUngrabPointer(display: *mut Display, time: Time) -> c_int;
+    fn XWhitePixel(display: *mut Display, screen: c_int) -> c_ulong;
+    fn XWidthOfScreen(screen: *mut Screen) -> c_int;
+    fn Xutf8LookupString(
+        ic: XIC, event: *mut XKeyEvent, buffer: *mut c_char, len: c_int, keysym: *mut KeySym, status: *mut Status
+    ) -> c_int;
+}
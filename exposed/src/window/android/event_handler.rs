@@ -34,12 +34,6 @@ impl<E: Event> Destroy for EventHandler<E> {
     }
 }
 
-impl<E: Event> Into<crate::window::EventHandler<E>> for EventHandler<E> {
-    fn into(self) -> crate::window::EventHandler<E> {
-        crate::window::EventHandler(self)
-    }
-}
-
 #[repr(C)]
 #[derive(Debug)]
 pub struct EventHandlerBuilder {}
@@ -55,7 +49,15 @@ impl EventHandlerBuilder {
         Self::default()
     }
 
-    pub unsafe fn build<E: Event>(&self, _user_data: *mut E) -> Result<EventHandler<E>, Error> {
+    pub fn with_gamepad_mappings(&mut self, _mappings: &str) -> &mut Self {
+        self
+    }
+
+    pub fn with_app_id(&mut self, _name: &str, _class: &str) -> &mut Self {
+        self
+    }
+
+    pub(crate) unsafe fn build<E: Event>(&self, _user_data: *mut E) -> Result<EventHandler<E>, crate::Error> {
         todo!()
     }
 }
@@ -13,12 +13,19 @@ use ndk_sys::{
 };
 use unsafe_utilities::to_ref::ToReference;
 
-use crate::window::{platform::WindowHandle, Event, Key, Touch, TouchPhase};
+use crate::window::{platform::WindowHandle, Event, Key, Requests, Timers, Touch, TouchPhase};
 
 use super::{ActivityContext, Context, WaitState};
 
 pub extern "C" fn main<E: Event>(data: *mut c_void) -> *mut c_void {
-    unsafe { _main::<E>((data as *mut ActivityContext).to_ref()) }
+    // Unwinding out of the thread's entry aborts, the panic is logged and the thread exits instead.
+    let result =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe { _main::<E>((data as *mut ActivityContext).to_ref()) }));
+
+    result.unwrap_or_else(|_| {
+        log_error!("Exposed", "Event loop panicked.");
+        null_mut()
+    })
 }
 
 unsafe fn _main<E: Event>(data: &mut ActivityContext) -> *mut c_void {
@@ -95,10 +102,22 @@ unsafe fn _main<E: Event>(data: &mut ActivityContext) -> *mut c_void {
                 None => unreachable!(),
 
                 Running => {
+                    if Timers::has_events() {
+                        while let Some(timer) = Timers::pop() {
+                            _e.timer_fired(timer);
+                        }
+                    }
+
+                    // Nothing wakes the looper for them, they run with the next event.
+                    Requests::run();
+
+                    // Wakes up with `ALOOPER_POLL_TIMEOUT` when the next timer is due.
+                    let timeout = Timers::timeout().map_or(-1, |t| t.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32);
+
                     let mut fd = zeroed();
                     let mut events = zeroed();
                     let mut out_data = zeroed();
-                    let l = ALooper_pollOnce(-1, &mut fd, &mut events, &mut out_data);
+                    let l = ALooper_pollOnce(timeout, &mut fd, &mut events, &mut out_data);
                     match l {
                         ALOOPER_POLL_WAKE => {
                             _e.render(window);
@@ -16,6 +16,12 @@ pub use window::*;
 mod event_handler;
 pub use event_handler::*;
 
+mod pixel_surface;
+pub use pixel_surface::*;
+
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
+
 pub use jni_sys as jni;
 pub use libc;
 pub use ndk_sys as ndk;
@@ -0,0 +1,102 @@
+use std::{
+    io::{Error, ErrorKind},
+    mem::zeroed,
+};
+
+use ndk_sys::{AHardwareBuffer_Format, ANativeWindow_lock, ANativeWindow_setBuffersGeometry, ANativeWindow_unlockAndPost, ARect};
+
+use crate::{destroy::Destroy, window::Rect};
+
+use super::WindowHandle;
+
+/// Pixels are kept in memory and converted to `R8G8B8X8` while the window buffer is locked.
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub window: WindowHandle,
+    pub width: u32,
+    pub height: u32,
+    pub buffer: Vec<u32>,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        Ok(Self { window, width: 0, height: 0, buffer: Vec::new() })
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        let size = self.window.client_size()?;
+        let (width, height) = (size.width.max(0) as u32, size.height.max(0) as u32);
+
+        if (width, height) != (self.width, self.height) {
+            let format = AHardwareBuffer_Format::AHARDWAREBUFFER_FORMAT_R8G8B8X8_UNORM.0 as _;
+            if unsafe { ANativeWindow_setBuffersGeometry(self.window.native_handle(), width as _, height as _, format) } != 0 {
+                return Err(Error::new(ErrorKind::Other, "Failed at ANativeWindow_setBuffersGeometry."));
+            }
+
+            self.width = width;
+            self.height = height;
+            self.buffer.resize((width * height) as usize, 0);
+        }
+
+        Ok(&mut self.buffer)
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.present_with_damage(&[Rect { left: 0, top: 0, right: self.width as _, bottom: self.height as _ }])
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        if self.buffer.is_empty() || damage.is_empty() {
+            return Ok(());
+        }
+
+        // Android locks a single dirty rectangle and may grow it, everything inside the returned bounds is copied.
+        let mut bounds = ARect { left: i32::MAX, top: i32::MAX, right: 0, bottom: 0 };
+        for rect in damage {
+            bounds.left = bounds.left.min(rect.left.max(0));
+            bounds.top = bounds.top.min(rect.top.max(0));
+            bounds.right = bounds.right.max(rect.right.min(self.width as _));
+            bounds.bottom = bounds.bottom.max(rect.bottom.min(self.height as _));
+        }
+
+        if bounds.left >= bounds.right || bounds.top >= bounds.bottom {
+            return Ok(());
+        }
+
+        unsafe {
+            let window = self.window.native_handle();
+            let mut buffer = zeroed();
+
+            if ANativeWindow_lock(window, &mut buffer, &mut bounds) != 0 {
+                return Err(Error::new(ErrorKind::Other, "Failed at ANativeWindow_lock."));
+            }
+
+            let width = (buffer.width as u32).min(self.width) as usize;
+            let height = (buffer.height as u32).min(self.height) as usize;
+            let pixels = std::slice::from_raw_parts_mut(buffer.bits as *mut u32, buffer.stride as usize * height);
+
+            let (left, right) = (bounds.left.max(0) as usize, (bounds.right.max(0) as usize).min(width));
+            for y in bounds.top.max(0) as usize..(bounds.bottom.max(0) as usize).min(height) {
+                let source = &self.buffer[y * self.width as usize..][left..right];
+                let target = &mut pixels[y * buffer.stride as usize..][left..right];
+
+                for (target, pixel) in target.iter_mut().zip(source) {
+                    // 0x00RRGGBB to R, G, B, X bytes.
+                    *target = 0xFF000000 | (pixel >> 16 & 0xFF) | (pixel & 0xFF00) | ((pixel & 0xFF) << 16);
+                }
+            }
+
+            if ANativeWindow_unlockAndPost(window) != 0 {
+                return Err(Error::new(ErrorKind::Other, "Failed at ANativeWindow_unlockAndPost."));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
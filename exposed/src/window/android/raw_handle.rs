@@ -0,0 +1,41 @@
+use super::{Context, WindowHandle};
+
+#[cfg(feature = "rwh_05")]
+impl WindowHandle {
+    pub fn raw_window_handle_05(self) -> rwh_05::RawWindowHandle {
+        let mut handle = rwh_05::AndroidNdkWindowHandle::empty();
+        handle.a_native_window = self.native_handle().cast();
+        rwh_05::RawWindowHandle::AndroidNdk(handle)
+    }
+
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        rwh_05::RawDisplayHandle::Android(rwh_05::AndroidDisplayHandle::empty())
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+impl Context {
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        rwh_05::RawDisplayHandle::Android(rwh_05::AndroidDisplayHandle::empty())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl WindowHandle {
+    /// `Unavailable` while the activity has no native window.
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        let window = std::ptr::NonNull::new(self.native_handle().cast()).ok_or(rwh_06::HandleError::Unavailable)?;
+        Ok(rwh_06::AndroidNdkWindowHandle::new(window).into())
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Ok(rwh_06::AndroidDisplayHandle::new().into())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Ok(rwh_06::AndroidDisplayHandle::new().into())
+    }
+}
@@ -12,7 +12,7 @@ use unsafe_utilities::to_ref::ToReference;
 
 use crate::{
     destroy::Destroy,
-    window::{platform::WaitState, Event, Rect, Size},
+    window::{platform::WaitState, Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
 };
 
 use super::Context;
@@ -29,6 +29,11 @@ impl Destroy for WindowHandle {
 }
 
 impl WindowHandle {
+    /// The native window lives as long as the activity, on the thread of the event handler.
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        Ok(())
+    }
+
     pub fn release_capture(self) -> Result<(), Error> {
         Ok(())
     }
@@ -92,15 +97,15 @@ impl WindowHandle {
     }
 
     pub fn window_title(self) -> Result<String, Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented."))
+        Err(crate::Error::Unsupported("window titles on Android").into())
     }
 
     pub fn set_window_title(self, _title: &str) -> Result<(), Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented."))
+        Err(crate::Error::Unsupported("window titles on Android").into())
     }
 
     pub fn dpi(self) -> Result<u32, Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented."))
+        Err(crate::Error::Unsupported("dpi on Android").into())
     }
 
     pub fn client_size(&self) -> Result<Size, Error> {
@@ -113,21 +118,55 @@ impl WindowHandle {
     }
 
     pub fn client_rect(self) -> Result<Rect, Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented."))
+        Err(crate::Error::Unsupported("client rects on Android").into())
     }
 
     pub fn window_rect(self) -> Result<Rect, Error> {
-        Err(Error::new(ErrorKind::Other, "Not implemented."))
+        Err(crate::Error::Unsupported("window rects on Android").into())
     }
 
-    pub fn get_window_size(&self) -> Result<Size, Error> {
-        self.client_size()
+    pub fn set_opacity(self, _opacity: f32) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("opacity on Android").into())
+    }
+
+    pub fn capture(self) -> Result<Image, Error> {
+        Err(crate::Error::Unsupported("capturing windows on Android").into())
+    }
+
+    pub fn set_decorations(self, _decorations: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("decorations on Android").into())
+    }
+
+    pub fn drag_move(self) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("dragging windows on Android").into())
+    }
+
+    pub fn drag_resize(self, _edge: ResizeEdge) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("dragging windows on Android").into())
+    }
+
+    pub fn set_always_on_top(self, _always_on_top: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("always on top windows on Android").into())
+    }
+
+    pub fn set_skip_taskbar(self, _skip_taskbar: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("the taskbar on Android").into())
+    }
+
+    pub fn request_user_attention(self, _attention: Option<UserAttention>) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("user attention on Android").into())
+    }
+
+    pub fn set_visible_on_all_workspaces(self, _visible: bool) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("workspaces on Android").into())
     }
-}
 
-impl Into<crate::window::WindowHandle> for WindowHandle {
-    fn into(self) -> crate::window::WindowHandle {
-        crate::window::WindowHandle(self)
+    pub fn move_to_workspace(self, _workspace: u32) -> Result<(), Error> {
+        Err(crate::Error::Unsupported("workspaces on Android").into())
+    }
+
+    pub fn get_window_size(&self) -> Result<Size, Error> {
+        self.client_size()
     }
 }
 
@@ -154,6 +193,50 @@ impl WindowBuilder {
         self
     }
 
+    pub fn with_transparent(&mut self, _transparent: bool) -> &mut Self {
+        self
+    }
+
+    pub fn with_kind(&mut self, _kind: WindowKind) -> &mut Self {
+        self
+    }
+
+    pub fn with_owner(&mut self, _owner: WindowHandle) -> &mut Self {
+        self
+    }
+
+    pub fn with_anchor(&mut self, _anchor: Rect) -> &mut Self {
+        self
+    }
+
+    pub fn with_decorations(&mut self, _decorations: bool) -> &mut Self {
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, _always_on_top: bool) -> &mut Self {
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, _skip_taskbar: bool) -> &mut Self {
+        self
+    }
+
+    pub fn with_user_attention(&mut self, _attention: Option<UserAttention>) -> &mut Self {
+        self
+    }
+
+    pub fn with_visible_on_all_workspaces(&mut self, _visible: bool) -> &mut Self {
+        self
+    }
+
+    pub fn with_workspace(&mut self, _workspace: u32) -> &mut Self {
+        self
+    }
+
+    pub fn with_events(&mut self, _events: EventMask) -> &mut Self {
+        self
+    }
+
     /// Blocks Android thread until WindowHandle is created
     pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
         use WaitState::*;
@@ -0,0 +1,25 @@
+use exposed_macro::log_warn;
+
+/// Display server used on Linux. Wayland is only available with the `wayland` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Backend {
+    X11,
+    Wayland,
+}
+
+impl Backend {
+    /// Reads `EXPOSED_BACKEND`, either `x11` or `wayland`.
+    pub fn from_env() -> Option<Self> {
+        let backend = std::env::var("EXPOSED_BACKEND").ok()?;
+
+        match backend.to_ascii_lowercase().as_str() {
+            "x11" => Some(Self::X11),
+            "wayland" => Some(Self::Wayland),
+            "" => None,
+            _ => {
+                log_warn!("Exposed", "Unknown backend {backend} in EXPOSED_BACKEND.");
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::{
+    collections::VecDeque,
+    ffi::{c_int, c_ulong, CStr, CString},
+    io::Error,
+    mem::{size_of, zeroed},
+};
+
+use exposed_macro::{cstr, log_warn};
+use libc::{
+    close, epoll_ctl, epoll_event, inotify_add_watch, inotify_init1, ioctl, open, read, EAGAIN, ENODEV, EPOLLIN, EPOLL_CTL_ADD,
+    EPOLL_CTL_DEL, IN_ATTRIB, IN_CLOEXEC, IN_CREATE, IN_DELETE, IN_NONBLOCK, O_CLOEXEC, O_NONBLOCK, O_RDONLY,
+};
+
+use crate::{
+    destroy::Destroy,
+    window::{
+        sdl_guid, AbsInfo, EvdevEvent, EvdevLayout, Gamepad, GamepadEvent, GamepadMapper, GamepadMapping, GamepadMappings, ABS_MAX,
+        EV_ABS, EV_KEY, KEY_MAX,
+    },
+};
+
+const IOC_READ: c_ulong = 2;
+
+const fn ioc_read(nr: c_ulong, size: usize) -> c_ulong {
+    (IOC_READ << 30) | ((size as c_ulong) << 16) | ((b'E' as c_ulong) << 8) | nr
+}
+
+const fn eviocgbit(ev: u16, len: usize) -> c_ulong {
+    ioc_read(0x20 + ev as c_ulong, len)
+}
+
+const fn eviocgabs(abs: u16) -> c_ulong {
+    ioc_read(0x40 + abs as c_ulong, size_of::<AbsInfo>())
+}
+
+const EVIOCGID: c_ulong = ioc_read(0x02, 8);
+const EVIOCGNAME_LEN: usize = 256;
+const EVIOCGNAME: c_ulong = ioc_read(0x06, EVIOCGNAME_LEN);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadUpdate {
+    Connected(String),
+    Disconnected,
+    Event(GamepadEvent),
+}
+
+#[derive(Debug)]
+pub struct GamepadDevice {
+    pub gamepad: Gamepad,
+    pub fd: c_int,
+    pub path: CString,
+    pub name: String,
+    pub mapper: GamepadMapper,
+}
+
+/// Joysticks and gamepads under `/dev/input`, watched for hotplug with inotify.
+#[derive(Debug)]
+pub struct Gamepads {
+    pub epoll: c_int,
+    pub inotify: c_int,
+    pub devices: Vec<GamepadDevice>,
+    pub mappings: GamepadMappings,
+    pub queue: VecDeque<(Gamepad, GamepadUpdate)>,
+    next_id: u32,
+    events: Vec<GamepadEvent>,
+}
+
+impl Gamepads {
+    const INPUT_DIR: &'static str = "/dev/input";
+
+    /// Registers its file descriptors to `epoll` with the descriptor as the event data.
+    pub fn new(epoll: c_int, mappings: GamepadMappings) -> Self {
+        let mut this = Self {
+            epoll,
+            inotify: -1,
+            devices: Vec::new(),
+            mappings,
+            queue: VecDeque::new(),
+            next_id: 0,
+            events: Vec::new(),
+        };
+
+        unsafe {
+            this.inotify = inotify_init1(IN_NONBLOCK | IN_CLOEXEC);
+            if this.inotify == -1 {
+                log_warn!("Exposed", "Gamepad hotplug is disabled: {}", Error::last_os_error());
+            } else if inotify_add_watch(this.inotify, cstr!("/dev/input"), IN_CREATE | IN_ATTRIB | IN_DELETE) == -1 {
+                close(this.inotify);
+                this.inotify = -1;
+            } else {
+                this.watch(this.inotify);
+            }
+        }
+
+        if let Ok(entries) = std::fs::read_dir(Self::INPUT_DIR) {
+            let mut paths: Vec<String> = entries
+                .filter_map(|e| e.ok()?.file_name().into_string().ok())
+                .filter(|name| name.starts_with("event"))
+                .collect();
+            paths.sort();
+
+            for name in paths {
+                this.open_device(&name);
+            }
+        }
+
+        this
+    }
+
+    pub fn has_events(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    pub fn pop(&mut self) -> Option<(Gamepad, GamepadUpdate)> {
+        self.queue.pop_front()
+    }
+
+    /// Returns false if `fd` doesn't belong to gamepads.
+    pub fn ready(&mut self, fd: c_int) -> bool {
+        if fd == self.inotify {
+            self.read_inotify();
+            return true;
+        }
+
+        if let Some(index) = self.devices.iter().position(|d| d.fd == fd) {
+            self.read_device(index);
+            return true;
+        }
+
+        false
+    }
+
+    fn watch(&self, fd: c_int) {
+        let mut event = epoll_event { events: EPOLLIN as _, u64: fd as _ };
+        if unsafe { epoll_ctl(self.epoll, EPOLL_CTL_ADD, fd, &mut event) } == -1 {
+            log_warn!("Exposed", "Failed to watch gamepad: {}", Error::last_os_error());
+        }
+    }
+
+    fn open_device(&mut self, name: &str) {
+        let path = match CString::new(format!("{}/{name}", Self::INPUT_DIR)) {
+            Ok(path) => path,
+            Err(_) => return,
+        };
+
+        if self.devices.iter().any(|d| d.path == path) {
+            return;
+        }
+
+        unsafe {
+            // Permissions of new nodes are set by udev after IN_CREATE, IN_ATTRIB retries.
+            let fd = open(path.as_ptr(), O_RDONLY | O_NONBLOCK | O_CLOEXEC);
+            if fd == -1 {
+                return;
+            }
+
+            let mut keys = [0u8; KEY_MAX as usize / 8 + 1];
+            let mut abs = [0u8; ABS_MAX as usize / 8 + 1];
+
+            if ioctl(fd, eviocgbit(EV_KEY, keys.len()) as _, keys.as_mut_ptr()) < 0
+                || ioctl(fd, eviocgbit(EV_ABS, abs.len()) as _, abs.as_mut_ptr()) < 0
+            {
+                close(fd);
+                return;
+            }
+
+            let has = |bits: &[u8], code: u16| bits[code as usize / 8] & (1 << (code % 8)) != 0;
+
+            let key_codes: Vec<u16> = (0..KEY_MAX).filter(|c| has(&keys, *c)).collect();
+
+            let mut abs_codes = Vec::new();
+            for code in (0..ABS_MAX).filter(|c| has(&abs, *c)) {
+                let mut info: AbsInfo = zeroed();
+                if ioctl(fd, eviocgabs(code) as _, &mut info) >= 0 {
+                    abs_codes.push((code, info));
+                }
+            }
+
+            let layout = EvdevLayout::new(&key_codes, &abs_codes);
+            if !layout.is_gamepad() {
+                close(fd);
+                return;
+            }
+
+            let mut id = [0u16; 4];
+            ioctl(fd, EVIOCGID as _, id.as_mut_ptr());
+            let guid = sdl_guid(id[0], id[1], id[2], id[3]);
+
+            let mut name_buffer = [0u8; EVIOCGNAME_LEN];
+            ioctl(fd, EVIOCGNAME as _, name_buffer.as_mut_ptr());
+            let name = CStr::from_bytes_until_nul(&name_buffer)
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| name.to_string());
+
+            let mapping = match self.mappings.find(&guid) {
+                Some(mapping) => mapping.clone(),
+                None => GamepadMapping::kernel_default(&layout, &name),
+            };
+
+            let gamepad = Gamepad(self.next_id);
+            self.next_id += 1;
+
+            self.watch(fd);
+
+            self.queue.push_back((gamepad, GamepadUpdate::Connected(name.clone())));
+            self.devices.push(GamepadDevice { gamepad, fd, path, name, mapper: GamepadMapper::new(layout, mapping) });
+        }
+    }
+
+    fn close_device(&mut self, index: usize) {
+        let device = self.devices.remove(index);
+
+        unsafe {
+            epoll_ctl(self.epoll, EPOLL_CTL_DEL, device.fd, std::ptr::null_mut());
+            close(device.fd);
+        }
+
+        self.queue.push_back((device.gamepad, GamepadUpdate::Disconnected));
+    }
+
+    fn read_device(&mut self, index: usize) {
+        let mut buffer = [0u8; EvdevEvent::SIZE * 64];
+
+        loop {
+            let device = &mut self.devices[index];
+
+            let count = unsafe { read(device.fd, buffer.as_mut_ptr().cast(), buffer.len()) };
+
+            if count < 0 {
+                let error = Error::last_os_error();
+                if error.raw_os_error() == Some(ENODEV) {
+                    self.close_device(index);
+                } else if error.raw_os_error() != Some(EAGAIN) {
+                    log_warn!("Exposed", "Failed to read gamepad: {error}");
+                }
+                return;
+            }
+
+            if count == 0 {
+                return;
+            }
+
+            for event in EvdevEvent::parse_stream(&buffer[..count as usize]) {
+                device.mapper.process(event, &mut self.events);
+            }
+
+            for event in self.events.drain(..) {
+                self.queue.push_back((device.gamepad, GamepadUpdate::Event(event)));
+            }
+        }
+    }
+
+    fn read_inotify(&mut self) {
+        // struct inotify_event { int wd; uint32_t mask; uint32_t cookie; uint32_t len; char name[]; }
+        const HEADER: usize = 16;
+
+        let mut buffer = [0u8; 4096];
+
+        loop {
+            let count = unsafe { read(self.inotify, buffer.as_mut_ptr().cast(), buffer.len()) };
+            if count <= 0 {
+                return;
+            }
+
+            let mut offset = 0;
+            while offset + HEADER <= count as usize {
+                let header = &buffer[offset..offset + HEADER];
+                let mask = u32::from_ne_bytes([header[4], header[5], header[6], header[7]]);
+                let len = u32::from_ne_bytes([header[12], header[13], header[14], header[15]]) as usize;
+
+                let name = &buffer[offset + HEADER..(offset + HEADER + len).min(count as usize)];
+                offset += HEADER + len;
+
+                let name = match CStr::from_bytes_until_nul(name).ok().and_then(|n| n.to_str().ok()) {
+                    Some(name) if name.starts_with("event") => name.to_string(),
+                    _ => continue,
+                };
+
+                if mask & IN_DELETE != 0 {
+                    let path = format!("{}/{name}", Self::INPUT_DIR);
+                    if let Some(index) = self.devices.iter().position(|d| d.path.as_bytes() == path.as_bytes()) {
+                        self.close_device(index);
+                    }
+                } else {
+                    self.open_device(&name);
+                }
+            }
+        }
+    }
+}
+
+impl Destroy for Gamepads {
+    fn destroy(&mut self) -> Result<(), Error> {
+        unsafe {
+            for device in self.devices.drain(..) {
+                close(device.fd);
+            }
+
+            if self.inotify != -1 {
+                close(self.inotify);
+                self.inotify = -1;
+            }
+        }
+
+        Ok(())
+    }
+}
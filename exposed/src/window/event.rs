@@ -1,4 +1,4 @@
-use super::{Context, Key, MouseButton, ScanCode, Touch, WindowHandle};
+use super::{Context, Gamepad, GamepadAxis, GamepadButton, Gesture, Key, MouseButton, ScanCode, TimerId, Touch, WindowHandle};
 
 #[allow(unused)]
 pub trait Event: Sized + 'static {
@@ -7,7 +7,7 @@ pub trait Event: Sized + 'static {
     fn render(&mut self, window: WindowHandle) {}
 
     #[inline]
-    #[cfg(target_os = "windows")]
+    #[cfg(all(target_os = "windows", not(feature = "headless")))]
     /// Validate dirty region of the window. Otherwise Windows sends the same message again.
     fn low_render(&mut self, window: WindowHandle) {
         use windows_sys::Win32::Graphics::Gdi::{BeginPaint, EndPaint};
@@ -23,7 +23,7 @@ pub trait Event: Sized + 'static {
     }
 
     #[inline]
-    #[cfg(not(target_os = "windows"))]
+    #[cfg(any(not(target_os = "windows"), feature = "headless"))]
     /// Use full for validating window in windows
     fn low_render(&mut self, window: WindowHandle) {
         self.render(window);
@@ -37,6 +37,9 @@ pub trait Event: Sized + 'static {
 
     fn show(&mut self, window: WindowHandle) {}
 
+    /// Other windows fully cover the window, or stopped covering it. Nothing drawn while occluded is visible.
+    fn occluded(&mut self, window: WindowHandle, occluded: bool) {}
+
     fn maximized(&mut self, window: WindowHandle) {}
 
     fn moved(&mut self, window: WindowHandle, x: i32, y: i32) {}
@@ -71,10 +74,28 @@ pub trait Event: Sized + 'static {
 
     fn touch_end(&mut self, window: WindowHandle) {}
 
-    fn axis_motion() {}
+    fn gesture_begin(&mut self, window: WindowHandle, gesture: Gesture) {}
+
+    fn gesture_update(&mut self, window: WindowHandle, gesture: Gesture) {}
+
+    fn gesture_end(&mut self, window: WindowHandle, gesture: Gesture) {}
+
+    fn gamepad_connected(&mut self, gamepad: Gamepad, name: String) {}
+
+    fn gamepad_disconnected(&mut self, gamepad: Gamepad) {}
+
+    fn gamepad_button_down(&mut self, gamepad: Gamepad, button: GamepadButton) {}
+
+    fn gamepad_button_up(&mut self, gamepad: Gamepad, button: GamepadButton) {}
+
+    /// Sticks range from `-1.0` to `1.0`, triggers from `0.0` to `1.0`.
+    fn axis_motion(&mut self, gamepad: Gamepad, axis: GamepadAxis, value: f32) {}
 
     fn scale_factor_changed() {}
 
+    /// `timer` of `Context::set_timer` is due.
+    fn timer_fired(&mut self, timer: TimerId) {}
+
     // ─── HELPER ─────────────────────────────────────────────────────────────────────
     // ────────────────────────────────────────────────────────────────────────────────
 
@@ -1,12 +1,24 @@
-use std::io::Error;
+use std::{mem::MaybeUninit, ptr::NonNull};
 
-use crate::destroy::Destroy;
+use exposed_macro::log_error;
 
-use super::{platform, Event};
+use crate::{
+    destroy::{report_leaks, Destroy},
+    Error,
+};
+
+use super::{platform, resume_panic, Event};
 
 #[derive(Debug)]
 /// Provides a way to control event loop in a platform compatible way.
-pub struct EventHandler<E: Event>(pub platform::EventHandler<E>);
+///
+/// Owns the `E` it was built with, `E` is dropped before the platform so it can still destroy its windows.
+/// Windows alive after `E` is dropped are reported when resources are tracked, see `destroy::track`.
+/// Panics of callbacks continue from the method that called them.
+pub struct EventHandler<E: Event> {
+    user_data: NonNull<E>,
+    pub(crate) event_handler: platform::EventHandler<E>,
+}
 
 impl<E: Event> EventHandler<E> {
     /// Returns 0 if not message is available.
@@ -17,7 +29,9 @@ impl<E: Event> EventHandler<E> {
     /// - `XCheckIfEvent` in X11
     /// - `ALooper_poolAll` with timeout 0 in Android
     pub fn poll(&mut self) -> i32 {
-        self.0.poll()
+        let result = self.event_handler.poll();
+        resume_panic();
+        result
     }
 
     /// Return value is ignored.
@@ -27,27 +41,101 @@ impl<E: Event> EventHandler<E> {
     /// - `XNextEvent` in X11
     /// - `ALooper_poolAll` with timeout negative in Android
     pub fn wait(&mut self) -> i32 {
-        self.0.wait()
+        let result = self.event_handler.wait();
+        resume_panic();
+        result
     }
 
     pub fn dispatch(&mut self) {
-        self.0.dispatch()
+        self.event_handler.dispatch();
+        resume_panic();
+    }
+
+    pub fn user_data(&self) -> &E {
+        unsafe { self.user_data.as_ref() }
+    }
+
+    pub fn user_data_mut(&mut self) -> &mut E {
+        unsafe { self.user_data.as_mut() }
+    }
+
+    /// Queues an input or window manager event for the next `dispatch`, windows it changes are updated before the callback.
+    #[cfg(feature = "headless")]
+    pub fn inject(&mut self, event: super::InjectedEvent) {
+        self.event_handler.inject(event)
     }
 }
 
-impl<E: Event> Destroy for EventHandler<E> {
-    fn destroy(&mut self) -> Result<(), std::io::Error> {
-        self.0.destroy()
+impl<E: Event> Drop for EventHandler<E> {
+    fn drop(&mut self) {
+        // Window procedures called while `E` is dropped must not see it.
+        #[cfg(all(target_os = "windows", not(feature = "headless")))]
+        unsafe {
+            platform::ThreadContext::get_ref().user_data = std::ptr::null_mut()
+        };
+
+        drop(unsafe { Box::from_raw(self.user_data.as_ptr()) });
+        report_leaks();
+
+        if let Err(e) = self.event_handler.destroy() {
+            log_error!("Exposed", "{e}")
+        }
+
+        resume_panic();
     }
 }
 
 #[derive(Debug, Default)]
 /// Provides a way to create `EventHandler` in a platform compatible way.
-pub struct EventHandlerBuilder(pub platform::EventHandlerBuilder);
+pub struct EventHandlerBuilder(pub(crate) platform::EventHandlerBuilder);
 
 impl EventHandlerBuilder {
-    #[inline]
-    pub unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<EventHandler<E>, Error> {
-        Ok(self.0.build(user_data)?.into())
+    /// Adds gamepad mappings in the format of SDL's `gamecontrollerdb.txt`.
+    /// Mappings in `SDL_GAMECONTROLLERCONFIG` environment variable are always loaded.
+    pub fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        self.0.with_gamepad_mappings(mappings);
+        self
+    }
+
+    /// Identifies the application to the desktop environment so it can group windows and match them to a `.desktop` file.
+    /// `class` should be the name of the `.desktop` file without the extension, empty values default to the executable name.
+    ///
+    /// Represents:
+    /// - `WM_CLASS` in X11, startup notification is completed when the first window is shown
+    /// - `SetCurrentProcessExplicitAppUserModelID` with `class` in Windows
+    pub fn with_app_id(&mut self, name: &str, class: &str) -> &mut Self {
+        self.0.with_app_id(name, class);
+        self
+    }
+
+    /// Chooses the display server, `EXPOSED_BACKEND` is read when it isn't set.
+    /// Without either, Wayland is preferred when `WAYLAND_DISPLAY` is set and the `wayland` feature is enabled.
+    #[cfg(all(target_os = "linux", not(feature = "headless")))]
+    pub fn with_backend(&mut self, backend: super::Backend) -> &mut Self {
+        self.0.with_backend(backend);
+        self
+    }
+
+    /// Creates `E` with `Event::create` inside the new event handler.
+    /// Nothing is left behind if it fails or `Event::create` returns `None`, which is `Error::CreateFailed`.
+    /// A thread has one event handler at a time, `Error::EventHandlerExists` until the previous one is dropped.
+    pub fn build<E: Event>(&mut self) -> Result<EventHandler<E>, Error> {
+        let mut user_data = Box::new(MaybeUninit::<E>::uninit());
+
+        // Platforms write `E` only when they succeed.
+        let event_handler = unsafe { self.0.build(user_data.as_mut_ptr()) };
+        let event_handler = match event_handler {
+            Ok(event_handler) => event_handler,
+            Err(e) => {
+                resume_panic();
+                return Err(e);
+            }
+        };
+
+        let user_data = unsafe { NonNull::new_unchecked(Box::into_raw(user_data).cast::<E>()) };
+        let event_handler = EventHandler { user_data, event_handler };
+        resume_panic();
+
+        Ok(event_handler)
     }
 }
@@ -0,0 +1,83 @@
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+/// Callbacks a window subscribes to with `WindowBuilder::with_events`, windows get `EventMask::DEFAULT` otherwise.
+///
+/// X11 is the only platform that selects events per window, the others deliver every event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventMask(pub u32);
+
+impl EventMask {
+    pub const NONE: Self = Self(0);
+    /// `key_down`, `key_up` and `received_character`.
+    pub const KEYBOARD: Self = Self(1);
+    /// `focused`.
+    pub const FOCUS: Self = Self(1 << 1);
+    /// `mouse_button_down` and `mouse_button_release`.
+    pub const BUTTONS: Self = Self(1 << 2);
+    /// `cursor_moved` for every motion of the cursor over the window.
+    pub const POINTER_MOTION: Self = Self(1 << 3);
+    /// `cursor_entered` and `cursor_left`.
+    pub const CROSSING: Self = Self(1 << 4);
+    /// `resized`, `moved`, `show` and `minimized`.
+    pub const STRUCTURE: Self = Self(1 << 5);
+    /// `render`, `WindowHandle::redraw` needs it too.
+    pub const EXPOSURE: Self = Self(1 << 6);
+    /// `occluded`.
+    pub const VISIBILITY: Self = Self(1 << 7);
+    /// `maximized`, X11 only reads the window manager state when a window's `_NET_WM_STATE` changes.
+    pub const PROPERTY: Self = Self(1 << 8);
+
+    /// Every callback.
+    pub const DEFAULT: Self = Self(
+        Self::KEYBOARD.0
+            | Self::FOCUS.0
+            | Self::BUTTONS.0
+            | Self::POINTER_MOTION.0
+            | Self::CROSSING.0
+            | Self::STRUCTURE.0
+            | Self::EXPOSURE.0
+            | Self::VISIBILITY.0
+            | Self::PROPERTY.0,
+    );
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl Default for EventMask {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl BitOr for EventMask {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for EventMask {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0
+    }
+}
+
+impl BitAnd for EventMask {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+/// `EventMask::DEFAULT & !EventMask::POINTER_MOTION` opts out of motion.
+impl Not for EventMask {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
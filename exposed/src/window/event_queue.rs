@@ -0,0 +1,280 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+use super::{
+    Context, Event, EventHandler, Gamepad, GamepadAxis, GamepadButton, Gesture, Key, MouseButton, ScanCode, TimerId, Touch,
+    WindowHandle,
+};
+
+thread_local! {
+    static QUEUE: RefCell<VecDeque<WindowEvent>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Callback of `Event` with its arguments, returned by `EventHandler::next_event`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WindowEvent {
+    Render(WindowHandle),
+    Resized(WindowHandle, i32, i32),
+    Focused(WindowHandle, bool),
+    Minimized(WindowHandle),
+    Show(WindowHandle),
+    Occluded(WindowHandle, bool),
+    Maximized(WindowHandle),
+    Moved(WindowHandle, i32, i32),
+    FileReceived(WindowHandle, String),
+    CloseRequested(WindowHandle),
+    Destroyed(WindowHandle),
+    KeyDown(WindowHandle, Key, ScanCode),
+    KeyUp(WindowHandle, Key, ScanCode),
+    ReceivedCharacter(WindowHandle, char),
+    MouseWheel(WindowHandle, f32, f32),
+    MouseButtonDown(WindowHandle, MouseButton),
+    MouseButtonRelease(WindowHandle, MouseButton),
+    CursorMoved(WindowHandle, i32, i32),
+    CursorEntered(WindowHandle),
+    CursorLeft(WindowHandle),
+    RawMouseMotion(i32, i32),
+    Touch(WindowHandle, Touch, usize),
+    TouchEnd(WindowHandle),
+    GestureBegin(WindowHandle, Gesture),
+    GestureUpdate(WindowHandle, Gesture),
+    GestureEnd(WindowHandle, Gesture),
+    GamepadConnected(Gamepad, String),
+    GamepadDisconnected(Gamepad),
+    GamepadButtonDown(Gamepad, GamepadButton),
+    GamepadButtonUp(Gamepad, GamepadButton),
+    AxisMotion(Gamepad, GamepadAxis, f32),
+    ScaleFactorChanged,
+    TimerFired(TimerId),
+}
+
+impl WindowEvent {
+    /// Calls the matching callback of `app`, so parts written against `Event` can be fed from the queue.
+    pub fn dispatch<E: Event>(self, app: &mut E) {
+        use WindowEvent::*;
+
+        match self {
+            Render(w) => app.render(w),
+            Resized(w, width, height) => app.resized(w, width, height),
+            Focused(w, focused) => app.focused(w, focused),
+            Minimized(w) => app.minimized(w),
+            Show(w) => app.show(w),
+            Occluded(w, occluded) => app.occluded(w, occluded),
+            Maximized(w) => app.maximized(w),
+            Moved(w, x, y) => app.moved(w, x, y),
+            FileReceived(w, path) => app.file_received(w, path),
+            CloseRequested(w) => app.close_requested(w),
+            Destroyed(w) => app.destroyed(w),
+            KeyDown(w, key, scancode) => app.key_down(w, key, scancode),
+            KeyUp(w, key, scancode) => app.key_up(w, key, scancode),
+            ReceivedCharacter(w, character) => app.received_character(w, character),
+            MouseWheel(w, delta_x, delta_y) => app.mouse_wheel(w, delta_x, delta_y),
+            MouseButtonDown(w, button) => app.mouse_button_down(w, button),
+            MouseButtonRelease(w, button) => app.mouse_button_release(w, button),
+            CursorMoved(w, x, y) => app.cursor_moved(w, x, y),
+            CursorEntered(w) => app.cursor_entered(w),
+            CursorLeft(w) => app.cursor_left(w),
+            RawMouseMotion(delta_x, delta_y) => app.raw_mouse_motion(delta_x, delta_y),
+            Touch(w, touch, pointer_count) => app.touch(w, touch, pointer_count),
+            TouchEnd(w) => app.touch_end(w),
+            GestureBegin(w, gesture) => app.gesture_begin(w, gesture),
+            GestureUpdate(w, gesture) => app.gesture_update(w, gesture),
+            GestureEnd(w, gesture) => app.gesture_end(w, gesture),
+            GamepadConnected(gamepad, name) => app.gamepad_connected(gamepad, name),
+            GamepadDisconnected(gamepad) => app.gamepad_disconnected(gamepad),
+            GamepadButtonDown(gamepad, button) => app.gamepad_button_down(gamepad, button),
+            GamepadButtonUp(gamepad, button) => app.gamepad_button_up(gamepad, button),
+            AxisMotion(gamepad, axis, value) => app.axis_motion(gamepad, axis, value),
+            ScaleFactorChanged => E::scale_factor_changed(),
+            TimerFired(timer) => app.timer_fired(timer),
+        }
+    }
+}
+
+/// `Event` implementation that buffers every callback as a `WindowEvent`, for applications that pull events instead.
+///
+/// Build the event handler with it, for example with `EventHandlerBuilder::build::<EventQueue>`, then create windows with
+/// `context` and read events with `EventHandler::next_event`.
+/// The queue belongs to the thread, like the event handler.
+#[derive(Debug, Clone, Copy)]
+pub struct EventQueue {
+    pub context: Context,
+}
+
+impl EventQueue {
+    fn push(event: WindowEvent) {
+        QUEUE.with(|q| q.borrow_mut().push_back(event));
+    }
+
+    fn pop() -> Option<WindowEvent> {
+        QUEUE.with(|q| q.borrow_mut().pop_front())
+    }
+
+    fn is_empty() -> bool {
+        QUEUE.with(|q| q.borrow().is_empty())
+    }
+}
+
+impl EventHandler<EventQueue> {
+    /// Returns the next buffered event, dispatches pending platform events first if there is none. Never blocks.
+    pub fn next_event(&mut self) -> Option<WindowEvent> {
+        while EventQueue::is_empty() {
+            if self.poll() == 0 {
+                return None;
+            }
+            self.dispatch();
+        }
+
+        EventQueue::pop()
+    }
+
+    /// Like `next_event` but waits for the platform until an event arrives.
+    pub fn wait_event(&mut self) -> WindowEvent {
+        loop {
+            if let Some(event) = EventQueue::pop() {
+                return event;
+            }
+
+            self.wait();
+            self.dispatch();
+        }
+    }
+}
+
+impl Event for EventQueue {
+    fn create(context: Context) -> Option<Self> {
+        QUEUE.with(|q| q.borrow_mut().clear());
+        Some(Self { context })
+    }
+
+    fn render(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::Render(window))
+    }
+
+    fn resized(&mut self, window: WindowHandle, width: i32, height: i32) {
+        Self::push(WindowEvent::Resized(window, width, height))
+    }
+
+    fn focused(&mut self, window: WindowHandle, focused: bool) {
+        Self::push(WindowEvent::Focused(window, focused))
+    }
+
+    fn minimized(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::Minimized(window))
+    }
+
+    fn show(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::Show(window))
+    }
+
+    fn occluded(&mut self, window: WindowHandle, occluded: bool) {
+        Self::push(WindowEvent::Occluded(window, occluded))
+    }
+
+    fn maximized(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::Maximized(window))
+    }
+
+    fn moved(&mut self, window: WindowHandle, x: i32, y: i32) {
+        Self::push(WindowEvent::Moved(window, x, y))
+    }
+
+    fn file_received(&mut self, window: WindowHandle, path: String) {
+        Self::push(WindowEvent::FileReceived(window, path))
+    }
+
+    fn close_requested(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::CloseRequested(window))
+    }
+
+    fn destroyed(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::Destroyed(window))
+    }
+
+    fn key_down(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        Self::push(WindowEvent::KeyDown(window, key, scancode))
+    }
+
+    fn key_up(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        Self::push(WindowEvent::KeyUp(window, key, scancode))
+    }
+
+    fn received_character(&mut self, window: WindowHandle, character: char) {
+        Self::push(WindowEvent::ReceivedCharacter(window, character))
+    }
+
+    fn mouse_wheel(&mut self, window: WindowHandle, delta_x: f32, delta_y: f32) {
+        Self::push(WindowEvent::MouseWheel(window, delta_x, delta_y))
+    }
+
+    fn mouse_button_down(&mut self, window: WindowHandle, button: MouseButton) {
+        Self::push(WindowEvent::MouseButtonDown(window, button))
+    }
+
+    fn mouse_button_release(&mut self, window: WindowHandle, button: MouseButton) {
+        Self::push(WindowEvent::MouseButtonRelease(window, button))
+    }
+
+    fn cursor_moved(&mut self, window: WindowHandle, position_x: i32, position_y: i32) {
+        Self::push(WindowEvent::CursorMoved(window, position_x, position_y))
+    }
+
+    fn cursor_entered(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::CursorEntered(window))
+    }
+
+    fn cursor_left(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::CursorLeft(window))
+    }
+
+    fn raw_mouse_motion(&mut self, delta_x: i32, delta_y: i32) {
+        Self::push(WindowEvent::RawMouseMotion(delta_x, delta_y))
+    }
+
+    fn touch(&mut self, window: WindowHandle, touch: Touch, pointer_count: usize) {
+        Self::push(WindowEvent::Touch(window, touch, pointer_count))
+    }
+
+    fn touch_end(&mut self, window: WindowHandle) {
+        Self::push(WindowEvent::TouchEnd(window))
+    }
+
+    fn gesture_begin(&mut self, window: WindowHandle, gesture: Gesture) {
+        Self::push(WindowEvent::GestureBegin(window, gesture))
+    }
+
+    fn gesture_update(&mut self, window: WindowHandle, gesture: Gesture) {
+        Self::push(WindowEvent::GestureUpdate(window, gesture))
+    }
+
+    fn gesture_end(&mut self, window: WindowHandle, gesture: Gesture) {
+        Self::push(WindowEvent::GestureEnd(window, gesture))
+    }
+
+    fn gamepad_connected(&mut self, gamepad: Gamepad, name: String) {
+        Self::push(WindowEvent::GamepadConnected(gamepad, name))
+    }
+
+    fn gamepad_disconnected(&mut self, gamepad: Gamepad) {
+        Self::push(WindowEvent::GamepadDisconnected(gamepad))
+    }
+
+    fn gamepad_button_down(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        Self::push(WindowEvent::GamepadButtonDown(gamepad, button))
+    }
+
+    fn gamepad_button_up(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        Self::push(WindowEvent::GamepadButtonUp(gamepad, button))
+    }
+
+    fn axis_motion(&mut self, gamepad: Gamepad, axis: GamepadAxis, value: f32) {
+        Self::push(WindowEvent::AxisMotion(gamepad, axis, value))
+    }
+
+    fn scale_factor_changed() {
+        Self::push(WindowEvent::ScaleFactorChanged)
+    }
+
+    fn timer_fired(&mut self, timer: TimerId) {
+        Self::push(WindowEvent::TimerFired(timer))
+    }
+}
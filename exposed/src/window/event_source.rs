@@ -0,0 +1,92 @@
+//! Driving `EventHandler` from other event loops through its file descriptor.
+//!
+//! The descriptor is an epoll that becomes readable when the display connection or a gamepad has input.
+//! Xlib reads events into its own queue, so always drain with `poll` and `dispatch` before waiting on it again.
+
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd};
+
+use super::{Event, EventHandler};
+
+impl<E: Event> AsRawFd for EventHandler<E> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.event_handler.as_raw_fd()
+    }
+}
+
+impl<E: Event> AsFd for EventHandler<E> {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.event_handler.as_fd()
+    }
+}
+
+impl<E: Event> EventHandler<E> {
+    /// Dispatches every pending event, returns how many were dispatched.
+    pub fn dispatch_pending(&mut self) -> usize {
+        let mut count = 0;
+
+        while self.poll() > 0 {
+            self.dispatch();
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Waits on the file descriptor with tokio's reactor, then dispatches the pending events.
+    /// Must be called inside a tokio runtime with IO enabled.
+    #[cfg(feature = "tokio")]
+    pub async fn next(&mut self) -> std::io::Result<()> {
+        use tokio::io::{unix::AsyncFd, Interest};
+
+        if self.dispatch_pending() > 0 {
+            return Ok(());
+        }
+
+        // The epoll lives as long as `self`, which is borrowed until the `AsyncFd` is dropped.
+        let fd =
+            unsafe { AsyncFd::register_with_interest(self.as_raw_fd(), Interest::READABLE) }.map_err(|e| e.into_parts().1)?;
+
+        loop {
+            let mut guard = fd.readable().await?;
+            guard.clear_ready();
+
+            if self.dispatch_pending() > 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Dispatches the pending events whenever the file descriptor is readable, the callback is called after each batch.
+#[cfg(feature = "calloop")]
+impl<E: Event> calloop::EventSource for EventHandler<E> {
+    type Event = ();
+    type Metadata = ();
+    type Ret = ();
+    type Error = std::io::Error;
+
+    fn process_events<F>(
+        &mut self, _readiness: calloop::Readiness, _token: calloop::Token, mut callback: F,
+    ) -> Result<calloop::PostAction, Self::Error>
+    where
+        F: FnMut(Self::Event, &mut Self::Metadata) -> Self::Ret,
+    {
+        if self.dispatch_pending() > 0 {
+            callback((), &mut ());
+        }
+
+        Ok(calloop::PostAction::Continue)
+    }
+
+    fn register(&mut self, poll: &mut calloop::Poll, token_factory: &mut calloop::TokenFactory) -> calloop::Result<()> {
+        unsafe { poll.register(self.as_fd(), calloop::Interest::READ, calloop::Mode::Level, token_factory.token()) }
+    }
+
+    fn reregister(&mut self, poll: &mut calloop::Poll, token_factory: &mut calloop::TokenFactory) -> calloop::Result<()> {
+        poll.reregister(self.as_fd(), calloop::Interest::READ, calloop::Mode::Level, token_factory.token())
+    }
+
+    fn unregister(&mut self, poll: &mut calloop::Poll) -> calloop::Result<()> {
+        poll.unregister(self.as_fd())
+    }
+}
@@ -0,0 +1,744 @@
+//! Gamepad mapping shared by every backend that reads Linux evdev devices.
+//!
+//! Mappings use the format of SDL's `gamecontrollerdb.txt`, so community databases can be fed directly to
+//! `EventHandlerBuilder::with_gamepad_mappings`.
+
+use std::collections::HashMap;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Gamepad(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Misc1,
+    Paddle1,
+    Paddle2,
+    Paddle3,
+    Paddle4,
+    Touchpad,
+}
+
+impl GamepadButton {
+    pub const COUNT: usize = 21;
+
+    /// Every button, `ALL[button as usize] == button`.
+    pub const ALL: [Self; Self::COUNT] = {
+        use GamepadButton::*;
+        [
+            A,
+            B,
+            X,
+            Y,
+            Back,
+            Guide,
+            Start,
+            LeftStick,
+            RightStick,
+            LeftShoulder,
+            RightShoulder,
+            DPadUp,
+            DPadDown,
+            DPadLeft,
+            DPadRight,
+            Misc1,
+            Paddle1,
+            Paddle2,
+            Paddle3,
+            Paddle4,
+            Touchpad,
+        ]
+    };
+
+    pub fn from_sdl_name(name: &str) -> Option<Self> {
+        use GamepadButton::*;
+
+        Some(match name {
+            "a" => A,
+            "b" => B,
+            "x" => X,
+            "y" => Y,
+            "back" => Back,
+            "guide" => Guide,
+            "start" => Start,
+            "leftstick" => LeftStick,
+            "rightstick" => RightStick,
+            "leftshoulder" => LeftShoulder,
+            "rightshoulder" => RightShoulder,
+            "dpup" => DPadUp,
+            "dpdown" => DPadDown,
+            "dpleft" => DPadLeft,
+            "dpright" => DPadRight,
+            "misc1" => Misc1,
+            "paddle1" => Paddle1,
+            "paddle2" => Paddle2,
+            "paddle3" => Paddle3,
+            "paddle4" => Paddle4,
+            "touchpad" => Touchpad,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GamepadAxis {
+    /// `-1.0` is left.
+    LeftX,
+    /// `-1.0` is up.
+    LeftY,
+    RightX,
+    RightY,
+    /// Ranges from `0.0` to `1.0`.
+    LeftTrigger,
+    RightTrigger,
+}
+
+impl GamepadAxis {
+    pub const COUNT: usize = 6;
+
+    /// Every axis, `ALL[axis as usize] == axis`.
+    pub const ALL: [Self; Self::COUNT] = {
+        use GamepadAxis::*;
+        [LeftX, LeftY, RightX, RightY, LeftTrigger, RightTrigger]
+    };
+
+    pub fn from_sdl_name(name: &str) -> Option<Self> {
+        use GamepadAxis::*;
+
+        Some(match name {
+            "leftx" => LeftX,
+            "lefty" => LeftY,
+            "rightx" => RightX,
+            "righty" => RightY,
+            "lefttrigger" => LeftTrigger,
+            "righttrigger" => RightTrigger,
+            _ => return None,
+        })
+    }
+
+    pub fn is_trigger(self) -> bool {
+        matches!(self, Self::LeftTrigger | Self::RightTrigger)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    Button(GamepadButton, bool),
+    Axis(GamepadAxis, f32),
+}
+
+// ─── EVDEV ──────────────────────────────────────────────────────────────────────
+// ────────────────────────────────────────────────────────────────────────────────
+
+pub const EV_SYN: u16 = 0x00;
+pub const EV_KEY: u16 = 0x01;
+pub const EV_ABS: u16 = 0x03;
+
+pub const BTN_MISC: u16 = 0x100;
+pub const BTN_JOYSTICK: u16 = 0x120;
+pub const BTN_GAMEPAD: u16 = 0x130;
+pub const KEY_MAX: u16 = 0x2ff;
+
+pub const ABS_HAT0X: u16 = 0x10;
+pub const ABS_HAT3Y: u16 = 0x17;
+pub const ABS_MAX: u16 = 0x3f;
+
+/// `struct input_event` without its timestamp.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvdevEvent {
+    pub kind: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+impl EvdevEvent {
+    /// Size of `struct input_event`, a `struct timeval` followed by the event.
+    pub const SIZE: usize = 2 * std::mem::size_of::<usize>() + 8;
+
+    /// Parses a stream of `struct input_event` as read from `/dev/input/event*`. A trailing partial event is ignored.
+    pub fn parse_stream(bytes: &[u8]) -> impl Iterator<Item = EvdevEvent> + '_ {
+        bytes.chunks_exact(Self::SIZE).map(|e| {
+            let e = &e[Self::SIZE - 8..];
+            EvdevEvent {
+                kind: u16::from_ne_bytes([e[0], e[1]]),
+                code: u16::from_ne_bytes([e[2], e[3]]),
+                value: i32::from_ne_bytes([e[4], e[5], e[6], e[7]]),
+            }
+        })
+    }
+}
+
+/// `struct input_absinfo`
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AbsInfo {
+    pub value: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub fuzz: i32,
+    pub flat: i32,
+    pub resolution: i32,
+}
+
+impl AbsInfo {
+    fn normalize(&self, value: i32) -> f32 {
+        let range = self.maximum as f32 - self.minimum as f32;
+        if range <= 0.0 {
+            return 0.0;
+        }
+
+        let center = (self.maximum as f32 + self.minimum as f32) / 2.0;
+        if self.flat != 0 && (value as f32 - center).abs() <= self.flat as f32 {
+            return 0.0;
+        }
+
+        ((value as f32 - self.minimum as f32) / range * 2.0 - 1.0).clamp(-1.0, 1.0)
+    }
+}
+
+/// Numbers buttons, axes and hats of a device the same way SDL does, which is what mapping strings refer to.
+#[derive(Debug, Default, Clone)]
+pub struct EvdevLayout {
+    pub buttons: Vec<u16>,
+    pub axes: Vec<(u16, AbsInfo)>,
+    /// `ABS_HAT0X` + 2 * hat.
+    pub hats: Vec<u16>,
+}
+
+impl EvdevLayout {
+    pub fn new(keys: &[u16], abs: &[(u16, AbsInfo)]) -> Self {
+        let mut buttons: Vec<u16> = keys.iter().copied().filter(|k| (BTN_JOYSTICK..KEY_MAX).contains(k)).collect();
+        let mut misc: Vec<u16> = keys.iter().copied().filter(|k| *k < BTN_JOYSTICK).collect();
+        buttons.sort_unstable();
+        misc.sort_unstable();
+        buttons.append(&mut misc);
+
+        let mut axes: Vec<(u16, AbsInfo)> =
+            abs.iter().copied().filter(|(code, _)| *code < ABS_MAX && !(ABS_HAT0X..=ABS_HAT3Y).contains(code)).collect();
+        axes.sort_unstable_by_key(|(code, _)| *code);
+
+        let mut hats = Vec::new();
+        for hat in (ABS_HAT0X..=ABS_HAT3Y).step_by(2) {
+            if abs.iter().any(|(code, _)| *code == hat || *code == hat + 1) {
+                hats.push(hat);
+            }
+        }
+
+        Self { buttons, axes, hats }
+    }
+
+    pub fn button_index(&self, code: u16) -> Option<usize> {
+        self.buttons.iter().position(|b| *b == code)
+    }
+
+    pub fn axis_index(&self, code: u16) -> Option<usize> {
+        self.axes.iter().position(|(a, _)| *a == code)
+    }
+
+    pub fn hat_index(&self, code: u16) -> Option<usize> {
+        self.hats.iter().position(|h| *h == code & !1)
+    }
+
+    pub fn is_gamepad(&self) -> bool {
+        self.buttons.iter().any(|b| (BTN_JOYSTICK..BTN_GAMEPAD + 0x10).contains(b))
+    }
+}
+
+// ─── MAPPING ────────────────────────────────────────────────────────────────────
+// ────────────────────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AxisHalf {
+    #[default]
+    Full,
+    Positive,
+    Negative,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingInput {
+    Button(usize),
+    Axis { index: usize, half: AxisHalf, invert: bool },
+    /// `mask` is 1 for up, 2 for right, 4 for down and 8 for left.
+    Hat { index: usize, mask: u8 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingOutput {
+    Button(GamepadButton),
+    Axis { axis: GamepadAxis, half: AxisHalf },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Binding {
+    pub input: MappingInput,
+    pub output: MappingOutput,
+}
+
+/// One line of SDL's controller database.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GamepadMapping {
+    pub guid: String,
+    pub name: String,
+    pub bindings: Vec<Binding>,
+}
+
+impl GamepadMapping {
+    /// Parses `GUID,name,a:b0,leftx:a0,dpup:h0.1,...`. Lines for other platforms, comments and bindings this crate
+    /// doesn't know are skipped.
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut fields = line.split(',');
+        let guid = fields.next()?.trim().to_ascii_lowercase();
+        let name = fields.next()?.trim().to_string();
+
+        if guid.len() != 32 {
+            return None;
+        }
+
+        let mut bindings = Vec::new();
+
+        for field in fields {
+            let (target, source) = match field.split_once(':') {
+                Some(kv) => kv,
+                None => continue,
+            };
+
+            if target == "platform" {
+                if source != "Linux" {
+                    return None;
+                }
+                continue;
+            }
+
+            if let (Some(output), Some(input)) = (parse_output(target), parse_input(source)) {
+                bindings.push(Binding { input, output });
+            }
+        }
+
+        Some(Self { guid, name, bindings })
+    }
+
+    /// Layout of the Linux gamepad API (`Documentation/input/gamepad.rst`). Used when the database doesn't know the device.
+    pub fn kernel_default(layout: &EvdevLayout, name: &str) -> Self {
+        use GamepadAxis::*;
+        use GamepadButton::*;
+
+        const BUTTONS: [(u16, GamepadButton); 15] = [
+            (0x130, A),
+            (0x131, B),
+            (0x133, X),
+            (0x134, Y),
+            (0x136, LeftShoulder),
+            (0x137, RightShoulder),
+            (0x13a, Back),
+            (0x13b, Start),
+            (0x13c, Guide),
+            (0x13d, LeftStick),
+            (0x13e, RightStick),
+            (0x220, DPadUp),
+            (0x221, DPadDown),
+            (0x222, DPadLeft),
+            (0x223, DPadRight),
+        ];
+
+        const AXES: [(u16, GamepadAxis); 6] =
+            [(0x00, LeftX), (0x01, LeftY), (0x03, RightX), (0x04, RightY), (0x02, LeftTrigger), (0x05, RightTrigger)];
+
+        let mut bindings = Vec::new();
+
+        for (code, button) in BUTTONS {
+            if let Some(index) = layout.button_index(code) {
+                bindings.push(Binding { input: MappingInput::Button(index), output: MappingOutput::Button(button) });
+            }
+        }
+
+        for (code, axis) in AXES {
+            if let Some(index) = layout.axis_index(code) {
+                bindings.push(Binding {
+                    input: MappingInput::Axis { index, half: AxisHalf::Full, invert: false },
+                    output: MappingOutput::Axis { axis, half: AxisHalf::Full },
+                });
+            }
+        }
+
+        // Digital triggers, BTN_TL2 and BTN_TR2
+        for (code, axis) in [(0x138, LeftTrigger), (0x139, RightTrigger)] {
+            if bindings.iter().any(|b| matches!(b.output, MappingOutput::Axis { axis: a, .. } if a == axis)) {
+                continue;
+            }
+
+            if let Some(index) = layout.button_index(code) {
+                bindings.push(Binding {
+                    input: MappingInput::Button(index),
+                    output: MappingOutput::Axis { axis, half: AxisHalf::Full },
+                });
+            }
+        }
+
+        if !layout.hats.is_empty() {
+            for (mask, button) in [(1, DPadUp), (2, DPadRight), (4, DPadDown), (8, DPadLeft)] {
+                bindings.push(Binding { input: MappingInput::Hat { index: 0, mask }, output: MappingOutput::Button(button) });
+            }
+        }
+
+        Self { guid: String::new(), name: name.to_string(), bindings }
+    }
+}
+
+fn parse_output(target: &str) -> Option<MappingOutput> {
+    let (half, target) = match target.as_bytes().first() {
+        Some(b'+') => (AxisHalf::Positive, &target[1..]),
+        Some(b'-') => (AxisHalf::Negative, &target[1..]),
+        _ => (AxisHalf::Full, target),
+    };
+
+    if let Some(axis) = GamepadAxis::from_sdl_name(target) {
+        return Some(MappingOutput::Axis { axis, half });
+    }
+
+    GamepadButton::from_sdl_name(target).map(MappingOutput::Button)
+}
+
+fn parse_input(source: &str) -> Option<MappingInput> {
+    let (half, source) = match source.as_bytes().first() {
+        Some(b'+') => (AxisHalf::Positive, &source[1..]),
+        Some(b'-') => (AxisHalf::Negative, &source[1..]),
+        _ => (AxisHalf::Full, source),
+    };
+
+    let (invert, source) = match source.strip_suffix('~') {
+        Some(source) => (true, source),
+        None => (false, source),
+    };
+
+    match source.as_bytes().first()? {
+        b'b' => Some(MappingInput::Button(source[1..].parse().ok()?)),
+        b'a' => Some(MappingInput::Axis { index: source[1..].parse().ok()?, half, invert }),
+        b'h' => {
+            let (index, mask) = source[1..].split_once('.')?;
+            Some(MappingInput::Hat { index: index.parse().ok()?, mask: mask.parse().ok()? })
+        }
+        _ => None,
+    }
+}
+
+/// Mappings keyed by SDL GUID without the CRC.
+#[derive(Debug, Default, Clone)]
+pub struct GamepadMappings {
+    pub mappings: HashMap<String, GamepadMapping>,
+}
+
+impl GamepadMappings {
+    /// Adds every Linux mapping in `database`. Later mappings replace earlier ones with the same GUID.
+    pub fn add(&mut self, database: &str) {
+        for mapping in database.lines().filter_map(GamepadMapping::parse) {
+            self.mappings.insert(without_crc(&mapping.guid), mapping);
+        }
+    }
+
+    /// Finds mapping of `guid`. SDL writes a CRC of the device name into bytes 2 and 3 since 2.26, older databases and
+    /// `sdl_guid` have zeros there so the CRC is ignored on both sides.
+    pub fn find(&self, guid: &str) -> Option<&GamepadMapping> {
+        self.mappings.get(&without_crc(guid))
+    }
+}
+
+/// `guid` with zeros in place of the name CRC.
+fn without_crc(guid: &str) -> String {
+    match (guid.get(..4), guid.get(8..)) {
+        (Some(bus), Some(rest)) => format!("{bus}0000{rest}"),
+        _ => guid.to_string(),
+    }
+}
+
+/// SDL GUID of an evdev device from `struct input_id`.
+pub fn sdl_guid(bus: u16, vendor: u16, product: u16, version: u16) -> String {
+    let mut guid = String::with_capacity(32);
+
+    for word in [bus, 0, vendor, 0, product, 0, version, 0] {
+        for byte in word.to_le_bytes() {
+            guid.push_str(&format!("{byte:02x}"));
+        }
+    }
+
+    guid
+}
+
+/// Turns evdev events of one device into gamepad events.
+#[derive(Debug, Clone)]
+pub struct GamepadMapper {
+    pub layout: EvdevLayout,
+    pub mapping: GamepadMapping,
+    hats: Vec<u8>,
+    axis_values: Vec<f32>,
+    buttons: [bool; GamepadButton::COUNT],
+    axes: [f32; GamepadAxis::COUNT],
+}
+
+impl GamepadMapper {
+    pub fn new(layout: EvdevLayout, mapping: GamepadMapping) -> Self {
+        Self {
+            hats: vec![0; layout.hats.len()],
+            axis_values: vec![0.0; layout.axes.len()],
+            layout,
+            mapping,
+            buttons: [false; GamepadButton::COUNT],
+            axes: [0.0; GamepadAxis::COUNT],
+        }
+    }
+
+    pub fn process(&mut self, event: EvdevEvent, events: &mut Vec<GamepadEvent>) {
+        match event.kind {
+            EV_KEY => {
+                let index = match self.layout.button_index(event.code) {
+                    Some(index) => index,
+                    None => return,
+                };
+
+                // 2 is autorepeat
+                if event.value == 2 {
+                    return;
+                }
+
+                let value = if event.value != 0 { 1.0 } else { 0.0 };
+
+                for i in 0..self.mapping.bindings.len() {
+                    let binding = self.mapping.bindings[i];
+                    if binding.input == MappingInput::Button(index) {
+                        self.emit(binding.output, value, events);
+                    }
+                }
+            }
+
+            EV_ABS => {
+                if let Some(hat) = self.layout.hat_index(event.code) {
+                    self.process_hat(hat, event, events);
+                    return;
+                }
+
+                let index = match self.layout.axis_index(event.code) {
+                    Some(index) => index,
+                    None => return,
+                };
+
+                let value = self.layout.axes[index].1.normalize(event.value);
+                self.axis_values[index] = value;
+
+                for i in 0..self.mapping.bindings.len() {
+                    let binding = self.mapping.bindings[i];
+                    if let MappingInput::Axis { index: a, half, invert } = binding.input {
+                        if a != index {
+                            continue;
+                        }
+
+                        let value = if invert { -value } else { value };
+
+                        let value = match half {
+                            AxisHalf::Full => value,
+                            AxisHalf::Positive => value.max(0.0),
+                            AxisHalf::Negative => (-value).max(0.0),
+                        };
+
+                        let value = match (half, binding.output) {
+                            // Full range input driving a trigger goes from released at -1 to pressed at 1.
+                            (AxisHalf::Full, MappingOutput::Axis { axis, half: AxisHalf::Full }) if axis.is_trigger() => {
+                                (value + 1.0) / 2.0
+                            }
+                            _ => value,
+                        };
+
+                        self.emit(binding.output, value, events);
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn process_hat(&mut self, hat: usize, event: EvdevEvent, events: &mut Vec<GamepadEvent>) {
+        let old = self.hats[hat];
+
+        let is_x = event.code & 1 == 0;
+        let (negative, positive) = if is_x { (8, 2) } else { (1, 4) };
+
+        let mut new = old & !(negative | positive);
+        if event.value < 0 {
+            new |= negative;
+        } else if event.value > 0 {
+            new |= positive;
+        }
+
+        self.hats[hat] = new;
+
+        for i in 0..self.mapping.bindings.len() {
+            let binding = self.mapping.bindings[i];
+            if let MappingInput::Hat { index, mask } = binding.input {
+                if index == hat && (old ^ new) & mask != 0 {
+                    self.emit(binding.output, if new & mask != 0 { 1.0 } else { 0.0 }, events);
+                }
+            }
+        }
+    }
+
+    /// `value` is in `0.0..=1.0` unless the input is a full axis.
+    fn emit(&mut self, output: MappingOutput, value: f32, events: &mut Vec<GamepadEvent>) {
+        match output {
+            MappingOutput::Button(button) => {
+                let pressed = value.abs() > 0.5;
+                if self.buttons[button as usize] != pressed {
+                    self.buttons[button as usize] = pressed;
+                    events.push(GamepadEvent::Button(button, pressed));
+                }
+            }
+
+            MappingOutput::Axis { axis, half } => {
+                let value = match half {
+                    AxisHalf::Full => value,
+                    AxisHalf::Positive => value.abs(),
+                    AxisHalf::Negative => -value.abs(),
+                };
+
+                // Half bindings share the axis, a released half must not reset the other half.
+                if half != AxisHalf::Full && value == 0.0 && self.axes[axis as usize].signum() != half_sign(half) {
+                    return;
+                }
+
+                if self.axes[axis as usize] != value {
+                    self.axes[axis as usize] = value;
+                    events.push(GamepadEvent::Axis(axis, value));
+                }
+            }
+        }
+    }
+}
+
+fn half_sign(half: AxisHalf) -> f32 {
+    match half {
+        AxisHalf::Negative => -1.0,
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+fn record(events: &[(u16, u16, i32)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for (kind, code, value) in events {
+        bytes.extend_from_slice(&[0; EvdevEvent::SIZE - 8]);
+        bytes.extend_from_slice(&kind.to_ne_bytes());
+        bytes.extend_from_slice(&code.to_ne_bytes());
+        bytes.extend_from_slice(&value.to_ne_bytes());
+    }
+    bytes
+}
+
+#[test]
+fn gamepad_mapping_parse_test() {
+    let line = "030000005e0400008e02000014010000,Xbox 360 Controller,a:b0,b:b1,back:b6,dpdown:h0.4,dpleft:h0.8,\
+                dpright:h0.2,dpup:h0.1,guide:b8,leftshoulder:b4,leftstick:b9,lefttrigger:a2,leftx:a0,lefty:a1,\
+                rightshoulder:b5,rightstick:b10,righttrigger:a5,rightx:a3,righty:a4,start:b7,x:b2,y:b3,platform:Linux,";
+
+    let mapping = GamepadMapping::parse(line).unwrap();
+    assert_eq!(mapping.name, "Xbox 360 Controller");
+    assert_eq!(mapping.bindings.len(), 21);
+    assert!(mapping.bindings.contains(&Binding {
+        input: MappingInput::Hat { index: 0, mask: 8 },
+        output: MappingOutput::Button(GamepadButton::DPadLeft)
+    }));
+
+    assert!(GamepadMapping::parse(&line.replace("platform:Linux", "platform:Windows")).is_none());
+    assert!(GamepadMapping::parse("# comment").is_none());
+
+    let mut mappings = GamepadMappings::default();
+    mappings.add(line);
+    assert_eq!(sdl_guid(0x03, 0x045e, 0x028e, 0x0114), "030000005e0400008e02000014010000");
+    assert!(mappings.find("0300a1b25e0400008e02000014010000").is_some());
+
+    // SDL 2.26 and later databases carry the CRC of the name, devices are looked up without it.
+    mappings.add(&line.replace("030000005e0400008e02000014010000", "0300c3d45e040000a102000000010000"));
+    assert_eq!(mappings.find(&sdl_guid(0x03, 0x045e, 0x02a1, 0x0100)).unwrap().guid, "0300c3d45e040000a102000000010000");
+}
+
+#[test]
+fn gamepad_recorded_stream_test() {
+    let stick = AbsInfo { minimum: -32768, maximum: 32767, flat: 128, ..Default::default() };
+    let trigger = AbsInfo { minimum: 0, maximum: 255, ..Default::default() };
+    let hat = AbsInfo { minimum: -1, maximum: 1, ..Default::default() };
+
+    let layout = EvdevLayout::new(
+        &[0x130, 0x131, 0x133, 0x134, 0x136, 0x137, 0x13a, 0x13b, 0x13c, 0x13d, 0x13e],
+        &[(0x00, stick), (0x01, stick), (0x02, trigger), (0x03, stick), (0x04, stick), (0x05, trigger), (0x10, hat), (0x11, hat)],
+    );
+    assert!(layout.is_gamepad());
+    assert_eq!(layout.button_index(0x133), Some(2));
+    assert_eq!(layout.hats, [ABS_HAT0X]);
+
+    let line = "030000005e0400008e02000014010000,Xbox 360 Controller,a:b0,b:b1,x:b2,y:b3,dpup:h0.1,dpdown:h0.4,\
+                lefttrigger:a2,leftx:a0,lefty:a1,-rightx:b4,+rightx:b5,platform:Linux,";
+    let mut mapper = GamepadMapper::new(layout.clone(), GamepadMapping::parse(line).unwrap());
+
+    let stream = record(&[
+        (EV_KEY, 0x130, 1),
+        (EV_SYN, 0, 0),
+        (EV_KEY, 0x130, 2),
+        (EV_KEY, 0x130, 0),
+        (EV_ABS, 0x00, 32767),
+        (EV_ABS, 0x01, 50),
+        (EV_ABS, 0x02, 255),
+        (EV_ABS, 0x11, -1),
+        (EV_ABS, 0x11, 1),
+        (EV_ABS, 0x11, 0),
+        (EV_KEY, 0x136, 1),
+        (EV_KEY, 0x137, 1),
+        (EV_KEY, 0x136, 0),
+    ]);
+
+    let mut events = Vec::new();
+    for event in EvdevEvent::parse_stream(&stream) {
+        mapper.process(event, &mut events);
+    }
+
+    use GamepadEvent::*;
+    assert_eq!(
+        events,
+        [
+            Button(GamepadButton::A, true),
+            Button(GamepadButton::A, false),
+            Axis(GamepadAxis::LeftX, 1.0),
+            Axis(GamepadAxis::LeftTrigger, 1.0),
+            Button(GamepadButton::DPadUp, true),
+            Button(GamepadButton::DPadUp, false),
+            Button(GamepadButton::DPadDown, true),
+            Button(GamepadButton::DPadDown, false),
+            Axis(GamepadAxis::RightX, -1.0),
+            Axis(GamepadAxis::RightX, 1.0),
+        ]
+    );
+
+    let mut mapper = GamepadMapper::new(layout.clone(), GamepadMapping::kernel_default(&layout, "Unknown"));
+    let mut events = Vec::new();
+    for event in EvdevEvent::parse_stream(&record(&[(EV_KEY, 0x134, 1), (EV_ABS, 0x10, -1)])) {
+        mapper.process(event, &mut events);
+    }
+    assert_eq!(events, [Button(GamepadButton::Y, true), Button(GamepadButton::DPadLeft, true)]);
+}
@@ -0,0 +1,24 @@
+/// Experimental
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Gesture {
+    pub kind: GestureKind,
+    /// Number of fingers on the touchpad.
+    pub fingers: u32,
+    /// Center of the gesture in window coordinates.
+    pub location: (f32, f32),
+    /// Movement of the center since the last event.
+    pub delta: (f32, f32),
+    /// Scale relative to the start of the gesture. Always `1.0` for swipes.
+    pub scale: f32,
+    /// Rotation in degrees since the last event. Always `0.0` for swipes.
+    pub rotation: f32,
+    /// Only meaningful in `Event::gesture_end`, set if the gesture was cancelled instead of finished.
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GestureKind {
+    #[default]
+    Pinch,
+    Swipe,
+}
@@ -0,0 +1,88 @@
+#[cfg(feature = "png")]
+use std::io::Error;
+
+/// RGBA8 pixels, rows from the top left.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, pixels: vec![0; width as usize * height as usize * 4] }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[i..i + 4].copy_from_slice(&rgba);
+    }
+
+    /// Largest difference of any channel, images of different sizes differ by 255.
+    /// Golden image tests should allow a small difference since drivers round differently.
+    pub fn max_difference(&self, other: &Image) -> u8 {
+        if (self.width, self.height) != (other.width, other.height) {
+            return u8::MAX;
+        }
+
+        self.pixels.iter().zip(&other.pixels).map(|(a, b)| a.abs_diff(*b)).max().unwrap_or(0)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png<W: std::io::Write>(&self, writer: W) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(Error::other)?;
+        writer.write_image_data(&self.pixels).map_err(Error::other)
+    }
+
+    /// Only 8-bit RGBA images are supported, which is what `write_png` writes.
+    #[cfg(feature = "png")]
+    pub fn read_png<R: std::io::Read>(reader: R) -> Result<Self, Error> {
+        let mut reader = png::Decoder::new(reader).read_info().map_err(Error::other)?;
+
+        let info = reader.info();
+        if (info.color_type, info.bit_depth) != (png::ColorType::Rgba, png::BitDepth::Eight) {
+            return Err(Error::other("Only 8-bit RGBA images are supported."));
+        }
+
+        let mut image = Self::new(info.width, info.height);
+        reader.next_frame(&mut image.pixels).map_err(Error::other)?;
+
+        Ok(image)
+    }
+}
+
+/// Scales the bits of `pixel` selected by `mask` to `0..=255`.
+pub fn mask_channel(pixel: u64, mask: u64) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let value = (pixel & mask) >> mask.trailing_zeros();
+    let max = mask >> mask.trailing_zeros();
+
+    ((value * 255 + max / 2) / max) as u8
+}
+
+#[test]
+fn mask_channel_test() {
+    assert_eq!(mask_channel(0x00AB_CDEF, 0x00FF_0000), 0xAB);
+    assert_eq!(mask_channel(0x00AB_CDEF, 0x0000_00FF), 0xEF);
+
+    // 565
+    assert_eq!(mask_channel(0xF800, 0xF800), 255);
+    assert_eq!(mask_channel(0x07E0, 0x07E0), 255);
+    assert_eq!(mask_channel(0x0400, 0x07E0), 130);
+    assert_eq!(mask_channel(0x0000, 0x001F), 0);
+
+    assert_eq!(mask_channel(0xFFFF_FFFF, 0), 0);
+}
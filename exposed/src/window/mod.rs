@@ -1,21 +1,35 @@
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "headless")))]
 pub mod win32;
 
 use std::fmt::Debug;
 
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "headless")))]
 pub use win32 as platform;
 
 #[cfg(target_os = "linux")]
 pub mod _x11;
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "wayland"), not(feature = "headless")))]
 pub use _x11 as platform;
 
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "linux", feature = "wayland", not(feature = "headless")))]
+pub mod _wayland;
+#[cfg(all(target_os = "linux", feature = "wayland", not(feature = "headless")))]
+pub mod _linux;
+#[cfg(all(target_os = "linux", feature = "wayland", not(feature = "headless")))]
+pub use _linux as platform;
+
+#[cfg(all(target_os = "android", not(feature = "headless")))]
 pub mod android;
-#[cfg(target_os = "android")]
+#[cfg(all(target_os = "android", not(feature = "headless")))]
 pub use android as platform;
 
+#[cfg(feature = "headless")]
+pub mod _headless;
+#[cfg(feature = "headless")]
+pub use _headless as platform;
+#[cfg(feature = "headless")]
+pub use _headless::{InjectedEvent, VirtualWindow};
+
 mod event;
 pub use event::*;
 
@@ -27,18 +41,68 @@ pub mod utility;
 mod window;
 pub use window::*;
 
+mod window_kind;
+pub use window_kind::*;
+
+mod event_mask;
+pub use event_mask::*;
+
+mod pixel_surface;
+pub use pixel_surface::*;
+
+mod image;
+pub use image::*;
+
 mod event_handler;
 pub use event_handler::*;
 
+mod event_queue;
+pub use event_queue::*;
+
+#[cfg(all(target_os = "linux", not(feature = "headless")))]
+mod event_source;
+
+mod panic;
+pub(crate) use panic::*;
+
 pub use exposed_macro::android_on_create;
 
 mod touch;
 pub use touch::*;
 
+mod gesture;
+pub use gesture::*;
+
+mod gamepad;
+pub use gamepad::*;
+
+mod recording;
+pub use recording::*;
+
+mod timer;
+pub use timer::*;
+
+mod proxy;
+pub use proxy::*;
+
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
+
+#[cfg(target_os = "linux")]
+mod backend;
+#[cfg(target_os = "linux")]
+pub use backend::*;
+
+#[cfg(target_os = "linux")]
+mod evdev;
+#[cfg(target_os = "linux")]
+pub use evdev::*;
+
 pub use platform::Android;
 pub use platform::Context;
 
 #[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Rect {
     pub left: i32,
     pub top: i32,
@@ -76,3 +140,24 @@ pub struct Size {
     pub width: i32,
     pub height: i32,
 }
+
+/// Edge or corner grabbed by `WindowHandle::drag_resize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResizeEdge {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UserAttention {
+    /// Highlights the window in the taskbar until it's focused.
+    Informational,
+    /// Also flashes or bounces the window until it's focused where the platform supports it.
+    Critical,
+}
@@ -0,0 +1,47 @@
+//! Callbacks called from foreign frames, like Win32 window procedures, must not unwind through them.
+//! Their panics are caught and continued once the call into the event handler returns.
+
+use std::{any::Any, cell::RefCell};
+
+thread_local! {
+    static PANIC: RefCell<Option<Box<dyn Any + Send>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` unless a caught panic is waiting, the panic of `f` waits for `resume_panic`.
+#[cfg(any(test, all(target_os = "windows", not(feature = "headless"))))]
+pub(crate) fn catch_panic<R>(f: impl FnOnce() -> R) -> Option<R> {
+    if PANIC.with(|p| p.borrow().is_some()) {
+        return None;
+    }
+
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            PANIC.with(|p| *p.borrow_mut() = Some(payload));
+            None
+        }
+    }
+}
+
+/// Continues the caught panic, it's dropped if the thread is already unwinding.
+pub(crate) fn resume_panic() {
+    if let Some(payload) = PANIC.with(|p| p.borrow_mut().take()) {
+        if !std::thread::panicking() {
+            std::panic::resume_unwind(payload)
+        }
+    }
+}
+
+#[test]
+fn catch_panic_test() {
+    assert_eq!(catch_panic(|| 1), Some(1));
+    assert_eq!(catch_panic(|| panic!("callback")), None::<()>);
+    // Callbacks after the panic are skipped until it's continued.
+    assert_eq!(catch_panic(|| 2), None);
+
+    let payload = std::panic::catch_unwind(resume_panic).unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"callback"));
+
+    resume_panic();
+    assert_eq!(catch_panic(|| 3), Some(3));
+}
@@ -0,0 +1,53 @@
+use std::io::Error;
+
+use crate::destroy::Destroy;
+
+use super::{platform, Rect, WindowHandle};
+
+#[derive(Debug)]
+/// Presents pixels rendered on the CPU to a window, without any graphics API.
+///
+/// Pixels are `0x00RRGGBB` rows from the top left, the top byte is alpha for transparent X11 windows.
+///
+/// Uses:
+/// - A DIB section in a memory DC copied with `BitBlt` in Windows
+/// - `XShmPutImage` in X11 if MIT-SHM is available, `XPutImage` otherwise
+/// - `ANativeWindow_lock` in Android
+pub struct PixelSurface(pub platform::PixelSurface);
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        Ok(Self(platform::PixelSurface::new(window.0)?))
+    }
+
+    /// Buffer sized to the current client area, reallocated when the window is resized.
+    /// Contents are undefined after a resize.
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        self.0.buffer_mut()
+    }
+
+    /// Width of the buffer returned by the last `buffer_mut`.
+    pub fn width(&self) -> u32 {
+        self.0.width
+    }
+
+    /// Height of the buffer returned by the last `buffer_mut`.
+    pub fn height(&self) -> u32 {
+        self.0.height
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.0.present()
+    }
+
+    /// Presents only the parts of the buffer inside `damage`, rectangles are clipped to the buffer.
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        self.0.present_with_damage(damage)
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        self.0.destroy()
+    }
+}
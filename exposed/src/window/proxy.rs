@@ -0,0 +1,221 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use exposed_macro::log_error;
+
+use crate::Error;
+
+use super::WindowHandle;
+
+thread_local! {
+    static REQUESTS: RefCell<Option<Arc<Mutex<Queue>>>> = const { RefCell::new(None) };
+}
+
+type Request = Box<dyn FnOnce() + Send>;
+
+/// Requests of the proxies for the windows of a thread and what wakes its event handler.
+struct Queue {
+    requests: VecDeque<Request>,
+    /// Set when the event handler is destroyed, proxies fail from then on.
+    closed: bool,
+    /// eventfd in the event handler's epoll, -1 until `watch`.
+    #[cfg(all(target_os = "linux", not(feature = "headless")))]
+    fd: std::ffi::c_int,
+    /// Thread that receives an empty `WM_NULL` message.
+    #[cfg(all(target_os = "windows", not(feature = "headless")))]
+    thread: u32,
+}
+
+impl Queue {
+    fn new() -> Self {
+        Self {
+            requests: VecDeque::new(),
+            closed: false,
+            #[cfg(all(target_os = "linux", not(feature = "headless")))]
+            fd: -1,
+            #[cfg(all(target_os = "windows", not(feature = "headless")))]
+            thread: unsafe { windows_sys::Win32::System::Threading::GetCurrentThreadId() },
+        }
+    }
+
+    /// Called with the lock held so the event handler can't close what it wakes.
+    fn wake(&self) {
+        #[cfg(all(target_os = "linux", not(feature = "headless")))]
+        if self.fd != -1 {
+            unsafe { libc::write(self.fd, (&1u64 as *const u64).cast(), std::mem::size_of::<u64>()) };
+        }
+
+        #[cfg(all(target_os = "windows", not(feature = "headless")))]
+        unsafe {
+            use windows_sys::Win32::UI::WindowsAndMessaging::{PostThreadMessageW, WM_NULL};
+            PostThreadMessageW(self.thread, WM_NULL, 0, 0);
+        }
+    }
+}
+
+/// Window handle made `Send`, it's only turned back into a `WindowHandle` on the thread of the window.
+#[derive(Clone, Copy)]
+struct SendHandle(WindowHandle);
+
+unsafe impl Send for SendHandle {}
+unsafe impl Sync for SendHandle {}
+
+impl SendHandle {
+    /// Closures capture the wrapper through this instead of the `!Send` field.
+    fn get(self) -> WindowHandle {
+        self.0
+    }
+}
+
+impl WindowHandle {
+    /// Proxy that other threads use to send requests for the window, see `WindowProxy`.
+    /// Fails with `Error::WrongThread` or `Error::WindowDestroyed` unless the window is alive on this thread.
+    pub fn proxy(self) -> Result<WindowProxy, Error> {
+        // Alive windows mean the thread has an event handler to run the requests, the queue is shared with it.
+        self.0.alive()?;
+
+        Ok(WindowProxy { window: SendHandle(self), queue: Requests::queue() })
+    }
+}
+
+/// Sends requests for a window from any thread, `EventHandler::dispatch` runs them on the thread of the window.
+///
+/// `WindowHandle` and `Context` can't leave their thread, this is how worker threads change windows.
+/// Requests fail with `Error::WindowDestroyed` once the event handler is destroyed, errors of the calls are logged.
+#[derive(Clone)]
+pub struct WindowProxy {
+    window: SendHandle,
+    queue: Arc<Mutex<Queue>>,
+}
+
+impl Debug for WindowProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WindowProxy").field("window", &self.window.0).finish()
+    }
+}
+
+impl WindowProxy {
+    /// Queues `request` to be called with the window and wakes the event handler.
+    pub fn send(&self, request: impl FnOnce(WindowHandle) -> Result<(), std::io::Error> + Send + 'static) -> Result<(), Error> {
+        let window = self.window;
+        let request = Box::new(move || {
+            if let Err(e) = request(window.get()) {
+                log_error!("Exposed", "{e}")
+            }
+        });
+
+        let mut queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.closed {
+            return Err(Error::WindowDestroyed);
+        }
+
+        queue.requests.push_back(request);
+        queue.wake();
+
+        Ok(())
+    }
+
+    pub fn set_window_title(&self, title: &str) -> Result<(), Error> {
+        let title = title.to_string();
+        self.send(move |window| window.set_window_title(&title))
+    }
+
+    pub fn redraw(&self) -> Result<(), Error> {
+        self.send(WindowHandle::redraw)
+    }
+
+    pub fn show(&self) -> Result<(), Error> {
+        self.send(WindowHandle::show)
+    }
+}
+
+/// Receiving side of the proxies of the thread, event handlers wait on it and run the requests in `dispatch`.
+pub(crate) struct Requests;
+
+impl Requests {
+    fn queue() -> Arc<Mutex<Queue>> {
+        REQUESTS.with(|r| r.borrow_mut().get_or_insert_with(|| Arc::new(Mutex::new(Queue::new()))).clone())
+    }
+
+    fn with<R>(f: impl FnOnce(&mut Queue) -> R) -> Option<R> {
+        let queue = REQUESTS.with(|r| r.borrow().clone())?;
+        let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+        Some(f(&mut queue))
+    }
+
+    pub fn has_events() -> bool {
+        Self::with(|q| !q.requests.is_empty()).unwrap_or(false)
+    }
+
+    /// Runs the queued requests, the ones they send run on the next `dispatch`.
+    pub fn run() {
+        let requests = Self::with(|q| std::mem::take(&mut q.requests)).unwrap_or_default();
+
+        for request in requests {
+            request();
+        }
+    }
+
+    /// Closes the queue of the thread, called when the event handler is destroyed.
+    pub fn reset() {
+        let Some(queue) = REQUESTS.with(|r| r.borrow_mut().take()) else { return };
+        let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+
+        queue.closed = true;
+        queue.requests.clear();
+
+        #[cfg(all(target_os = "linux", not(feature = "headless")))]
+        if queue.fd != -1 {
+            unsafe { libc::close(queue.fd) };
+            queue.fd = -1;
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "headless")))]
+impl Requests {
+    /// Registers an eventfd to `epoll` with the descriptor as the event data, proxies write to it.
+    pub fn watch(epoll: std::ffi::c_int) {
+        use libc::{epoll_ctl, epoll_event, eventfd, EFD_CLOEXEC, EFD_NONBLOCK, EPOLLIN, EPOLL_CTL_ADD};
+
+        let queue = Self::queue();
+        let mut queue = queue.lock().unwrap_or_else(|e| e.into_inner());
+
+        unsafe {
+            let fd = eventfd(0, EFD_NONBLOCK | EFD_CLOEXEC);
+            if fd == -1 {
+                exposed_macro::log_warn!("Exposed", "Window proxies are disabled: {}", std::io::Error::last_os_error());
+                return;
+            }
+
+            let mut event = epoll_event { events: EPOLLIN as _, u64: fd as _ };
+            epoll_ctl(epoll, EPOLL_CTL_ADD, fd, &mut event);
+
+            queue.fd = fd;
+        }
+
+        // Requests sent from `Event::create` were queued before there was anything to wake.
+        if !queue.requests.is_empty() {
+            queue.wake();
+        }
+    }
+
+    /// Returns false if `fd` isn't the eventfd.
+    pub fn ready(fd: std::ffi::c_int) -> bool {
+        Self::with(|q| {
+            if fd != q.fd {
+                return false;
+            }
+
+            let mut count = 0u64;
+            unsafe { libc::read(fd, (&mut count as *mut u64).cast(), std::mem::size_of::<u64>()) };
+
+            true
+        })
+        .unwrap_or(false)
+    }
+}
@@ -0,0 +1,45 @@
+//! `raw-window-handle` traits, so wgpu, vulkano, ash-window and softbuffer accept exposed windows directly.
+//!
+//! Handles are only valid until the window or the event handler is destroyed.
+
+#[cfg(all(feature = "rwh_05", not(feature = "headless")))]
+unsafe impl rwh_05::HasRawWindowHandle for super::WindowHandle {
+    fn raw_window_handle(&self) -> rwh_05::RawWindowHandle {
+        self.0.raw_window_handle_05()
+    }
+}
+
+#[cfg(all(feature = "rwh_05", not(feature = "headless")))]
+unsafe impl rwh_05::HasRawDisplayHandle for super::WindowHandle {
+    fn raw_display_handle(&self) -> rwh_05::RawDisplayHandle {
+        self.0.raw_display_handle_05()
+    }
+}
+
+#[cfg(all(feature = "rwh_05", not(feature = "headless")))]
+unsafe impl rwh_05::HasRawDisplayHandle for super::Context {
+    fn raw_display_handle(&self) -> rwh_05::RawDisplayHandle {
+        self.raw_display_handle_05()
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl rwh_06::HasWindowHandle for super::WindowHandle {
+    fn window_handle(&self) -> Result<rwh_06::WindowHandle<'_>, rwh_06::HandleError> {
+        Ok(unsafe { rwh_06::WindowHandle::borrow_raw(self.0.raw_window_handle_06()?) })
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl rwh_06::HasDisplayHandle for super::WindowHandle {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Ok(unsafe { rwh_06::DisplayHandle::borrow_raw(self.0.raw_display_handle_06()?) })
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl rwh_06::HasDisplayHandle for super::Context {
+    fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
+        Ok(unsafe { rwh_06::DisplayHandle::borrow_raw(self.raw_display_handle_06()?) })
+    }
+}
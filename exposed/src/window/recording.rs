@@ -0,0 +1,824 @@
+//! Records the callbacks an `Event` receives and plays them back, for reproducible bug reports and input driven tests.
+//!
+//! A recording starts with `MAGIC` and holds one entry per callback: the microseconds since the previous entry,
+//! a tag and the arguments. Integers are LEB128, signed ones zigzag encoded, floats are little endian.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
+    time::{Duration, Instant},
+};
+
+use exposed_macro::log_error;
+
+use super::{
+    Context, Event, Gamepad, GamepadAxis, GamepadButton, Gesture, GestureKind, Key, MouseButton, ScanCode, TimerId, Touch,
+    TouchPhase, WindowHandle,
+};
+
+/// First bytes of every recording, the last byte is the format version.
+pub const MAGIC: [u8; 6] = *b"EXREC\x01";
+
+/// Longest string a recording may hold, longer lengths are treated as corruption instead of allocated.
+pub const MAX_STRING_LEN: u64 = 1 << 16;
+
+/// Callback of `Event` with its arguments. Windows are numbered in the order they first received an event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordedEvent {
+    Render(u32),
+    Resized(u32, i32, i32),
+    Focused(u32, bool),
+    Minimized(u32),
+    Show(u32),
+    Occluded(u32, bool),
+    Maximized(u32),
+    Moved(u32, i32, i32),
+    FileReceived(u32, String),
+    CloseRequested(u32),
+    Destroyed(u32),
+    KeyDown(u32, Key, ScanCode),
+    KeyUp(u32, Key, ScanCode),
+    ReceivedCharacter(u32, char),
+    MouseWheel(u32, f32, f32),
+    MouseButtonDown(u32, MouseButton),
+    MouseButtonRelease(u32, MouseButton),
+    CursorMoved(u32, i32, i32),
+    CursorEntered(u32),
+    CursorLeft(u32),
+    RawMouseMotion(i32, i32),
+    Touch(u32, Touch, usize),
+    TouchEnd(u32),
+    GestureBegin(u32, Gesture),
+    GestureUpdate(u32, Gesture),
+    GestureEnd(u32, Gesture),
+    GamepadConnected(Gamepad, String),
+    GamepadDisconnected(Gamepad),
+    GamepadButtonDown(Gamepad, GamepadButton),
+    GamepadButtonUp(Gamepad, GamepadButton),
+    AxisMotion(Gamepad, GamepadAxis, f32),
+    TimerFired(TimerId),
+}
+
+impl RecordedEvent {
+    /// Calls the matching callback of `app`, `windows[i]` stands for window `i` of the recording.
+    /// Events of windows missing from `windows` are skipped.
+    pub fn dispatch<E: Event>(&self, app: &mut E, windows: &[WindowHandle]) {
+        use RecordedEvent::*;
+
+        let on = |i: &u32, callback: &mut dyn FnMut(WindowHandle)| {
+            if let Some(window) = windows.get(*i as usize) {
+                callback(*window)
+            }
+        };
+
+        match self {
+            Render(w) => on(w, &mut |w| app.render(w)),
+            Resized(w, width, height) => on(w, &mut |w| app.resized(w, *width, *height)),
+            Focused(w, focused) => on(w, &mut |w| app.focused(w, *focused)),
+            Minimized(w) => on(w, &mut |w| app.minimized(w)),
+            Show(w) => on(w, &mut |w| app.show(w)),
+            Occluded(w, occluded) => on(w, &mut |w| app.occluded(w, *occluded)),
+            Maximized(w) => on(w, &mut |w| app.maximized(w)),
+            Moved(w, x, y) => on(w, &mut |w| app.moved(w, *x, *y)),
+            FileReceived(w, path) => on(w, &mut |w| app.file_received(w, path.clone())),
+            CloseRequested(w) => on(w, &mut |w| app.close_requested(w)),
+            Destroyed(w) => on(w, &mut |w| app.destroyed(w)),
+            KeyDown(w, key, scancode) => on(w, &mut |w| app.key_down(w, *key, *scancode)),
+            KeyUp(w, key, scancode) => on(w, &mut |w| app.key_up(w, *key, *scancode)),
+            ReceivedCharacter(w, character) => on(w, &mut |w| app.received_character(w, *character)),
+            MouseWheel(w, delta_x, delta_y) => on(w, &mut |w| app.mouse_wheel(w, *delta_x, *delta_y)),
+            MouseButtonDown(w, button) => on(w, &mut |w| app.mouse_button_down(w, *button)),
+            MouseButtonRelease(w, button) => on(w, &mut |w| app.mouse_button_release(w, *button)),
+            CursorMoved(w, x, y) => on(w, &mut |w| app.cursor_moved(w, *x, *y)),
+            CursorEntered(w) => on(w, &mut |w| app.cursor_entered(w)),
+            CursorLeft(w) => on(w, &mut |w| app.cursor_left(w)),
+            RawMouseMotion(delta_x, delta_y) => app.raw_mouse_motion(*delta_x, *delta_y),
+            Touch(w, touch, pointer_count) => on(w, &mut |w| app.touch(w, *touch, *pointer_count)),
+            TouchEnd(w) => on(w, &mut |w| app.touch_end(w)),
+            GestureBegin(w, gesture) => on(w, &mut |w| app.gesture_begin(w, *gesture)),
+            GestureUpdate(w, gesture) => on(w, &mut |w| app.gesture_update(w, *gesture)),
+            GestureEnd(w, gesture) => on(w, &mut |w| app.gesture_end(w, *gesture)),
+            GamepadConnected(gamepad, name) => app.gamepad_connected(*gamepad, name.clone()),
+            GamepadDisconnected(gamepad) => app.gamepad_disconnected(*gamepad),
+            GamepadButtonDown(gamepad, button) => app.gamepad_button_down(*gamepad, *button),
+            GamepadButtonUp(gamepad, button) => app.gamepad_button_up(*gamepad, *button),
+            AxisMotion(gamepad, axis, value) => app.axis_motion(*gamepad, *axis, *value),
+            TimerFired(timer) => app.timer_fired(*timer),
+        }
+    }
+}
+
+/// Writes `RecordedEvent`s with the time they happened.
+#[derive(Debug)]
+pub struct EventWriter<W: Write> {
+    pub writer: W,
+    pub last: Duration,
+}
+
+impl<W: Write> EventWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self, Error> {
+        writer.write_all(&MAGIC)?;
+        Ok(Self { writer, last: Duration::ZERO })
+    }
+
+    /// `time` is relative to the start of the recording and never goes backwards.
+    pub fn write(&mut self, time: Duration, event: &RecordedEvent) -> Result<(), Error> {
+        use RecordedEvent::*;
+
+        let delta = time.saturating_sub(self.last);
+        self.last = self.last.max(time);
+
+        let mut b = Vec::with_capacity(16);
+        put_u64(&mut b, delta.as_micros() as u64);
+
+        match event {
+            Render(w) => window_tag(&mut b, 0, *w),
+            Resized(w, width, height) => {
+                window_tag(&mut b, 1, *w);
+                put_i32(&mut b, *width);
+                put_i32(&mut b, *height);
+            }
+            Focused(w, focused) => {
+                window_tag(&mut b, 2, *w);
+                b.push(*focused as u8);
+            }
+            Minimized(w) => window_tag(&mut b, 3, *w),
+            Show(w) => window_tag(&mut b, 4, *w),
+            Maximized(w) => window_tag(&mut b, 5, *w),
+            Moved(w, x, y) => {
+                window_tag(&mut b, 6, *w);
+                put_i32(&mut b, *x);
+                put_i32(&mut b, *y);
+            }
+            FileReceived(w, path) => {
+                window_tag(&mut b, 7, *w);
+                put_str(&mut b, path);
+            }
+            CloseRequested(w) => window_tag(&mut b, 8, *w),
+            Destroyed(w) => window_tag(&mut b, 9, *w),
+            KeyDown(w, key, scancode) | KeyUp(w, key, scancode) => {
+                window_tag(&mut b, if matches!(event, KeyDown(..)) { 10 } else { 11 }, *w);
+                put_u64(&mut b, key.0);
+                put_u64(&mut b, *scancode as u64);
+            }
+            ReceivedCharacter(w, character) => {
+                window_tag(&mut b, 12, *w);
+                put_u64(&mut b, *character as u64);
+            }
+            MouseWheel(w, delta_x, delta_y) => {
+                window_tag(&mut b, 13, *w);
+                put_f32(&mut b, *delta_x);
+                put_f32(&mut b, *delta_y);
+            }
+            MouseButtonDown(w, button) | MouseButtonRelease(w, button) => {
+                window_tag(&mut b, if matches!(event, MouseButtonDown(..)) { 14 } else { 15 }, *w);
+                put_u64(&mut b, button.0 as u64);
+            }
+            CursorMoved(w, x, y) => {
+                window_tag(&mut b, 16, *w);
+                put_i32(&mut b, *x);
+                put_i32(&mut b, *y);
+            }
+            CursorEntered(w) => window_tag(&mut b, 17, *w),
+            CursorLeft(w) => window_tag(&mut b, 18, *w),
+            RawMouseMotion(delta_x, delta_y) => {
+                b.push(19);
+                put_i32(&mut b, *delta_x);
+                put_i32(&mut b, *delta_y);
+            }
+            Touch(w, touch, pointer_count) => {
+                window_tag(&mut b, 20, *w);
+                b.push(touch.phase as u8);
+                put_f32(&mut b, touch.location.0);
+                put_f32(&mut b, touch.location.1);
+                put_u64(&mut b, touch.pointer_index as u64);
+                put_u64(&mut b, touch.id);
+                put_u64(&mut b, *pointer_count as u64);
+            }
+            TouchEnd(w) => window_tag(&mut b, 21, *w),
+            GestureBegin(w, gesture) | GestureUpdate(w, gesture) | GestureEnd(w, gesture) => {
+                let tag = match event {
+                    GestureBegin(..) => 22,
+                    GestureUpdate(..) => 23,
+                    _ => 24,
+                };
+                window_tag(&mut b, tag, *w);
+                b.push(gesture.kind as u8);
+                put_u64(&mut b, gesture.fingers as u64);
+                for value in
+                    [gesture.location.0, gesture.location.1, gesture.delta.0, gesture.delta.1, gesture.scale, gesture.rotation]
+                {
+                    put_f32(&mut b, value);
+                }
+                b.push(gesture.cancelled as u8);
+            }
+            GamepadConnected(gamepad, name) => {
+                b.push(25);
+                put_u64(&mut b, gamepad.0 as u64);
+                put_str(&mut b, name);
+            }
+            GamepadDisconnected(gamepad) => {
+                b.push(26);
+                put_u64(&mut b, gamepad.0 as u64);
+            }
+            GamepadButtonDown(gamepad, button) | GamepadButtonUp(gamepad, button) => {
+                b.push(if matches!(event, GamepadButtonDown(..)) { 27 } else { 28 });
+                put_u64(&mut b, gamepad.0 as u64);
+                b.push(*button as u8);
+            }
+            AxisMotion(gamepad, axis, value) => {
+                b.push(29);
+                put_u64(&mut b, gamepad.0 as u64);
+                b.push(*axis as u8);
+                put_f32(&mut b, *value);
+            }
+            TimerFired(timer) => {
+                b.push(30);
+                put_u64(&mut b, timer.0 as u64);
+            }
+            Occluded(w, occluded) => {
+                window_tag(&mut b, 31, *w);
+                b.push(*occluded as u8);
+            }
+        }
+
+        self.writer.write_all(&b)
+    }
+}
+
+/// Reads what `EventWriter` wrote.
+#[derive(Debug)]
+pub struct EventReader<R: Read> {
+    pub reader: R,
+    pub time: Duration,
+}
+
+impl<R: Read> EventReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+
+        if magic != MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "Not a recording or recorded with another version."));
+        }
+
+        Ok(Self { reader, time: Duration::ZERO })
+    }
+
+    /// Returns the event with the time since the start of the recording, `None` at the end.
+    pub fn read(&mut self) -> Result<Option<(Duration, RecordedEvent)>, Error> {
+        use RecordedEvent::*;
+
+        let delta = match self.u64() {
+            Ok(delta) => delta,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        self.time = self.time.checked_add(Duration::from_micros(delta)).ok_or_else(invalid)?;
+
+        let tag = self.u8()?;
+        let event = match tag {
+            0 => Render(self.u32()?),
+            1 => Resized(self.u32()?, self.i32()?, self.i32()?),
+            2 => Focused(self.u32()?, self.u8()? != 0),
+            3 => Minimized(self.u32()?),
+            4 => Show(self.u32()?),
+            5 => Maximized(self.u32()?),
+            6 => Moved(self.u32()?, self.i32()?, self.i32()?),
+            7 => FileReceived(self.u32()?, self.string()?),
+            8 => CloseRequested(self.u32()?),
+            9 => Destroyed(self.u32()?),
+            10 => KeyDown(self.u32()?, Key(self.u64()?), self.u32()?),
+            11 => KeyUp(self.u32()?, Key(self.u64()?), self.u32()?),
+            12 => {
+                let w = self.u32()?;
+                let character = char::from_u32(self.u32()?).ok_or_else(invalid)?;
+                ReceivedCharacter(w, character)
+            }
+            13 => MouseWheel(self.u32()?, self.f32()?, self.f32()?),
+            14 => MouseButtonDown(self.u32()?, MouseButton(self.u32()?)),
+            15 => MouseButtonRelease(self.u32()?, MouseButton(self.u32()?)),
+            16 => CursorMoved(self.u32()?, self.i32()?, self.i32()?),
+            17 => CursorEntered(self.u32()?),
+            18 => CursorLeft(self.u32()?),
+            19 => RawMouseMotion(self.i32()?, self.i32()?),
+            20 => {
+                let w = self.u32()?;
+                let phase = match self.u8()? {
+                    0 => TouchPhase::Started,
+                    1 => TouchPhase::Moved,
+                    2 => TouchPhase::Ended,
+                    3 => TouchPhase::Cancelled,
+                    _ => return Err(invalid()),
+                };
+                let location = (self.f32()?, self.f32()?);
+                let (pointer_index, id) = (self.u64()? as usize, self.u64()?);
+                let touch = super::Touch { phase, location, pointer_index, id, os_data: 0 };
+                Touch(w, touch, self.u64()? as usize)
+            }
+            21 => TouchEnd(self.u32()?),
+            22..=24 => {
+                let w = self.u32()?;
+                let kind = match self.u8()? {
+                    0 => GestureKind::Pinch,
+                    1 => GestureKind::Swipe,
+                    _ => return Err(invalid()),
+                };
+                let gesture = Gesture {
+                    kind,
+                    fingers: self.u32()?,
+                    location: (self.f32()?, self.f32()?),
+                    delta: (self.f32()?, self.f32()?),
+                    scale: self.f32()?,
+                    rotation: self.f32()?,
+                    cancelled: self.u8()? != 0,
+                };
+                match tag {
+                    22 => GestureBegin(w, gesture),
+                    23 => GestureUpdate(w, gesture),
+                    _ => GestureEnd(w, gesture),
+                }
+            }
+            25 => GamepadConnected(Gamepad(self.u32()?), self.string()?),
+            26 => GamepadDisconnected(Gamepad(self.u32()?)),
+            27 | 28 => {
+                let gamepad = Gamepad(self.u32()?);
+                let button = *GamepadButton::ALL.get(self.u8()? as usize).ok_or_else(invalid)?;
+                if tag == 27 {
+                    GamepadButtonDown(gamepad, button)
+                } else {
+                    GamepadButtonUp(gamepad, button)
+                }
+            }
+            29 => {
+                let gamepad = Gamepad(self.u32()?);
+                let axis = *GamepadAxis::ALL.get(self.u8()? as usize).ok_or_else(invalid)?;
+                AxisMotion(gamepad, axis, self.f32()?)
+            }
+            30 => TimerFired(TimerId(self.u32()?)),
+            31 => Occluded(self.u32()?, self.u8()? != 0),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Some((self.time, event)))
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        let mut b = [0];
+        self.reader.read_exact(&mut b)?;
+        Ok(b[0])
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let b = self.u8()?;
+            value |= ((b & 0x7F) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid())
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.u64()?.try_into().map_err(|_| invalid())
+    }
+
+    fn i32(&mut self) -> Result<i32, Error> {
+        let value = self.u32()?;
+        Ok((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        let mut b = [0; 4];
+        self.reader.read_exact(&mut b)?;
+        Ok(f32::from_le_bytes(b))
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        let len = self.u64()?;
+        if len > MAX_STRING_LEN {
+            return Err(invalid());
+        }
+
+        let mut b = Vec::new();
+        if self.reader.by_ref().take(len).read_to_end(&mut b)? as u64 != len {
+            return Err(invalid());
+        }
+        String::from_utf8(b).map_err(|_| invalid())
+    }
+}
+
+fn invalid() -> Error {
+    Error::new(ErrorKind::InvalidData, "Corrupted recording.")
+}
+
+fn window_tag(b: &mut Vec<u8>, tag: u8, window: u32) {
+    b.push(tag);
+    put_u64(b, window as u64);
+}
+
+fn put_u64(b: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        b.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    b.push(value as u8);
+}
+
+fn put_i32(b: &mut Vec<u8>, value: i32) {
+    put_u64(b, ((value << 1) ^ (value >> 31)) as u32 as u64);
+}
+
+fn put_f32(b: &mut Vec<u8>, value: f32) {
+    b.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_str(b: &mut Vec<u8>, value: &str) {
+    put_u64(b, value.len() as u64);
+    b.extend_from_slice(value.as_bytes());
+}
+
+/// Wraps `E` and records every callback it receives, use it in place of `E` when building the event handler.
+///
+/// Recording starts in `Event::create` if `EXPOSED_RECORD` holds a path, or with `Recorder::record_to`.
+/// `Event::scale_factor_changed` has no receiver and isn't recorded.
+pub struct Recorder<E: Event> {
+    pub inner: E,
+    pub writer: Option<EventWriter<Box<dyn Write>>>,
+    pub start: Instant,
+    /// Windows in the order they first received an event, the index is the window in the recording.
+    pub windows: Vec<WindowHandle>,
+}
+
+impl<E: Event> Recorder<E> {
+    /// Records to `writer` from now on, replacing the previous recording.
+    pub fn record_to<W: Write + 'static>(&mut self, writer: W) -> Result<(), Error> {
+        self.writer = Some(EventWriter::new(Box::new(writer) as Box<dyn Write>)?);
+        self.start = Instant::now();
+        self.windows.clear();
+        Ok(())
+    }
+
+    /// Flushes and stops the recording.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        match self.writer.take() {
+            Some(mut writer) => writer.writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn window(&mut self, window: WindowHandle) -> u32 {
+        match self.windows.iter().position(|w| *w == window) {
+            Some(i) => i as u32,
+            None => {
+                self.windows.push(window);
+                self.windows.len() as u32 - 1
+            }
+        }
+    }
+
+    fn record(&mut self, event: impl FnOnce(&mut Self) -> RecordedEvent) {
+        if self.writer.is_none() {
+            return;
+        }
+
+        let event = event(self);
+        let time = self.start.elapsed();
+
+        if let Err(e) = self.writer.as_mut().unwrap().write(time, &event) {
+            log_error!("Exposed", "Stopped recording, {e}");
+            self.writer = None;
+        }
+    }
+}
+
+impl<E: Event> Event for Recorder<E> {
+    fn create(context: Context) -> Option<Self> {
+        let mut recorder = Self { inner: E::create(context)?, writer: None, start: Instant::now(), windows: Vec::new() };
+
+        if let Some(path) = std::env::var_os("EXPOSED_RECORD") {
+            if let Err(e) = File::create(path).and_then(|file| recorder.record_to(BufWriter::new(file))) {
+                log_error!("Exposed", "Failed to start recording, {e}");
+            }
+        }
+
+        Some(recorder)
+    }
+
+    fn render(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Render(s.window(window)));
+        self.inner.render(window)
+    }
+
+    fn low_render(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Render(s.window(window)));
+        self.inner.low_render(window)
+    }
+
+    fn resized(&mut self, window: WindowHandle, width: i32, height: i32) {
+        self.record(|s| RecordedEvent::Resized(s.window(window), width, height));
+        self.inner.resized(window, width, height)
+    }
+
+    fn focused(&mut self, window: WindowHandle, focused: bool) {
+        self.record(|s| RecordedEvent::Focused(s.window(window), focused));
+        self.inner.focused(window, focused)
+    }
+
+    fn minimized(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Minimized(s.window(window)));
+        self.inner.minimized(window)
+    }
+
+    fn show(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Show(s.window(window)));
+        self.inner.show(window)
+    }
+
+    fn occluded(&mut self, window: WindowHandle, occluded: bool) {
+        self.record(|s| RecordedEvent::Occluded(s.window(window), occluded));
+        self.inner.occluded(window, occluded)
+    }
+
+    fn maximized(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Maximized(s.window(window)));
+        self.inner.maximized(window)
+    }
+
+    fn moved(&mut self, window: WindowHandle, x: i32, y: i32) {
+        self.record(|s| RecordedEvent::Moved(s.window(window), x, y));
+        self.inner.moved(window, x, y)
+    }
+
+    fn file_received(&mut self, window: WindowHandle, path: String) {
+        self.record(|s| RecordedEvent::FileReceived(s.window(window), path.clone()));
+        self.inner.file_received(window, path)
+    }
+
+    fn close_requested(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::CloseRequested(s.window(window)));
+        self.inner.close_requested(window)
+    }
+
+    fn destroyed(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::Destroyed(s.window(window)));
+        self.inner.destroyed(window)
+    }
+
+    fn key_down(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        self.record(|s| RecordedEvent::KeyDown(s.window(window), key, scancode));
+        self.inner.key_down(window, key, scancode)
+    }
+
+    fn key_up(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        self.record(|s| RecordedEvent::KeyUp(s.window(window), key, scancode));
+        self.inner.key_up(window, key, scancode)
+    }
+
+    fn received_character(&mut self, window: WindowHandle, character: char) {
+        self.record(|s| RecordedEvent::ReceivedCharacter(s.window(window), character));
+        self.inner.received_character(window, character)
+    }
+
+    fn mouse_wheel(&mut self, window: WindowHandle, delta_x: f32, delta_y: f32) {
+        self.record(|s| RecordedEvent::MouseWheel(s.window(window), delta_x, delta_y));
+        self.inner.mouse_wheel(window, delta_x, delta_y)
+    }
+
+    fn mouse_button_down(&mut self, window: WindowHandle, button: MouseButton) {
+        self.record(|s| RecordedEvent::MouseButtonDown(s.window(window), button));
+        self.inner.mouse_button_down(window, button)
+    }
+
+    fn mouse_button_release(&mut self, window: WindowHandle, button: MouseButton) {
+        self.record(|s| RecordedEvent::MouseButtonRelease(s.window(window), button));
+        self.inner.mouse_button_release(window, button)
+    }
+
+    fn cursor_moved(&mut self, window: WindowHandle, position_x: i32, position_y: i32) {
+        self.record(|s| RecordedEvent::CursorMoved(s.window(window), position_x, position_y));
+        self.inner.cursor_moved(window, position_x, position_y)
+    }
+
+    fn cursor_entered(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::CursorEntered(s.window(window)));
+        self.inner.cursor_entered(window)
+    }
+
+    fn cursor_left(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::CursorLeft(s.window(window)));
+        self.inner.cursor_left(window)
+    }
+
+    fn raw_mouse_motion(&mut self, delta_x: i32, delta_y: i32) {
+        self.record(|_| RecordedEvent::RawMouseMotion(delta_x, delta_y));
+        self.inner.raw_mouse_motion(delta_x, delta_y)
+    }
+
+    fn touch(&mut self, window: WindowHandle, touch: Touch, pointer_count: usize) {
+        self.record(|s| RecordedEvent::Touch(s.window(window), touch, pointer_count));
+        self.inner.touch(window, touch, pointer_count)
+    }
+
+    fn touch_end(&mut self, window: WindowHandle) {
+        self.record(|s| RecordedEvent::TouchEnd(s.window(window)));
+        self.inner.touch_end(window)
+    }
+
+    fn gesture_begin(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.record(|s| RecordedEvent::GestureBegin(s.window(window), gesture));
+        self.inner.gesture_begin(window, gesture)
+    }
+
+    fn gesture_update(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.record(|s| RecordedEvent::GestureUpdate(s.window(window), gesture));
+        self.inner.gesture_update(window, gesture)
+    }
+
+    fn gesture_end(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.record(|s| RecordedEvent::GestureEnd(s.window(window), gesture));
+        self.inner.gesture_end(window, gesture)
+    }
+
+    fn gamepad_connected(&mut self, gamepad: Gamepad, name: String) {
+        self.record(|_| RecordedEvent::GamepadConnected(gamepad, name.clone()));
+        self.inner.gamepad_connected(gamepad, name)
+    }
+
+    fn gamepad_disconnected(&mut self, gamepad: Gamepad) {
+        self.record(|_| RecordedEvent::GamepadDisconnected(gamepad));
+        self.inner.gamepad_disconnected(gamepad)
+    }
+
+    fn gamepad_button_down(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        self.record(|_| RecordedEvent::GamepadButtonDown(gamepad, button));
+        self.inner.gamepad_button_down(gamepad, button)
+    }
+
+    fn gamepad_button_up(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        self.record(|_| RecordedEvent::GamepadButtonUp(gamepad, button));
+        self.inner.gamepad_button_up(gamepad, button)
+    }
+
+    fn axis_motion(&mut self, gamepad: Gamepad, axis: GamepadAxis, value: f32) {
+        self.record(|_| RecordedEvent::AxisMotion(gamepad, axis, value));
+        self.inner.axis_motion(gamepad, axis, value)
+    }
+
+    fn scale_factor_changed() {
+        E::scale_factor_changed()
+    }
+
+    fn timer_fired(&mut self, timer: TimerId) {
+        self.record(|_| RecordedEvent::TimerFired(timer));
+        self.inner.timer_fired(timer)
+    }
+}
+
+/// Feeds a recording to an `Event` implementation.
+///
+/// `with_speed` scales the recorded timing, `2.0` plays twice as fast and `f32::INFINITY` ignores it.
+#[derive(Debug)]
+pub struct Player<R: Read> {
+    pub reader: EventReader<R>,
+    pub windows: Vec<WindowHandle>,
+    speed: f32,
+    pub start: Instant,
+    pub next: Option<(Duration, RecordedEvent)>,
+}
+
+impl Player<BufReader<File>> {
+    pub fn open<P: AsRef<std::path::Path>>(path: P, windows: &[WindowHandle]) -> Result<Self, Error> {
+        Self::new(BufReader::new(File::open(path)?), windows)
+    }
+}
+
+impl<R: Read> Player<R> {
+    /// `windows[i]` receives the events of window `i` of the recording, see `RecordedEvent::dispatch`.
+    pub fn new(reader: R, windows: &[WindowHandle]) -> Result<Self, Error> {
+        let mut reader = EventReader::new(reader)?;
+        let next = reader.read()?;
+
+        Ok(Self { reader, windows: windows.to_vec(), speed: 1.0, start: Instant::now(), next })
+    }
+
+    /// Fails with `InvalidInput` unless `speed` is positive.
+    pub fn with_speed(&mut self, speed: f32) -> Result<&mut Self, Error> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "The playback speed must be positive."));
+        }
+
+        self.speed = speed;
+        Ok(self)
+    }
+
+    /// When the next event is due, `None` once the recording is played.
+    /// Slow speeds can put it past what `Instant` holds, it's a century away then.
+    pub fn next_time(&self) -> Option<Instant> {
+        let (time, _) = self.next.as_ref()?;
+
+        let delay = Duration::try_from_secs_f64(time.as_secs_f64() / self.speed as f64).ok();
+        let due = delay.and_then(|delay| self.start.checked_add(delay));
+
+        Some(due.unwrap_or_else(|| Instant::now() + Duration::from_secs(100 * 365 * 24 * 60 * 60)))
+    }
+
+    /// Dispatches the events that are due without waiting, returns false once the recording is played.
+    /// Call it from the event loop of `app`, use `next_time` as the timeout of the wait.
+    pub fn play_due<E: Event>(&mut self, app: &mut E) -> Result<bool, Error> {
+        while let Some(due) = self.next_time() {
+            if due > Instant::now() {
+                return Ok(true);
+            }
+
+            self.dispatch_next(app)?;
+        }
+
+        Ok(false)
+    }
+
+    /// Plays the rest of the recording, sleeping between events.
+    pub fn play<E: Event>(&mut self, app: &mut E) -> Result<(), Error> {
+        while let Some(due) = self.next_time() {
+            std::thread::sleep(due.saturating_duration_since(Instant::now()));
+            self.dispatch_next(app)?;
+        }
+
+        Ok(())
+    }
+
+    fn dispatch_next<E: Event>(&mut self, app: &mut E) -> Result<(), Error> {
+        if let Some((_, event)) = self.next.take() {
+            event.dispatch(app, &self.windows);
+            self.next = self.reader.read()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn recording_round_trip_test() {
+    let touch = Touch { phase: TouchPhase::Moved, location: (1.5, -2.0), pointer_index: 1, id: 7, os_data: 0 };
+    let gesture = Gesture { kind: GestureKind::Swipe, fingers: 3, scale: 1.0, ..Default::default() };
+
+    let events = [
+        RecordedEvent::Resized(0, 640, -480),
+        RecordedEvent::KeyDown(1, Key(0xFF1B), 9),
+        RecordedEvent::ReceivedCharacter(0, 'ü'),
+        RecordedEvent::CursorMoved(0, -5, 300),
+        RecordedEvent::MouseWheel(0, 0.0, -1.25),
+        RecordedEvent::Touch(0, touch, 2),
+        RecordedEvent::GestureEnd(0, gesture),
+        RecordedEvent::FileReceived(0, "/tmp/file.txt".to_string()),
+        RecordedEvent::GamepadButtonDown(Gamepad(2), GamepadButton::Touchpad),
+        RecordedEvent::AxisMotion(Gamepad(2), GamepadAxis::RightTrigger, 0.5),
+        RecordedEvent::TimerFired(TimerId(7)),
+        RecordedEvent::RawMouseMotion(i32::MIN, i32::MAX),
+    ];
+
+    let mut writer = EventWriter::new(Vec::new()).unwrap();
+    for (i, event) in events.iter().enumerate() {
+        writer.write(Duration::from_millis(i as u64 * 10), event).unwrap();
+    }
+
+    let mut reader = EventReader::new(writer.writer.as_slice()).unwrap();
+    for (i, event) in events.iter().enumerate() {
+        let (time, read) = reader.read().unwrap().unwrap();
+        assert_eq!(time, Duration::from_millis(i as u64 * 10));
+        assert_eq!(&read, event);
+    }
+
+    assert_eq!(reader.read().unwrap(), None);
+    assert_eq!(EventReader::new(&b"EXREC\x00"[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // A file received event claiming a string far past the end of the recording.
+    let mut corrupted = MAGIC.to_vec();
+    corrupted.extend_from_slice(&[0, 7, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    assert_eq!(EventReader::new(corrupted.as_slice()).unwrap().read().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut truncated = MAGIC.to_vec();
+    truncated.extend_from_slice(&[0, 7, 0, 4, b'/', b't']);
+    assert_eq!(EventReader::new(truncated.as_slice()).unwrap().read().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Entries past the longest `Duration`.
+    let mut reader = EventReader { reader: &[1, 0, 0][..], time: Duration::MAX };
+    assert_eq!(reader.read().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn player_speed_test() {
+    let mut writer = EventWriter::new(Vec::new()).unwrap();
+    writer.write(Duration::from_secs(1), &RecordedEvent::Render(0)).unwrap();
+
+    let mut player = Player::new(writer.writer.as_slice(), &[]).unwrap();
+    assert!(player.with_speed(0.0).is_err() && player.with_speed(-1.0).is_err() && player.with_speed(f32::NAN).is_err());
+
+    player.with_speed(1e-30).unwrap();
+    assert!(player.next_time().unwrap() > Instant::now() + Duration::from_secs(365 * 24 * 60 * 60));
+
+    player.with_speed(f32::INFINITY).unwrap();
+    assert!(player.next_time().unwrap() <= Instant::now());
+}
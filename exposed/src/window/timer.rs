@@ -0,0 +1,217 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use super::Context;
+
+thread_local! {
+    static TIMERS: RefCell<Timers> = const { RefCell::new(Timers::new()) };
+}
+
+/// Identifies a timer of `Context::set_timer` in `Event::timer_fired`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimerId(pub u32);
+
+impl Context {
+    /// Calls `Event::timer_fired` from `dispatch` once `duration` passed, then every `duration` if `repeat` is set.
+    /// `wait` returns when the earliest timer is due, timers belong to the thread like the event handler.
+    /// Durations past what `Instant` can hold never fire.
+    pub fn set_timer(self, duration: Duration, repeat: bool) -> TimerId {
+        Timers::with(|t| t.set(duration, repeat))
+    }
+
+    /// Returns false if the timer is unknown or already fired without `repeat`.
+    pub fn cancel_timer(self, timer: TimerId) -> bool {
+        Timers::with(|t| t.cancel(timer))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Timer {
+    id: TimerId,
+    deadline: Instant,
+    interval: Option<Duration>,
+}
+
+/// Timers of the thread, event handlers wait on them and pop the ones that are due.
+#[derive(Debug)]
+pub(crate) struct Timers {
+    timers: Vec<Timer>,
+    due: VecDeque<TimerId>,
+    next_id: u32,
+    /// timerfd in the event handler's epoll, -1 until `watch`.
+    #[cfg(all(target_os = "linux", not(feature = "headless")))]
+    fd: std::ffi::c_int,
+}
+
+impl Timers {
+    const fn new() -> Self {
+        Self {
+            timers: Vec::new(),
+            due: VecDeque::new(),
+            next_id: 0,
+            #[cfg(all(target_os = "linux", not(feature = "headless")))]
+            fd: -1,
+        }
+    }
+
+    fn with<R>(f: impl FnOnce(&mut Timers) -> R) -> R {
+        TIMERS.with(|t| f(&mut t.borrow_mut()))
+    }
+
+    fn set(&mut self, duration: Duration, repeat: bool) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        let Some(deadline) = Instant::now().checked_add(duration) else {
+            return id;
+        };
+
+        let interval = repeat.then_some(duration.max(Duration::from_millis(1)));
+        self.timers.push(Timer { id, deadline, interval });
+        self.arm();
+
+        id
+    }
+
+    fn cancel(&mut self, id: TimerId) -> bool {
+        let len = self.timers.len();
+        self.timers.retain(|t| t.id != id);
+        self.due.retain(|&due| due != id);
+        self.arm();
+
+        self.timers.len() != len
+    }
+
+    /// Moves due timers to the queue, repeating timers are rescheduled without catching up on missed periods.
+    fn update(&mut self) {
+        let now = Instant::now();
+        let due = self.due.len();
+
+        self.timers.retain_mut(|t| {
+            if t.deadline > now {
+                return true;
+            }
+
+            self.due.push_back(t.id);
+
+            match t.interval.and_then(|interval| t.deadline.checked_add(interval)) {
+                Some(deadline) => {
+                    t.deadline = deadline.max(now);
+                    true
+                }
+                None => false,
+            }
+        });
+
+        if self.due.len() != due {
+            self.arm();
+        }
+    }
+
+    /// Checks the deadlines, true if a timer is waiting for `pop`.
+    pub fn has_events() -> bool {
+        Self::with(|t| {
+            t.update();
+            !t.due.is_empty()
+        })
+    }
+
+    pub fn pop() -> Option<TimerId> {
+        Self::with(|t| t.due.pop_front())
+    }
+
+    /// Forgets every timer, called when the event handler is destroyed.
+    pub fn reset() {
+        Self::with(|t| {
+            #[cfg(all(target_os = "linux", not(feature = "headless")))]
+            if t.fd != -1 {
+                unsafe { libc::close(t.fd) };
+            }
+
+            *t = Self::new();
+        })
+    }
+}
+
+/// Key repeat of the Wayland backend.
+#[cfg(all(target_os = "linux", feature = "wayland", not(feature = "headless")))]
+impl Timers {
+    /// Timer for the backends, it's popped like the ones of `Context::set_timer` and must not reach `Event::timer_fired`.
+    pub fn start(duration: Duration, repeat: bool) -> TimerId {
+        Self::with(|t| t.set(duration, repeat))
+    }
+
+    pub fn stop(timer: TimerId) -> bool {
+        Self::with(|t| t.cancel(timer))
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "headless")))]
+impl Timers {
+    /// Registers a timerfd to `epoll` with the descriptor as the event data, armed for the earliest deadline.
+    pub fn watch(epoll: std::ffi::c_int) {
+        use libc::{epoll_ctl, epoll_event, timerfd_create, CLOCK_MONOTONIC, EPOLLIN, EPOLL_CTL_ADD, TFD_CLOEXEC, TFD_NONBLOCK};
+
+        Self::with(|t| unsafe {
+            let fd = timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK | TFD_CLOEXEC);
+            if fd == -1 {
+                exposed_macro::log_warn!("Exposed", "Timers are disabled: {}", std::io::Error::last_os_error());
+                return;
+            }
+
+            let mut event = epoll_event { events: EPOLLIN as _, u64: fd as _ };
+            epoll_ctl(epoll, EPOLL_CTL_ADD, fd, &mut event);
+
+            t.fd = fd;
+            t.arm();
+        })
+    }
+
+    /// Returns false if `fd` isn't the timerfd.
+    pub fn ready(fd: std::ffi::c_int) -> bool {
+        Self::with(|t| {
+            if fd != t.fd {
+                return false;
+            }
+
+            let mut expirations = 0u64;
+            unsafe { libc::read(fd, (&mut expirations as *mut u64).cast(), std::mem::size_of::<u64>()) };
+            t.update();
+            // The timerfd is one-shot, repeating and later timers need it armed again.
+            t.arm();
+
+            true
+        })
+    }
+
+    fn arm(&mut self) {
+        if self.fd == -1 {
+            return;
+        }
+
+        let mut spec: libc::itimerspec = unsafe { std::mem::zeroed() };
+
+        if let Some(deadline) = self.timers.iter().map(|t| t.deadline).min() {
+            // A zero value disarms the timerfd, due timers fire after a nanosecond instead.
+            let timeout = deadline.saturating_duration_since(Instant::now()).max(Duration::from_nanos(1));
+            spec.it_value.tv_sec = timeout.as_secs() as _;
+            spec.it_value.tv_nsec = timeout.subsec_nanos() as _;
+        }
+
+        unsafe { libc::timerfd_settime(self.fd, 0, &spec, std::ptr::null_mut()) };
+    }
+}
+
+#[cfg(not(all(target_os = "linux", not(feature = "headless"))))]
+impl Timers {
+    /// Time until the earliest deadline, `None` without timers.
+    pub fn timeout() -> Option<Duration> {
+        Self::with(|t| t.timers.iter().map(|t| t.deadline).min().map(|d| d.saturating_duration_since(Instant::now())))
+    }
+
+    /// Platforms without a timerfd wait with `timeout` instead.
+    fn arm(&mut self) {}
+}
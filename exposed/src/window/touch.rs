@@ -1,5 +1,5 @@
 /// Experimental
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Touch {
     pub phase: TouchPhase,
     pub location: (f32, f32),
@@ -1,16 +1,17 @@
 use std::{
-    alloc::{alloc, Layout},
-    io::Error,
-    mem::MaybeUninit,
-    ops::{Deref, DerefMut},
+    collections::HashMap,
+    time::{Duration, Instant},
 };
 
 use crate::{
-    destroy::Destroyable,
     window::{Event, EventHandler},
+    Error,
 };
 
-use super::EventHandlerBuilder;
+use super::{
+    Context, EventHandlerBuilder, Gamepad, GamepadAxis, GamepadButton, Gesture, Key, MouseButton, ScanCode, TimerId, Touch,
+    WindowHandle,
+};
 
 pub trait ExtendedEvent {
     #[inline]
@@ -27,64 +28,431 @@ pub trait ExtendedEvent {
     fn is_running(&mut self) -> bool;
 }
 
-pub fn run<T: Event + ExtendedEvent>(mut event_handler_builder: EventHandlerBuilder) -> Result<(), Error> {
-    let mut app_container: MaybeUninit<T> = MaybeUninit::uninit();
+/// Saves the window as PNG to the path in `EXPOSED_CAPTURE` and returns true, returns false if it's not set.
+/// Call it after presenting a frame, golden image tests run the app with the variable set and compare the file.
+#[cfg(feature = "png")]
+pub fn capture_to_env(window: crate::window::WindowHandle) -> Result<bool, std::io::Error> {
+    let path = match std::env::var_os("EXPOSED_CAPTURE") {
+        Some(path) => path,
+        None => return Ok(false),
+    };
 
-    let app = unsafe { &mut *app_container.as_mut_ptr() };
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    window.capture()?.write_png(file)?;
 
-    let mut event_handler = Destroyable(unsafe { event_handler_builder.build(app) }?);
+    Ok(true)
+}
+
+/// Polls without waiting while `ExtendedEvent::is_animating` returns true, `run_with` paces frames instead.
+pub fn run<T: Event + ExtendedEvent>(mut event_handler_builder: EventHandlerBuilder) -> Result<(), Error> {
+    let mut event_handler = event_handler_builder.build::<T>()?;
 
-    while app.is_running() {
-        if app.is_animating() {
+    while event_handler.user_data_mut().is_running() {
+        if event_handler.user_data_mut().is_animating() {
             while event_handler.poll() > 0 {
                 event_handler.dispatch();
-                app.post_event();
+                event_handler.user_data_mut().post_event();
             }
 
-            app.polled();
+            event_handler.user_data_mut().polled();
         } else {
             event_handler.wait();
             event_handler.dispatch();
 
-            app.post_event();
+            event_handler.user_data_mut().post_event();
         }
     }
 
-    unsafe { (app as *mut T).drop_in_place() };
-
     Ok(())
 }
 
-pub struct HeapEventHandler<E: Event> {
-    pub user_data: Box<E>,
-    event_handler: Destroyable<EventHandler<E>>,
+/// How `run_with` spaces frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pacing {
+    /// Frames start `1 / fps` seconds apart and the thread waits for events in between, `fps` is at least 1.
+    Fps(f32),
+    /// Frames are drawn back to back, `FrameLoop::draw` is expected to block on presentation like a FIFO swapchain does.
+    Vsync,
 }
 
-impl<E: Event> Deref for HeapEventHandler<E> {
-    type Target = EventHandler<E>;
+/// Configures `run_with`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunConfig {
+    pub pacing: Pacing,
+    /// Simulated time every `FrameLoop::update` advances.
+    pub timestep: Duration,
+    /// Updates a frame may run to catch up, a longer backlog is dropped so slow frames can't snowball.
+    pub max_updates: u32,
+    /// Stops drawing while every window is minimized or occluded and waits for events instead.
+    pub pause_when_hidden: bool,
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.event_handler
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self { pacing: Pacing::Fps(60.0), timestep: Duration::from_nanos(16_666_667), max_updates: 8, pause_when_hidden: true }
     }
 }
 
-impl<E: Event> DerefMut for HeapEventHandler<E> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.event_handler
+impl RunConfig {
+    pub fn with_pacing(&mut self, pacing: Pacing) -> &mut Self {
+        self.pacing = pacing;
+        self
     }
+
+    pub fn with_timestep(&mut self, timestep: Duration) -> &mut Self {
+        self.timestep = timestep;
+        self
+    }
+
+    pub fn with_max_updates(&mut self, max_updates: u32) -> &mut Self {
+        self.max_updates = max_updates;
+        self
+    }
+
+    pub fn with_pause_when_hidden(&mut self, pause: bool) -> &mut Self {
+        self.pause_when_hidden = pause;
+        self
+    }
+}
+
+/// Frame times, the time from the start of a frame to the start of the next one, of about a second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    pub frames: u32,
+    pub average: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    pub max: Duration,
+    /// Frames longer than one and a half `target`.
+    pub dropped: u32,
 }
 
-impl<E: Event> HeapEventHandler<E> {
-    pub fn new(mut builder: EventHandlerBuilder) -> Result<HeapEventHandler<E>, Error> {
-        unsafe {
-            let user_data = alloc(Layout::new::<E>()) as *mut E;
-            if user_data.is_null() {
-                std::alloc::handle_alloc_error(Layout::new::<E>())
+impl FrameStats {
+    /// Sorts `frame_times`, the median stands for `target` when it's `None`.
+    pub fn new(frame_times: &mut [Duration], target: Option<Duration>) -> Self {
+        if frame_times.is_empty() {
+            return Self::default();
+        }
+
+        frame_times.sort_unstable();
+
+        let frames = frame_times.len() as u32;
+        let target = target.unwrap_or(frame_times[frame_times.len() / 2]);
+        let p99 = frame_times[(frame_times.len() * 99).div_ceil(100) - 1];
+
+        Self {
+            frames,
+            average: frame_times.iter().sum::<Duration>() / frames,
+            p99,
+            max: frame_times[frame_times.len() - 1],
+            dropped: frame_times.iter().filter(|&&t| t > target * 3 / 2).count() as u32,
+        }
+    }
+}
+
+/// Application of `run_with`, events arrive through `Event` like with `run`.
+pub trait FrameLoop: Event + ExtendedEvent {
+    /// Advances the simulation by exactly `dt`, it's `RunConfig::timestep`.
+    #[allow(unused)]
+    fn update(&mut self, dt: Duration) {}
+
+    /// Draws a frame, `alpha` from `0.0` to `1.0` is how far the frame is from the last update to the next one.
+    fn draw(&mut self, alpha: f32);
+
+    /// Called about once a second while frames are drawn.
+    #[allow(unused)]
+    fn frame_stats(&mut self, stats: &FrameStats) {}
+}
+
+/// Runs `T` with a fixed timestep: events are dispatched, `FrameLoop::update` catches up with real time,
+/// then `FrameLoop::draw` interpolates. Frames are paced by `config.pacing` without spinning.
+pub fn run_with<T: FrameLoop>(mut event_handler_builder: EventHandlerBuilder, config: RunConfig) -> Result<(), Error> {
+    let mut event_handler = event_handler_builder.build::<Paced<T>>()?;
+
+    let timestep = config.timestep.max(Duration::from_micros(1));
+    let interval = match config.pacing {
+        Pacing::Fps(fps) => Some(Duration::from_secs_f64(1.0 / fps.max(1.0) as f64)),
+        Pacing::Vsync => None,
+    };
+
+    let mut previous = Instant::now();
+    let mut next_frame = previous;
+    let mut accumulator = Duration::ZERO;
+    let mut frame_times = Vec::new();
+    let mut stats_start = previous;
+
+    while event_handler.user_data_mut().inner.is_running() {
+        if config.pause_when_hidden && event_handler.user_data().is_hidden() {
+            event_handler.wait();
+            event_handler.dispatch();
+            event_handler.user_data_mut().inner.post_event();
+
+            // Time spent hidden is neither simulated nor counted as a frame.
+            previous = Instant::now();
+            next_frame = previous;
+            continue;
+        }
+
+        while event_handler.poll() > 0 {
+            event_handler.dispatch();
+            event_handler.user_data_mut().inner.post_event();
+        }
+
+        let app = &mut event_handler.user_data_mut().inner;
+        app.polled();
+
+        let now = Instant::now();
+        let frame_time = now - previous;
+        previous = now;
+
+        accumulator += frame_time.min(timestep * config.max_updates);
+        while accumulator >= timestep {
+            app.update(timestep);
+            accumulator -= timestep;
+        }
+
+        app.draw(accumulator.as_secs_f32() / timestep.as_secs_f32());
+
+        frame_times.push(frame_time);
+        if now - stats_start >= Duration::from_secs(1) {
+            app.frame_stats(&FrameStats::new(&mut frame_times, interval));
+            frame_times.clear();
+            stats_start = now;
+        }
+
+        if let Some(interval) = interval {
+            next_frame += interval;
+            if next_frame < now {
+                next_frame = now + interval;
+            }
+
+            Paced::wait_until(&mut event_handler, next_frame);
+        }
+    }
+
+    Ok(())
+}
+
+/// Tracks which windows are hidden and takes the frame timer before `T` sees the callbacks.
+struct Paced<T: FrameLoop> {
+    inner: T,
+    context: Context,
+    hidden: HashMap<WindowHandle, bool>,
+    frame_timer: Option<TimerId>,
+}
+
+impl<T: FrameLoop> Paced<T> {
+    /// True once every window that reported its state is minimized or occluded.
+    fn is_hidden(&self) -> bool {
+        !self.hidden.is_empty() && self.hidden.values().all(|&hidden| hidden)
+    }
+
+    /// Dispatches events until `deadline`, returns early if the windows get hidden or the application stops.
+    fn wait_until(event_handler: &mut EventHandler<Self>, deadline: Instant) {
+        let delay = deadline.saturating_duration_since(Instant::now());
+        if delay.is_zero() {
+            return;
+        }
+
+        let paced = event_handler.user_data_mut();
+        let timer = paced.context.set_timer(delay, false);
+        paced.frame_timer = Some(timer);
+
+        loop {
+            let paced = event_handler.user_data_mut();
+            if paced.frame_timer.is_none() || !paced.inner.is_running() || paced.is_hidden() {
+                break;
             }
 
-            let event_handler = Destroyable(builder.build(user_data)?);
+            event_handler.wait();
+            event_handler.dispatch();
+            event_handler.user_data_mut().inner.post_event();
+        }
 
-            Ok(Self { user_data: Box::from_raw(user_data), event_handler })
+        let paced = event_handler.user_data_mut();
+        if paced.frame_timer.take().is_some() {
+            paced.context.cancel_timer(timer);
         }
     }
+
+    fn set_hidden(&mut self, window: WindowHandle, hidden: bool) {
+        self.hidden.insert(window, hidden);
+    }
+}
+
+impl<T: FrameLoop> Event for Paced<T> {
+    fn create(context: Context) -> Option<Self> {
+        Some(Self { inner: T::create(context)?, context, hidden: HashMap::new(), frame_timer: None })
+    }
+
+    fn render(&mut self, window: WindowHandle) {
+        self.set_hidden(window, false);
+        self.inner.render(window)
+    }
+
+    fn low_render(&mut self, window: WindowHandle) {
+        self.set_hidden(window, false);
+        self.inner.low_render(window)
+    }
+
+    fn resized(&mut self, window: WindowHandle, width: i32, height: i32) {
+        if width > 0 && height > 0 {
+            self.set_hidden(window, false);
+        }
+        self.inner.resized(window, width, height)
+    }
+
+    fn focused(&mut self, window: WindowHandle, focused: bool) {
+        if focused {
+            self.set_hidden(window, false);
+        }
+        self.inner.focused(window, focused)
+    }
+
+    fn minimized(&mut self, window: WindowHandle) {
+        self.set_hidden(window, true);
+        self.inner.minimized(window)
+    }
+
+    fn show(&mut self, window: WindowHandle) {
+        self.set_hidden(window, false);
+        self.inner.show(window)
+    }
+
+    fn occluded(&mut self, window: WindowHandle, occluded: bool) {
+        self.set_hidden(window, occluded);
+        self.inner.occluded(window, occluded)
+    }
+
+    fn maximized(&mut self, window: WindowHandle) {
+        self.inner.maximized(window)
+    }
+
+    fn moved(&mut self, window: WindowHandle, x: i32, y: i32) {
+        self.inner.moved(window, x, y)
+    }
+
+    fn file_received(&mut self, window: WindowHandle, path: String) {
+        self.inner.file_received(window, path)
+    }
+
+    fn close_requested(&mut self, window: WindowHandle) {
+        self.inner.close_requested(window)
+    }
+
+    fn destroyed(&mut self, window: WindowHandle) {
+        self.hidden.remove(&window);
+        self.inner.destroyed(window)
+    }
+
+    fn key_down(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        self.inner.key_down(window, key, scancode)
+    }
+
+    fn key_up(&mut self, window: WindowHandle, key: Key, scancode: ScanCode) {
+        self.inner.key_up(window, key, scancode)
+    }
+
+    fn received_character(&mut self, window: WindowHandle, character: char) {
+        self.inner.received_character(window, character)
+    }
+
+    fn mouse_wheel(&mut self, window: WindowHandle, delta_x: f32, delta_y: f32) {
+        self.inner.mouse_wheel(window, delta_x, delta_y)
+    }
+
+    fn mouse_button_down(&mut self, window: WindowHandle, button: MouseButton) {
+        self.inner.mouse_button_down(window, button)
+    }
+
+    fn mouse_button_release(&mut self, window: WindowHandle, button: MouseButton) {
+        self.inner.mouse_button_release(window, button)
+    }
+
+    fn cursor_moved(&mut self, window: WindowHandle, position_x: i32, position_y: i32) {
+        self.inner.cursor_moved(window, position_x, position_y)
+    }
+
+    fn cursor_entered(&mut self, window: WindowHandle) {
+        self.inner.cursor_entered(window)
+    }
+
+    fn cursor_left(&mut self, window: WindowHandle) {
+        self.inner.cursor_left(window)
+    }
+
+    fn raw_mouse_motion(&mut self, delta_x: i32, delta_y: i32) {
+        self.inner.raw_mouse_motion(delta_x, delta_y)
+    }
+
+    fn touch(&mut self, window: WindowHandle, touch: Touch, pointer_count: usize) {
+        self.inner.touch(window, touch, pointer_count)
+    }
+
+    fn touch_end(&mut self, window: WindowHandle) {
+        self.inner.touch_end(window)
+    }
+
+    fn gesture_begin(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.inner.gesture_begin(window, gesture)
+    }
+
+    fn gesture_update(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.inner.gesture_update(window, gesture)
+    }
+
+    fn gesture_end(&mut self, window: WindowHandle, gesture: Gesture) {
+        self.inner.gesture_end(window, gesture)
+    }
+
+    fn gamepad_connected(&mut self, gamepad: Gamepad, name: String) {
+        self.inner.gamepad_connected(gamepad, name)
+    }
+
+    fn gamepad_disconnected(&mut self, gamepad: Gamepad) {
+        self.inner.gamepad_disconnected(gamepad)
+    }
+
+    fn gamepad_button_down(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        self.inner.gamepad_button_down(gamepad, button)
+    }
+
+    fn gamepad_button_up(&mut self, gamepad: Gamepad, button: GamepadButton) {
+        self.inner.gamepad_button_up(gamepad, button)
+    }
+
+    fn axis_motion(&mut self, gamepad: Gamepad, axis: GamepadAxis, value: f32) {
+        self.inner.axis_motion(gamepad, axis, value)
+    }
+
+    fn scale_factor_changed() {
+        T::scale_factor_changed()
+    }
+
+    fn timer_fired(&mut self, timer: TimerId) {
+        if self.frame_timer == Some(timer) {
+            self.frame_timer = None;
+        } else {
+            self.inner.timer_fired(timer)
+        }
+    }
+}
+
+#[test]
+fn frame_stats_test() {
+    let ms = Duration::from_millis;
+
+    let mut frame_times = vec![ms(16); 98];
+    frame_times.extend([ms(40), ms(17)]);
+    let stats = FrameStats::new(&mut frame_times, Some(ms(16)));
+
+    assert_eq!(stats.frames, 100);
+    assert_eq!(stats.max, ms(40));
+    assert_eq!(stats.p99, ms(17));
+    assert_eq!(stats.dropped, 1);
+    assert_eq!(stats.average, ms(16) + ms(25) / 100);
+
+    let mut vsync = vec![ms(7), ms(7), ms(7), ms(14)];
+    assert_eq!(FrameStats::new(&mut vsync, None).dropped, 1);
+    assert_eq!(FrameStats::new(&mut [], None), FrameStats::default());
 }
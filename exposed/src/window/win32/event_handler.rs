@@ -1,22 +1,24 @@
 use std::{
     fmt::Debug,
-    io::{Error, ErrorKind},
-    mem::size_of,
+    io::Error,
+    mem::{size_of, zeroed},
     ptr::{null, null_mut},
 };
 
 use windows_sys::Win32::{
     Foundation::HMODULE,
     System::{LibraryLoader::GetModuleHandleW, Threading::GetCurrentThreadId},
+    UI::Shell::SetCurrentProcessExplicitAppUserModelID,
     UI::WindowsAndMessaging::{
-        DispatchMessageW, GetMessageW, LoadCursorW, PeekMessageW, RegisterClassExW, TranslateMessage, UnregisterClassW,
-        CS_HREDRAW, CS_OWNDC, CS_VREDRAW, HCURSOR, HICON, IDC_ARROW, MSG, PM_REMOVE, WNDCLASSEXW,
+        DispatchMessageW, GetMessageW, LoadCursorW, MsgWaitForMultipleObjectsEx, PeekMessageW, RegisterClassExW,
+        TranslateMessage, UnregisterClassW, CS_HREDRAW, CS_OWNDC, CS_VREDRAW, HCURSOR, HICON, IDC_ARROW, MSG,
+        MWMO_INPUTAVAILABLE, PM_REMOVE, QS_ALLINPUT, WM_NULL, WM_QUIT, WNDCLASSEXW,
     },
 };
 
 use crate::{
     destroy::{Destroy, Destroyable},
-    window::{platform, win32::win_proc, Context, Event},
+    window::{platform, win32::win_proc, Context, Event, Requests, Timers},
 };
 
 use super::ThreadContext;
@@ -34,12 +36,6 @@ impl<E: Event> Debug for EventHandler<E> {
     }
 }
 
-impl<E: Event> Into<crate::window::EventHandler<E>> for EventHandler<E> {
-    fn into(self) -> crate::window::EventHandler<E> {
-        crate::window::EventHandler(self)
-    }
-}
-
 impl<E: Event> Destroy for EventHandler<E> {
     fn destroy(&mut self) -> Result<(), Error> {
         let context = unsafe { ThreadContext::get_ref() };
@@ -47,6 +43,8 @@ impl<E: Event> Destroy for EventHandler<E> {
         context.window_class = null();
         let result = unsafe { UnregisterClassW(self.window_class.as_ptr(), 0) };
         context.user_data = null_mut();
+        Timers::reset();
+        Requests::reset();
 
         if result == 0 {
             return Err(Error::last_os_error());
@@ -57,19 +55,64 @@ impl<E: Event> Destroy for EventHandler<E> {
 }
 
 impl<E: Event> EventHandler<E> {
+    /// A due timer is reported with an empty `WM_NULL` message, proxies post one to wake the thread.
     pub fn poll(&mut self) -> i32 {
-        unsafe { PeekMessageW(&mut self.msg, 0, 0, 0, PM_REMOVE) }
+        if unsafe { PeekMessageW(&mut self.msg, 0, 0, 0, PM_REMOVE) } != 0 {
+            return 1;
+        }
+
+        if Timers::has_events() {
+            self.msg = unsafe { zeroed() };
+            return 1;
+        }
+
+        0
     }
 
+    /// Waits with `MsgWaitForMultipleObjectsEx` until a message arrives or the next timer is due.
     pub fn wait(&mut self) -> i32 {
-        unsafe { GetMessageW(&mut self.msg, 0, 0, 0) }
+        loop {
+            if Timers::has_events() {
+                self.msg = unsafe { zeroed() };
+                return 1;
+            }
+
+            let timeout = match Timers::timeout() {
+                Some(timeout) => timeout.as_nanos().div_ceil(1_000_000).min(u32::MAX as u128 - 1) as u32,
+                None => return unsafe { GetMessageW(&mut self.msg, 0, 0, 0) },
+            };
+
+            unsafe {
+                MsgWaitForMultipleObjectsEx(0, null(), timeout, QS_ALLINPUT, MWMO_INPUTAVAILABLE);
+
+                if PeekMessageW(&mut self.msg, 0, 0, 0, PM_REMOVE) != 0 {
+                    return (self.msg.message != WM_QUIT) as i32;
+                }
+            }
+        }
     }
 
     #[inline]
     pub fn dispatch(&mut self) {
         unsafe {
-            TranslateMessage(&self.msg);
-            DispatchMessageW(&self.msg);
+            if self.msg.message != WM_NULL || self.msg.hwnd != 0 {
+                TranslateMessage(&self.msg);
+                DispatchMessageW(&self.msg);
+            }
+        }
+
+        self.dispatch_timers();
+        Requests::run();
+    }
+
+    fn dispatch_timers(&mut self) {
+        let user_data = unsafe { ThreadContext::user_data::<E>() };
+        if user_data.is_null() {
+            return;
+        }
+
+        while let Some(timer) = Timers::pop() {
+            unsafe { &mut *user_data }.timer_fired(timer);
         }
     }
 }
@@ -82,22 +125,48 @@ pub struct EventHandlerBuilder {
     pub class_style: u32,
     pub icon: HICON,
     pub cursor: HCURSOR,
+    pub app_id: Option<Vec<u16>>,
 }
 
 impl Default for EventHandlerBuilder {
     fn default() -> Self {
-        Self { class_style: CS_OWNDC | CS_HREDRAW | CS_VREDRAW, icon: 0, cursor: unsafe { LoadCursorW(0, IDC_ARROW) } }
+        Self {
+            class_style: CS_OWNDC | CS_HREDRAW | CS_VREDRAW,
+            icon: 0,
+            cursor: unsafe { LoadCursorW(0, IDC_ARROW) },
+            app_id: None,
+        }
     }
 }
 
 impl EventHandlerBuilder {
-    pub unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<platform::EventHandler<E>, Error> {
+    pub fn with_gamepad_mappings(&mut self, _mappings: &str) -> &mut Self {
+        self
+    }
+
+    /// Taskbar groups windows by `class`, `name` is unused.
+    pub fn with_app_id(&mut self, _name: &str, class: &str) -> &mut Self {
+        self.app_id = if class.is_empty() { None } else { Some(format!("{class}\0").encode_utf16().collect()) };
+        self
+    }
+
+    pub(crate) unsafe fn build<E: Event>(&mut self, user_data: *mut E) -> Result<platform::EventHandler<E>, crate::Error> {
         if unsafe { HINSTANCE } == 0 {
             unsafe { HINSTANCE = GetModuleHandleW(null()) };
         }
 
         if !ThreadContext::get().window_class.is_null() {
-            return Err(Error::new(ErrorKind::Other, "Single EventHandler is allowed per thread."));
+            return Err(crate::Error::EventHandlerExists);
+        }
+
+        if let Some(app_id) = &self.app_id {
+            let result = SetCurrentProcessExplicitAppUserModelID(app_id.as_ptr());
+            if result < 0 {
+                return Err(crate::Error::BackendError {
+                    function: "SetCurrentProcessExplicitAppUserModelID",
+                    code: result as _,
+                });
+            }
         }
 
         let thread_id = GetCurrentThreadId();
@@ -121,7 +190,7 @@ impl EventHandlerBuilder {
 
         let class_atom = unsafe { RegisterClassExW(&wc) };
         if class_atom == 0 {
-            return Err(Error::last_os_error());
+            return Err(Error::last_os_error().into());
         }
         let event_handler = Destroyable(EventHandler { window_class, msg: std::mem::zeroed(), _mark: std::marker::PhantomData });
 
@@ -131,7 +200,7 @@ impl EventHandlerBuilder {
         if let Some(event) = E::create(Context(ThreadContext::get_ref())) {
             std::ptr::write(user_data, event);
         } else {
-            return Err(Error::new(ErrorKind::Other, "Failed to create event."));
+            return Err(crate::Error::CreateFailed);
         }
 
         context.user_data = user_data as _;
@@ -1,10 +1,14 @@
 mod context;
 mod event_handler;
+mod pixel_surface;
+#[cfg(any(feature = "rwh_05", feature = "rwh_06"))]
+mod raw_handle;
 mod win_proc;
 mod window;
 
 pub use context::*;
 pub use event_handler::*;
+pub use pixel_surface::*;
 pub use win_proc::*;
 pub use window::*;
 
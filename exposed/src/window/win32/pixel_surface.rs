@@ -0,0 +1,148 @@
+use std::{
+    io::Error,
+    mem::{size_of, zeroed},
+    ptr::null_mut,
+};
+
+use windows_sys::Win32::Graphics::Gdi::{
+    BitBlt, CreateCompatibleDC, CreateDIBSection, DeleteDC, DeleteObject, GetDC, ReleaseDC, SelectObject, BITMAPINFO,
+    BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP, HDC, HGDIOBJ, SRCCOPY,
+};
+
+use crate::{destroy::Destroy, window::Rect};
+
+use super::WindowHandle;
+
+/// Pixels live in a DIB section selected into a memory DC, presenting blits from it.
+#[derive(Debug)]
+pub struct PixelSurface {
+    pub window: WindowHandle,
+    pub memory_dc: HDC,
+    pub bitmap: HBITMAP,
+    pub default_bitmap: HGDIOBJ,
+    pub bits: *mut u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelSurface {
+    pub fn new(window: WindowHandle) -> Result<Self, Error> {
+        let memory_dc = unsafe { CreateCompatibleDC(0) };
+        if memory_dc == 0 {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(Self { window, memory_dc, bitmap: 0, default_bitmap: 0, bits: null_mut(), width: 0, height: 0 })
+    }
+
+    pub fn buffer_mut(&mut self) -> Result<&mut [u32], Error> {
+        let size = self.window.client_size()?;
+        let (width, height) = (size.width.max(0) as u32, size.height.max(0) as u32);
+
+        if (width, height) != (self.width, self.height) {
+            self.resize(width, height)?;
+        }
+
+        if self.bits.is_null() {
+            return Ok(&mut []);
+        }
+
+        Ok(unsafe { std::slice::from_raw_parts_mut(self.bits, (self.width * self.height) as usize) })
+    }
+
+    pub fn present(&mut self) -> Result<(), Error> {
+        self.present_with_damage(&[Rect { left: 0, top: 0, right: self.width as _, bottom: self.height as _ }])
+    }
+
+    pub fn present_with_damage(&mut self, damage: &[Rect]) -> Result<(), Error> {
+        if self.bitmap == 0 {
+            return Ok(());
+        }
+
+        unsafe {
+            let dc = GetDC(self.window.0);
+            if dc == 0 {
+                return Err(Error::last_os_error());
+            }
+
+            for rect in damage {
+                let left = rect.left.clamp(0, self.width as _);
+                let top = rect.top.clamp(0, self.height as _);
+                let right = rect.right.clamp(left, self.width as _);
+                let bottom = rect.bottom.clamp(top, self.height as _);
+
+                if left == right || top == bottom {
+                    continue;
+                }
+
+                BitBlt(dc, left, top, right - left, bottom - top, self.memory_dc, left, top, SRCCOPY);
+            }
+
+            ReleaseDC(self.window.0, dc);
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        self.destroy_bitmap();
+
+        self.width = width;
+        self.height = height;
+
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+
+        unsafe {
+            let mut info: BITMAPINFO = zeroed();
+            info.bmiHeader = BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as _,
+                biWidth: width as _,
+                // Negative height makes rows go from the top.
+                biHeight: -(height as i32),
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB,
+                ..zeroed()
+            };
+
+            let mut bits = null_mut();
+            self.bitmap = CreateDIBSection(self.memory_dc, &info, DIB_RGB_COLORS, &mut bits, 0, 0);
+            if self.bitmap == 0 {
+                return Err(Error::last_os_error());
+            }
+
+            self.bits = bits.cast();
+            self.default_bitmap = SelectObject(self.memory_dc, self.bitmap);
+        }
+
+        Ok(())
+    }
+
+    fn destroy_bitmap(&mut self) {
+        if self.bitmap == 0 {
+            return;
+        }
+
+        unsafe {
+            SelectObject(self.memory_dc, self.default_bitmap);
+            DeleteObject(self.bitmap);
+        }
+
+        self.bitmap = 0;
+        self.bits = null_mut();
+    }
+}
+
+impl Destroy for PixelSurface {
+    fn destroy(&mut self) -> Result<(), Error> {
+        self.destroy_bitmap();
+
+        if unsafe { DeleteDC(self.memory_dc) } == 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
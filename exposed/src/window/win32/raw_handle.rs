@@ -0,0 +1,45 @@
+#[cfg(feature = "rwh_06")]
+use std::num::NonZeroIsize;
+
+use super::{Context, WindowHandle, HINSTANCE};
+
+#[cfg(feature = "rwh_05")]
+impl WindowHandle {
+    pub fn raw_window_handle_05(self) -> rwh_05::RawWindowHandle {
+        let mut handle = rwh_05::Win32WindowHandle::empty();
+        handle.hwnd = self.0 as _;
+        handle.hinstance = unsafe { HINSTANCE } as _;
+        rwh_05::RawWindowHandle::Win32(handle)
+    }
+
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        rwh_05::RawDisplayHandle::Windows(rwh_05::WindowsDisplayHandle::empty())
+    }
+}
+
+#[cfg(feature = "rwh_05")]
+impl Context {
+    pub fn raw_display_handle_05(self) -> rwh_05::RawDisplayHandle {
+        rwh_05::RawDisplayHandle::Windows(rwh_05::WindowsDisplayHandle::empty())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl WindowHandle {
+    pub fn raw_window_handle_06(self) -> Result<rwh_06::RawWindowHandle, rwh_06::HandleError> {
+        let mut handle = rwh_06::Win32WindowHandle::new(NonZeroIsize::new(self.0).ok_or(rwh_06::HandleError::Unavailable)?);
+        handle.hinstance = NonZeroIsize::new(unsafe { HINSTANCE });
+        Ok(handle.into())
+    }
+
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Ok(rwh_06::WindowsDisplayHandle::new().into())
+    }
+}
+
+#[cfg(feature = "rwh_06")]
+impl Context {
+    pub fn raw_display_handle_06(self) -> Result<rwh_06::RawDisplayHandle, rwh_06::HandleError> {
+        Ok(rwh_06::WindowsDisplayHandle::new().into())
+    }
+}
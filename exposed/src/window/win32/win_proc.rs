@@ -8,12 +8,20 @@ use windows_sys::Win32::{
     },
 };
 
-use crate::window::{win32::ThreadContext, Event, Key, MouseButton};
+use crate::window::{catch_panic, win32::ThreadContext, Event, Key, MouseButton};
 
 use super::WindowHandle;
 
+/// Panics can't unwind into the system, they continue when the call of `EventHandler` that dispatched the message returns.
 #[inline(never)]
 pub unsafe extern "system" fn win_proc<E: Event>(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match catch_panic(|| handle_message::<E>(hwnd, msg, wparam, lparam)) {
+        Some(result) => result,
+        None => windows_sys::Win32::UI::WindowsAndMessaging::DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+unsafe fn handle_message<E: Event>(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     use windows_sys::Win32::{
         Devices::HumanInterfaceDevice::MOUSE_MOVE_RELATIVE,
         UI::{
@@ -170,6 +178,10 @@ pub unsafe extern "system" fn win_proc<E: Event>(hwnd: HWND, msg: u32, wparam: W
             let w = loword(lparam as u32) as i32;
             let h = hiword(lparam as u32) as i32;
 
+            if wparam as u32 == SIZE_MINIMIZED {
+                handler.minimized(WindowHandle(hwnd).into());
+            }
+
             handler.resized(WindowHandle(hwnd).into(), w, h);
             0
         }
@@ -1,21 +1,35 @@
 use std::{
     io::Error,
-    mem::{transmute, zeroed},
-    ptr::null,
+    mem::{size_of, transmute, zeroed},
+    ptr::{null, null_mut},
 };
 
 use windows_sys::{
     core::PCWSTR,
     w,
     Win32::{
-        Foundation::{ERROR_INVALID_HANDLE, HWND, RECT},
-        Graphics::Gdi::InvalidateRect,
+        Foundation::{ERROR_INVALID_HANDLE, HWND, POINT, RECT},
+        Graphics::{
+            Dwm::{DwmEnableBlurBehindWindow, DWM_BB_BLURREGION, DWM_BB_ENABLE, DWM_BLURBEHIND},
+            Gdi::{
+                BitBlt, ClientToScreen, CreateCompatibleDC, CreateDIBSection, CreateRectRgn, DeleteDC, DeleteObject, GetDC,
+                GetMonitorInfoW, InvalidateRect, MonitorFromWindow, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER,
+                BI_RGB, DIB_RGB_COLORS, MONITORINFO, MONITOR_DEFAULTTONEAREST, SRCCOPY,
+            },
+        },
+        System::Threading::GetCurrentThreadId,
         UI::{
             HiDpi::GetDpiForWindow,
             Input::KeyboardAndMouse::{ReleaseCapture, SetCapture},
             WindowsAndMessaging::{
-                CreateWindowExW, DestroyWindow, GetClientRect, GetWindowRect, GetWindowTextW, SetWindowTextW, ShowWindowAsync,
-                CW_USEDEFAULT, HMENU, SW_SHOWDEFAULT, WS_EX_ACCEPTFILES, WS_EX_OVERLAPPEDWINDOW, WS_OVERLAPPEDWINDOW,
+                CreateWindowExW, DestroyWindow, FlashWindowEx, GetClientRect, GetWindowLongPtrW, GetWindowRect, GetWindowTextW,
+                GetWindowThreadProcessId, SendMessageW, SetLayeredWindowAttributes, SetWindowLongPtrW, SetWindowPos,
+                SetWindowTextW, ShowWindowAsync, CW_USEDEFAULT, FLASHWINFO, FLASHW_ALL, FLASHW_STOP, FLASHW_TIMERNOFG,
+                FLASHW_TRAY, GWL_EXSTYLE, GWL_STYLE, HMENU, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTCAPTION, HTLEFT, HTRIGHT,
+                HTTOP, HTTOPLEFT, HTTOPRIGHT, HWND_NOTOPMOST, HWND_TOPMOST, LWA_ALPHA, SWP_FRAMECHANGED, SWP_NOACTIVATE,
+                SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_SHOWDEFAULT, WM_NCLBUTTONDOWN, WS_BORDER, WS_CAPTION, WS_EX_ACCEPTFILES,
+                WS_EX_APPWINDOW, WS_EX_DLGMODALFRAME, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_OVERLAPPEDWINDOW, WS_EX_TOOLWINDOW,
+                WS_EX_TOPMOST, WS_MAXIMIZEBOX, WS_MINIMIZEBOX, WS_OVERLAPPEDWINDOW, WS_POPUP, WS_SYSMENU, WS_THICKFRAME,
             },
         },
     },
@@ -23,7 +37,7 @@ use windows_sys::{
 
 use crate::{
     destroy::Destroy,
-    window::{Rect, Size},
+    window::{popup_position, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind},
 };
 
 use std::ffi::c_int;
@@ -39,6 +53,8 @@ pub struct WindowHandle(pub HWND);
 
 impl Destroy for WindowHandle {
     fn destroy(&mut self) -> Result<(), Error> {
+        self.alive()?;
+
         if unsafe { DestroyWindow(self.0) } == 0 {
             Err(Error::last_os_error())
         } else {
@@ -47,34 +63,36 @@ impl Destroy for WindowHandle {
     }
 }
 
-impl Into<crate::window::WindowHandle> for WindowHandle {
-    fn into(self) -> crate::window::WindowHandle {
-        crate::window::WindowHandle(self)
-    }
-}
-
 impl WindowHandle {
     pub fn show(self) -> Result<(), Error> {
+        self.alive()?;
+
         if unsafe { ShowWindowAsync(self.0, SW_SHOWDEFAULT) } == 0 {
-            Err(Error::new(std::io::ErrorKind::Other, "Failed to show window. Window might be not valid."))
+            Err(crate::Error::BackendError { function: "ShowWindowAsync", code: last_error() }.into())
         } else {
             Ok(())
         }
     }
 
     pub fn update(self) -> Result<(), Error> {
+        self.alive()?;
+
         todo!()
     }
 
     pub fn redraw(self) -> Result<(), Error> {
+        self.alive()?;
+
         if unsafe { InvalidateRect(self.0, null(), 0) } == 0 {
-            Err(Error::new(std::io::ErrorKind::Other, "Failed to invalidate the window."))
+            Err(crate::Error::BackendError { function: "InvalidateRect", code: last_error() }.into())
         } else {
             Ok(())
         }
     }
 
     pub fn window_title(self) -> Result<String, Error> {
+        self.alive()?;
+
         let mut buffer = [0u16; 255];
 
         let len = unsafe { GetWindowTextW(self.0, buffer.as_mut_ptr() as _, 255) };
@@ -86,6 +104,8 @@ impl WindowHandle {
     }
 
     pub fn set_window_title(self, title: &str) -> Result<(), Error> {
+        self.alive()?;
+
         let title_u16 = utf8_to_utf16_null(&title);
 
         if unsafe { SetWindowTextW(self.0, title_u16.as_ptr()) } == 0 {
@@ -96,6 +116,8 @@ impl WindowHandle {
     }
 
     pub fn dpi(self) -> Result<u32, Error> {
+        self.alive()?;
+
         let dpi = unsafe { GetDpiForWindow(self.0) };
         if dpi == 0 {
             // https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getdpiforwindow
@@ -111,6 +133,8 @@ impl WindowHandle {
     }
 
     pub fn release_capture(self) -> Result<(), Error> {
+        self.alive()?;
+
         unsafe {
             if ReleaseCapture() == 0 {
                 return Err(Error::last_os_error());
@@ -121,6 +145,8 @@ impl WindowHandle {
     }
 
     pub fn client_size(self) -> Result<Size, Error> {
+        self.alive()?;
+
         let rect = self.client_rect()?;
 
         let width = rect.right - rect.left;
@@ -130,6 +156,8 @@ impl WindowHandle {
     }
 
     pub fn client_rect(self) -> Result<Rect, Error> {
+        self.alive()?;
+
         unsafe {
             let mut rect = zeroed();
             if GetClientRect(self.0, &mut rect) == 0 {
@@ -141,6 +169,8 @@ impl WindowHandle {
     }
 
     pub fn window_rect(self) -> Result<Rect, Error> {
+        self.alive()?;
+
         unsafe {
             let mut rect = zeroed();
             if GetWindowRect(self.0, &mut rect) == 0 {
@@ -150,6 +180,202 @@ impl WindowHandle {
             }
         }
     }
+
+    /// `opacity` is clamped to `0.0..=1.0`.
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let ex_style = GetWindowLongPtrW(self.0, GWL_EXSTYLE);
+            if ex_style & WS_EX_LAYERED as isize == 0 {
+                SetWindowLongPtrW(self.0, GWL_EXSTYLE, ex_style | WS_EX_LAYERED as isize);
+            }
+
+            let alpha = (opacity.clamp(0.0, 1.0) * 255.0) as u8;
+            if SetLayeredWindowAttributes(self.0, 0, alpha, LWA_ALPHA) == 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn capture(self) -> Result<Image, Error> {
+        self.alive()?;
+
+        unsafe {
+            let mut rect = zeroed();
+            if GetClientRect(self.0, &mut rect) == 0 {
+                return Err(Error::last_os_error());
+            }
+
+            let (width, height) = ((rect.right - rect.left).max(0), (rect.bottom - rect.top).max(0));
+
+            let dc = GetDC(self.0);
+            if dc == 0 {
+                return Err(Error::last_os_error());
+            }
+
+            let memory_dc = CreateCompatibleDC(dc);
+
+            let mut info: BITMAPINFO = zeroed();
+            info.bmiHeader = BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as _,
+                biWidth: width,
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB,
+                ..zeroed()
+            };
+
+            let mut bits = null_mut();
+            let bitmap = CreateDIBSection(memory_dc, &info, DIB_RGB_COLORS, &mut bits, 0, 0);
+
+            let result = if bitmap == 0 {
+                Err(Error::last_os_error())
+            } else {
+                let default_bitmap = SelectObject(memory_dc, bitmap);
+                BitBlt(memory_dc, 0, 0, width, height, dc, 0, 0, SRCCOPY);
+                SelectObject(memory_dc, default_bitmap);
+
+                let bgra = std::slice::from_raw_parts(bits as *const u8, width as usize * height as usize * 4);
+
+                let mut image = Image::new(width as _, height as _);
+                for (rgba, bgra) in image.pixels.chunks_exact_mut(4).zip(bgra.chunks_exact(4)) {
+                    rgba.copy_from_slice(&[bgra[2], bgra[1], bgra[0], u8::MAX]);
+                }
+
+                DeleteObject(bitmap);
+
+                Ok(image)
+            };
+
+            DeleteDC(memory_dc);
+            ReleaseDC(self.0, dc);
+
+            result
+        }
+    }
+
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let style = decorated_style(GetWindowLongPtrW(self.0, GWL_STYLE) as u32, decorations);
+            SetWindowLongPtrW(self.0, GWL_STYLE, style as isize);
+
+            let flags = SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE;
+            if SetWindowPos(self.0, 0, 0, 0, 0, 0, flags) == 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn drag_move(self) -> Result<(), Error> {
+        self.alive()?;
+
+        self.non_client_drag(HTCAPTION)
+    }
+
+    pub fn drag_resize(self, edge: ResizeEdge) -> Result<(), Error> {
+        self.alive()?;
+
+        self.non_client_drag(match edge {
+            ResizeEdge::TopLeft => HTTOPLEFT,
+            ResizeEdge::Top => HTTOP,
+            ResizeEdge::TopRight => HTTOPRIGHT,
+            ResizeEdge::Right => HTRIGHT,
+            ResizeEdge::BottomRight => HTBOTTOMRIGHT,
+            ResizeEdge::Bottom => HTBOTTOM,
+            ResizeEdge::BottomLeft => HTBOTTOMLEFT,
+            ResizeEdge::Left => HTLEFT,
+        })
+    }
+
+    pub fn set_always_on_top(self, always_on_top: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        let insert_after = if always_on_top { HWND_TOPMOST } else { HWND_NOTOPMOST };
+
+        if unsafe { SetWindowPos(self.0, insert_after, 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE) } == 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Tool windows have no taskbar button.
+    pub fn set_skip_taskbar(self, skip_taskbar: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        unsafe {
+            let ex_style = GetWindowLongPtrW(self.0, GWL_EXSTYLE) as u32;
+            let ex_style =
+                if skip_taskbar { (ex_style & !WS_EX_APPWINDOW) | WS_EX_TOOLWINDOW } else { ex_style & !WS_EX_TOOLWINDOW };
+            SetWindowLongPtrW(self.0, GWL_EXSTYLE, ex_style as isize);
+
+            let flags = SWP_FRAMECHANGED | SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE;
+            if SetWindowPos(self.0, 0, 0, 0, 0, 0, flags) == 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        self.alive()?;
+
+        let flags = match attention {
+            Some(UserAttention::Informational) => FLASHW_TRAY | FLASHW_TIMERNOFG,
+            Some(UserAttention::Critical) => FLASHW_ALL | FLASHW_TIMERNOFG,
+            None => FLASHW_STOP,
+        };
+
+        let info = FLASHWINFO { cbSize: size_of::<FLASHWINFO>() as _, hwnd: self.0, dwFlags: flags, uCount: 0, dwTimeout: 0 };
+        unsafe { FlashWindowEx(&info) };
+
+        Ok(())
+    }
+
+    pub fn set_visible_on_all_workspaces(self, _visible: bool) -> Result<(), Error> {
+        self.alive()?;
+
+        Err(crate::Error::Unsupported("virtual desktops on Windows").into())
+    }
+
+    pub fn move_to_workspace(self, _workspace: u32) -> Result<(), Error> {
+        self.alive()?;
+
+        Err(crate::Error::Unsupported("virtual desktops on Windows").into())
+    }
+
+    /// Windows can only be changed from the thread that created them.
+    pub(crate) fn alive(self) -> Result<(), Error> {
+        match unsafe { GetWindowThreadProcessId(self.0, null_mut()) } {
+            0 => Err(crate::Error::WindowDestroyed.into()),
+            thread if thread != unsafe { GetCurrentThreadId() } => Err(crate::Error::WrongThread.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Pretends the press happened on the frame so the system runs its move or size loop.
+    fn non_client_drag(self, hit_test: u32) -> Result<(), Error> {
+        unsafe {
+            ReleaseCapture();
+            SendMessageW(self.0, WM_NCLBUTTONDOWN, hit_test as _, 0);
+        }
+
+        Ok(())
+    }
+}
+
+/// Code of the last failed call of the thread for `Error::BackendError`.
+fn last_error() -> i64 {
+    Error::last_os_error().raw_os_error().unwrap_or(0) as _
 }
 
 pub fn utf8_to_utf16_null(text: &str) -> Vec<u16> {
@@ -158,6 +384,15 @@ pub fn utf8_to_utf16_null(text: &str) -> Vec<u16> {
     utf16
 }
 
+/// Resizable popup style without a title bar, keeps the minimize and maximize behavior.
+fn decorated_style(style: u32, decorations: bool) -> u32 {
+    if decorations {
+        (style & !WS_POPUP) | WS_OVERLAPPEDWINDOW
+    } else {
+        (style & !WS_OVERLAPPEDWINDOW) | WS_POPUP | WS_THICKFRAME | WS_MINIMIZEBOX | WS_MAXIMIZEBOX
+    }
+}
+
 fn win32_rect_to_rect(rect: RECT) -> Rect {
     Rect { left: rect.left, top: rect.top, right: rect.right, bottom: rect.bottom }
 }
@@ -170,12 +405,21 @@ pub struct WindowBuilder {
     pub def_window_name: PCWSTR,
     pub parent: HWND,
     pub menu: HMENU,
+    /// Styles of `WindowKind::Normal` windows, the other kinds have their own.
     pub ex_style: u32,
     pub style: u32,
     pub x: c_int,
     pub y: c_int,
     pub width: c_int,
     pub height: c_int,
+    pub transparent: bool,
+    pub kind: WindowKind,
+    pub anchor: Option<Rect>,
+    pub user_attention: Option<UserAttention>,
+    /// Applied over the styles of the kind in `build`, `None` keeps them.
+    pub decorations: Option<bool>,
+    pub always_on_top: Option<bool>,
+    pub skip_taskbar: Option<bool>,
 
     pub utf8_name_buffer: [u16; 256],
 }
@@ -193,6 +437,13 @@ impl Default for WindowBuilder {
             height: CW_USEDEFAULT,
             parent: 0,
             menu: 0,
+            transparent: false,
+            kind: WindowKind::Normal,
+            anchor: None,
+            user_attention: None,
+            decorations: None,
+            always_on_top: None,
+            skip_taskbar: None,
             utf8_name_buffer: [0u16; TITLE_BUFFER_LEN],
         }
     }
@@ -217,17 +468,130 @@ impl WindowBuilder {
         self
     }
 
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Top level windows with a parent are owned by it.
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        self.parent = owner.0;
+        self
+    }
+
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.anchor = Some(anchor);
+        self
+    }
+
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.decorations = Some(decorations);
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.always_on_top = Some(always_on_top);
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, skip_taskbar: bool) -> &mut Self {
+        self.skip_taskbar = Some(skip_taskbar);
+        self
+    }
+
+    pub fn with_user_attention(&mut self, attention: Option<UserAttention>) -> &mut Self {
+        self.user_attention = attention;
+        self
+    }
+
+    /// Virtual desktops can't be controlled.
+    pub fn with_visible_on_all_workspaces(&mut self, _visible: bool) -> &mut Self {
+        self
+    }
+
+    /// Virtual desktops can't be controlled.
+    pub fn with_workspace(&mut self, _workspace: u32) -> &mut Self {
+        self
+    }
+
+    /// Ignored, every message reaches the window procedure.
+    pub fn with_events(&mut self, _events: EventMask) -> &mut Self {
+        self
+    }
+
+    /// Styles and extended styles of the kind with the flags applied, the order of the `with_` calls doesn't matter.
+    fn styles(&self) -> (u32, u32) {
+        let (mut style, mut ex_style) = match self.kind {
+            WindowKind::Normal => (self.style, self.ex_style),
+            WindowKind::Dialog => (WS_CAPTION | WS_SYSMENU, WS_EX_DLGMODALFRAME),
+            WindowKind::Utility => (WS_OVERLAPPEDWINDOW, WS_EX_TOOLWINDOW),
+            WindowKind::Popup | WindowKind::Menu => (WS_POPUP | WS_BORDER, WS_EX_TOOLWINDOW | WS_EX_TOPMOST),
+            WindowKind::Tooltip => (WS_POPUP | WS_BORDER, WS_EX_TOOLWINDOW | WS_EX_TOPMOST | WS_EX_NOACTIVATE),
+        };
+
+        if let Some(decorations) = self.decorations {
+            style = decorated_style(style, decorations);
+        }
+
+        match self.always_on_top {
+            Some(true) => ex_style |= WS_EX_TOPMOST,
+            Some(false) => ex_style &= !WS_EX_TOPMOST,
+            None => {}
+        }
+
+        match self.skip_taskbar {
+            Some(true) => ex_style = (ex_style & !WS_EX_APPWINDOW) | WS_EX_TOOLWINDOW,
+            Some(false) => ex_style &= !WS_EX_TOOLWINDOW,
+            None => {}
+        }
+
+        (style, ex_style)
+    }
+
+    /// Translates the anchor from the owner to the screen and keeps the window in the work area of its monitor.
+    unsafe fn popup_position(&self, anchor: Rect) -> (c_int, c_int) {
+        let mut top_left = POINT { x: anchor.left, y: anchor.top };
+        ClientToScreen(self.parent, &mut top_left);
+
+        let anchor = Rect {
+            left: top_left.x,
+            top: top_left.y,
+            right: top_left.x + anchor.right - anchor.left,
+            bottom: top_left.y + anchor.bottom - anchor.top,
+        };
+
+        let mut info: MONITORINFO = zeroed();
+        info.cbSize = size_of::<MONITORINFO>() as _;
+        GetMonitorInfoW(MonitorFromWindow(self.parent, MONITOR_DEFAULTTONEAREST), &mut info);
+
+        let size = |s: c_int| if s == CW_USEDEFAULT { 0 } else { s };
+
+        popup_position(anchor, size(self.width), size(self.height), win32_rect_to_rect(info.rcWork))
+    }
+
     pub fn build<E: Event>(&self, _context: Context) -> Result<WindowHandle, Error> {
         unsafe {
             let window_name = if self.utf8_name_buffer[0] == 0 { self.def_window_name } else { self.utf8_name_buffer.as_ptr() };
 
+            let (x, y) = match self.anchor {
+                Some(anchor) if self.kind.is_popup() => self.popup_position(anchor),
+                _ => (self.x, self.y),
+            };
+
+            let (style, ex_style) = self.styles();
+
             let hwnd = CreateWindowExW(
-                self.ex_style,
+                ex_style,
                 ThreadContext::get().window_class,
                 window_name,
-                self.style,
-                self.x,
-                self.y,
+                style,
+                x,
+                y,
                 self.width,
                 self.height,
                 self.parent,
@@ -240,7 +604,27 @@ impl WindowBuilder {
                 return Err(Error::last_os_error());
             }
 
-            Ok(WindowHandle(hwnd))
+            if self.transparent {
+                // An empty blur region makes DWM compose the client area with its alpha channel without blurring.
+                let region = CreateRectRgn(0, 0, -1, -1);
+                let blur_behind = DWM_BLURBEHIND {
+                    dwFlags: DWM_BB_ENABLE | DWM_BB_BLURREGION,
+                    fEnable: 1,
+                    hRgnBlur: region,
+                    fTransitionOnMaximized: 0,
+                };
+
+                DwmEnableBlurBehindWindow(hwnd, &blur_behind);
+                DeleteObject(region);
+            }
+
+            let handle = WindowHandle(hwnd);
+
+            if self.user_attention.is_some() {
+                handle.request_user_attention(self.user_attention)?;
+            }
+
+            Ok(handle)
         }
     }
 }
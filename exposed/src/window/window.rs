@@ -1,12 +1,25 @@
-use std::io::Error;
+use std::{fmt::Debug, io::Error, marker::PhantomData};
 
-use crate::destroy::Destroy;
+use crate::destroy::{track, untrack, Destroy};
 
-use super::{platform, Context, Event, Rect, Size};
+use super::{platform, Context, Event, EventMask, Image, Rect, ResizeEdge, Size, UserAttention, WindowKind};
 
+/// Belongs to the thread of its event handler, so it's neither `Send` nor `Sync`. Other threads use `WindowProxy`.
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct WindowHandle(pub platform::WindowHandle);
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WindowHandle(pub platform::WindowHandle, PhantomData<*mut ()>);
+
+impl Debug for WindowHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WindowHandle").field(&self.0).finish()
+    }
+}
+
+impl From<platform::WindowHandle> for WindowHandle {
+    fn from(value: platform::WindowHandle) -> Self {
+        Self(value, PhantomData)
+    }
+}
 
 impl WindowHandle {
     pub fn show(self) -> Result<(), Error> {
@@ -52,11 +65,78 @@ impl WindowHandle {
     pub fn window_rect(self) -> Result<Rect, Error> {
         self.0.window_rect()
     }
+
+    /// Opacity of the whole window including decorations, from `0.0` to `1.0`.
+    pub fn set_opacity(self, opacity: f32) -> Result<(), Error> {
+        self.0.set_opacity(opacity)
+    }
+
+    /// Reads the client area, the window must be visible.
+    /// Without a compositor, parts covered by other windows contain the other windows.
+    pub fn capture(self) -> Result<Image, Error> {
+        self.0.capture()
+    }
+
+    pub fn set_decorations(self, decorations: bool) -> Result<(), Error> {
+        self.0.set_decorations(decorations)
+    }
+
+    /// Lets the window manager move the window with the held mouse button, call it from `Event::mouse_button_down`.
+    pub fn drag_move(self) -> Result<(), Error> {
+        self.0.drag_move()
+    }
+
+    /// Lets the window manager resize the window from `edge` with the held mouse button, call it from `Event::mouse_button_down`.
+    pub fn drag_resize(self, edge: ResizeEdge) -> Result<(), Error> {
+        self.0.drag_resize(edge)
+    }
+
+    pub fn set_always_on_top(self, always_on_top: bool) -> Result<(), Error> {
+        self.0.set_always_on_top(always_on_top)
+    }
+
+    /// Hides the window from the taskbar.
+    pub fn set_skip_taskbar(self, skip_taskbar: bool) -> Result<(), Error> {
+        self.0.set_skip_taskbar(skip_taskbar)
+    }
+
+    /// `None` stops requesting attention, window managers usually also stop once the window is focused.
+    pub fn request_user_attention(self, attention: Option<UserAttention>) -> Result<(), Error> {
+        self.0.request_user_attention(attention)
+    }
+
+    /// Not supported on Windows, virtual desktops have no public API for it.
+    pub fn set_visible_on_all_workspaces(self, visible: bool) -> Result<(), Error> {
+        self.0.set_visible_on_all_workspaces(visible)
+    }
+
+    /// Workspaces are counted from 0. Not supported on Windows.
+    pub fn move_to_workspace(self, workspace: u32) -> Result<(), Error> {
+        self.0.move_to_workspace(workspace)
+    }
+
+    /// State the headless platform tracks for the window, `None` once it's destroyed.
+    #[cfg(feature = "headless")]
+    pub fn virtual_window(self) -> Option<super::VirtualWindow> {
+        self.0.virtual_window()
+    }
 }
 
 impl Destroy for WindowHandle {
+    /// A window that failed to be destroyed stays tracked, unless it was destroyed before which is reported.
     fn destroy(&mut self) -> Result<(), std::io::Error> {
-        self.0.destroy()
+        let result = self.0.destroy();
+
+        let destroyed_before = result
+            .as_ref()
+            .err()
+            .and_then(|e| e.get_ref()?.downcast_ref::<crate::Error>())
+            .is_some_and(|e| matches!(e, crate::Error::WindowDestroyed));
+        if result.is_ok() || destroyed_before {
+            untrack("Window", self);
+        }
+
+        result
     }
 }
 
@@ -75,8 +155,80 @@ impl WindowBuilder {
         self
     }
 
+    /// Creates the window with an alpha channel so the content can be translucent.
+    pub fn with_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.0.with_transparent(transparent);
+        self
+    }
+
+    pub fn with_kind(&mut self, kind: WindowKind) -> &mut Self {
+        self.0.with_kind(kind);
+        self
+    }
+
+    /// Keeps the window above `owner`, dialogs are usually centered on it by the window manager.
+    pub fn with_owner(&mut self, owner: WindowHandle) -> &mut Self {
+        self.0.with_owner(owner.0);
+        self
+    }
+
+    /// Rectangle in the client area of the owner that a popup, tooltip or menu opens next to.
+    /// The popup is placed below the anchor and moved to stay on screen, set its size with `with_size`.
+    pub fn with_anchor(&mut self, anchor: Rect) -> &mut Self {
+        self.0.with_anchor(anchor);
+        self
+    }
+
+    /// Without decorations the window has no title bar or borders, see `WindowHandle::drag_move` for custom ones.
+    pub fn with_decorations(&mut self, decorations: bool) -> &mut Self {
+        self.0.with_decorations(decorations);
+        self
+    }
+
+    pub fn with_always_on_top(&mut self, always_on_top: bool) -> &mut Self {
+        self.0.with_always_on_top(always_on_top);
+        self
+    }
+
+    pub fn with_skip_taskbar(&mut self, skip_taskbar: bool) -> &mut Self {
+        self.0.with_skip_taskbar(skip_taskbar);
+        self
+    }
+
+    pub fn with_user_attention(&mut self, attention: Option<UserAttention>) -> &mut Self {
+        self.0.with_user_attention(attention);
+        self
+    }
+
+    pub fn with_visible_on_all_workspaces(&mut self, visible: bool) -> &mut Self {
+        self.0.with_visible_on_all_workspaces(visible);
+        self
+    }
+
+    pub fn with_workspace(&mut self, workspace: u32) -> &mut Self {
+        self.0.with_workspace(workspace);
+        self
+    }
+
+    /// Callbacks the window subscribes to, `EventMask::DEFAULT` without it.
+    pub fn with_events(&mut self, events: EventMask) -> &mut Self {
+        self.0.with_events(events);
+        self
+    }
+
     #[inline]
     pub fn build<E: Event>(&self, context: Context) -> Result<WindowHandle, Error> {
-        Ok(self.0.build::<E>(context)?.into())
+        let window: WindowHandle = self.0.build::<E>(context)?.into();
+        track("Window", &window);
+        Ok(window)
+    }
+
+    /// Creates the window with `visual` instead of the default one, for GLX or Vulkan surfaces.
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn build_with_visual<E: Event>(&self, context: Context, visual: platform::WindowVisual) -> Result<WindowHandle, Error> {
+        let window: WindowHandle = self.0.build_with_visual::<E>(context, visual)?.into();
+        track("Window", &window);
+        Ok(window)
     }
 }
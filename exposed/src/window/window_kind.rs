@@ -0,0 +1,61 @@
+use super::Rect;
+
+/// Hints the window manager how to treat a window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WindowKind {
+    #[default]
+    Normal,
+    Dialog,
+    /// Tool palettes and other small windows that belong to an owner.
+    Utility,
+    /// Dropdowns and other transient surfaces that bypass the window manager.
+    Popup,
+    /// Bypasses the window manager and never takes focus.
+    Tooltip,
+    /// Context menus, bypasses the window manager.
+    Menu,
+}
+
+impl WindowKind {
+    /// Popups, tooltips and menus are placed by the application instead of the window manager.
+    pub const fn is_popup(self) -> bool {
+        matches!(self, Self::Popup | Self::Tooltip | Self::Menu)
+    }
+}
+
+/// Places a `width` x `height` popup below `anchor`, flipping above or to the left when it doesn't fit in `bounds`.
+///
+/// All rectangles are in the same coordinate space, usually the screen.
+pub fn popup_position(anchor: Rect, width: i32, height: i32, bounds: Rect) -> (i32, i32) {
+    let mut x = anchor.left;
+    let mut y = anchor.bottom;
+
+    if y + height > bounds.bottom && anchor.top - height >= bounds.top {
+        y = anchor.top - height;
+    }
+
+    if x + width > bounds.right {
+        x = anchor.right - width;
+    }
+
+    x = x.min(bounds.right - width).max(bounds.left);
+    y = y.min(bounds.bottom - height).max(bounds.top);
+
+    (x, y)
+}
+
+#[test]
+fn popup_position_test() {
+    let screen = Rect { left: 0, top: 0, right: 1920, bottom: 1080 };
+
+    let anchor = Rect { left: 100, top: 100, right: 200, bottom: 120 };
+    assert_eq!(popup_position(anchor, 150, 300, screen), (100, 120));
+
+    // Near the bottom right corner, flips above and aligns to the right edge of the anchor.
+    let anchor = Rect { left: 1850, top: 1000, right: 1900, bottom: 1020 };
+    assert_eq!(popup_position(anchor, 150, 300, screen), (1750, 700));
+
+    // Taller than the space on both sides, stays below and gets clamped.
+    let anchor = Rect { left: 10, top: 500, right: 20, bottom: 520 };
+    assert_eq!(popup_position(anchor, 100, 800, screen), (10, 280));
+}
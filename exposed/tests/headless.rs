@@ -0,0 +1,344 @@
+#![cfg(feature = "headless")]
+
+use std::time::{Duration, Instant};
+
+use exposed::{
+    destroy::Destroy,
+    window::{
+        utility::{self, FrameLoop, Pacing, RunConfig},
+        Context, Event, EventHandler, EventHandlerBuilder, EventQueue, InjectedEvent, Key, MouseButton, PixelSurface, Player,
+        Recorder, Touch, TouchPhase, WindowBuilder, WindowEvent, WindowHandle,
+    },
+};
+
+#[derive(Default)]
+struct Log {
+    renders: usize,
+    size: Option<(i32, i32)>,
+    keys: Vec<Key>,
+    characters: String,
+    buttons: Vec<MouseButton>,
+    cursor: Option<(i32, i32)>,
+    touches: usize,
+    files: Vec<String>,
+    close_requested: bool,
+}
+
+struct App {
+    window: WindowHandle,
+    surface: PixelSurface,
+    log: Log,
+}
+
+impl Event for App {
+    fn create(context: Context) -> Option<Self> {
+        let window = WindowBuilder::default().with_title("exposed").with_size(320, 200).build::<Self>(context).ok()?;
+        let surface = PixelSurface::new(window).ok()?;
+
+        Some(Self { window, surface, log: Log::default() })
+    }
+
+    fn render(&mut self, _window: WindowHandle) {
+        self.surface.buffer_mut().unwrap().fill(0x00FF8000);
+        self.surface.present().unwrap();
+        self.log.renders += 1;
+    }
+
+    fn resized(&mut self, _window: WindowHandle, width: i32, height: i32) {
+        self.log.size = Some((width, height));
+    }
+
+    fn key_down(&mut self, _window: WindowHandle, key: Key, _scancode: u32) {
+        self.log.keys.push(key);
+    }
+
+    fn received_character(&mut self, _window: WindowHandle, character: char) {
+        self.log.characters.push(character);
+    }
+
+    fn mouse_button_down(&mut self, _window: WindowHandle, button: MouseButton) {
+        self.log.buttons.push(button);
+    }
+
+    fn cursor_moved(&mut self, _window: WindowHandle, x: i32, y: i32) {
+        self.log.cursor = Some((x, y));
+    }
+
+    fn touch(&mut self, _window: WindowHandle, _touch: Touch, _pointer_count: usize) {
+        self.log.touches += 1;
+    }
+
+    fn file_received(&mut self, _window: WindowHandle, path: String) {
+        self.log.files.push(path);
+    }
+
+    fn close_requested(&mut self, _window: WindowHandle) {
+        self.log.close_requested = true;
+    }
+}
+
+fn dispatch_all<E: Event>(event_handler: &mut EventHandler<E>) {
+    while event_handler.poll() > 0 {
+        event_handler.dispatch();
+    }
+}
+
+#[test]
+fn headless_window_test() {
+    let mut event_handler = EventHandlerBuilder::default().build::<App>().unwrap();
+    let window = event_handler.user_data().window;
+
+    let state = window.virtual_window().unwrap();
+    assert_eq!((state.title.as_str(), state.width, state.height, state.visible), ("exposed", 320, 200, false));
+
+    window.show().unwrap();
+    dispatch_all(&mut event_handler);
+    assert!(window.virtual_window().unwrap().visible);
+    assert_eq!(event_handler.user_data().log.renders, 1);
+    assert_eq!(window.capture().unwrap().pixel(0, 0), [0xFF, 0x80, 0x00, 0xFF]);
+
+    window.set_window_title("renamed").unwrap();
+    assert_eq!(window.window_title().unwrap(), "renamed");
+
+    #[cfg(feature = "rwh_06")]
+    {
+        use exposed::rwh_06::{HandleError, HasWindowHandle};
+        assert!(matches!(window.window_handle(), Err(HandleError::NotSupported)));
+    }
+
+    event_handler.inject(InjectedEvent::Resized(window, 640, 480));
+    event_handler.inject(InjectedEvent::Moved(window, 10, 20));
+    event_handler.inject(InjectedEvent::Focused(window, true));
+    dispatch_all(&mut event_handler);
+
+    let state = window.virtual_window().unwrap();
+    assert_eq!((state.x, state.y, state.width, state.height, state.focused), (10, 20, 640, 480, true));
+    assert_eq!(event_handler.user_data().log.size, Some((640, 480)));
+    assert_eq!(event_handler.user_data().log.renders, 2);
+    assert_eq!(window.capture().unwrap().width, 640);
+
+    let touch = Touch { phase: TouchPhase::Started, location: (1.0, 2.0), ..Default::default() };
+
+    event_handler.inject(InjectedEvent::KeyDown(window, Key::ESCAPE, 9));
+    event_handler.inject(InjectedEvent::Character(window, 'a'));
+    event_handler.inject(InjectedEvent::CursorMoved(window, 5, 6));
+    event_handler.inject(InjectedEvent::MouseButtonDown(window, MouseButton::LEFT));
+    event_handler.inject(InjectedEvent::Touch(window, touch, 1));
+    event_handler.inject(InjectedEvent::FileReceived(window, "/tmp/file.txt".to_string()));
+    event_handler.inject(InjectedEvent::CloseRequested(window));
+    dispatch_all(&mut event_handler);
+
+    let log = &event_handler.user_data().log;
+    assert!(log.keys == [Key::ESCAPE]);
+    assert_eq!(log.characters, "a");
+    assert_eq!(log.cursor, Some((5, 6)));
+    assert_eq!(log.buttons, [MouseButton::LEFT]);
+    assert_eq!(log.touches, 1);
+    assert_eq!(log.files, ["/tmp/file.txt"]);
+    assert!(log.close_requested);
+
+    let app = event_handler.user_data_mut();
+    app.surface.destroy().unwrap();
+    app.window.destroy().unwrap();
+    assert!(window.virtual_window().is_none());
+}
+
+#[test]
+fn headless_record_replay_test() {
+    let path = std::env::temp_dir().join(format!("exposed-{}.rec", std::process::id()));
+
+    let mut event_handler = EventHandlerBuilder::default().build::<Recorder<App>>().unwrap();
+    let window = event_handler.user_data().inner.window;
+    event_handler.user_data_mut().record_to(std::io::BufWriter::new(std::fs::File::create(&path).unwrap())).unwrap();
+
+    event_handler.inject(InjectedEvent::Resized(window, 640, 480));
+    event_handler.inject(InjectedEvent::KeyDown(window, Key::ESCAPE, 9));
+    event_handler.inject(InjectedEvent::Character(window, 'a'));
+    event_handler.inject(InjectedEvent::CloseRequested(window));
+    while event_handler.poll() > 0 {
+        event_handler.dispatch();
+    }
+    event_handler.user_data_mut().stop().unwrap();
+
+    let recorded = &event_handler.user_data().inner.log;
+    let mut player = Player::open(&path, &[window]).unwrap();
+    let mut app = App { window, surface: PixelSurface::new(window).unwrap(), log: Log::default() };
+    assert!(player.with_speed(0.0).is_err() && player.with_speed(-1.0).is_err() && player.with_speed(f32::NAN).is_err());
+    player.with_speed(f32::INFINITY).unwrap().play(&mut app).unwrap();
+
+    assert_eq!(app.log.size, recorded.size);
+    assert!(app.log.keys == recorded.keys);
+    assert_eq!(app.log.characters, recorded.characters);
+    assert_eq!(app.log.renders, recorded.renders);
+    assert!(app.log.close_requested);
+
+    std::fs::remove_file(path).unwrap();
+    app.surface.destroy().unwrap();
+    event_handler.user_data_mut().inner.surface.destroy().unwrap();
+    event_handler.user_data_mut().inner.window.destroy().unwrap();
+}
+
+#[test]
+fn headless_event_queue_test() {
+    let mut event_handler = EventHandlerBuilder::default().build::<EventQueue>().unwrap();
+    let context = event_handler.user_data().context;
+    let mut window = WindowBuilder::default().with_size(320, 200).build::<EventQueue>(context).unwrap();
+
+    assert_eq!(event_handler.next_event(), None);
+
+    event_handler.inject(InjectedEvent::Resized(window, 640, 480));
+    event_handler.inject(InjectedEvent::KeyDown(window, Key::ESCAPE, 9));
+    event_handler.inject(InjectedEvent::CloseRequested(window));
+
+    assert_eq!(event_handler.next_event(), Some(WindowEvent::Resized(window, 640, 480)));
+    assert_eq!(event_handler.next_event(), Some(WindowEvent::KeyDown(window, Key::ESCAPE, 9)));
+    assert_eq!(event_handler.next_event(), Some(WindowEvent::CloseRequested(window)));
+    // Resizing queues a render behind the events that were already waiting.
+    assert_eq!(event_handler.wait_event(), WindowEvent::Render(window));
+    assert_eq!(event_handler.next_event(), None);
+
+    window.destroy().unwrap();
+}
+
+#[test]
+fn headless_timer_test() {
+    let mut event_handler = EventHandlerBuilder::default().build::<EventQueue>().unwrap();
+    let context = event_handler.user_data().context;
+
+    let once = context.set_timer(Duration::from_millis(20), false);
+    let repeat = context.set_timer(Duration::from_millis(5), true);
+    let cancelled = context.set_timer(Duration::from_millis(1), false);
+    context.set_timer(Duration::MAX, true);
+    assert!(context.cancel_timer(cancelled));
+    assert_eq!(event_handler.next_event(), None);
+
+    let start = Instant::now();
+    assert_eq!(event_handler.wait_event(), WindowEvent::TimerFired(repeat));
+    assert!(start.elapsed() >= Duration::from_millis(5));
+
+    let mut event = event_handler.wait_event();
+    while event == WindowEvent::TimerFired(repeat) {
+        event = event_handler.wait_event();
+    }
+    assert_eq!(event, WindowEvent::TimerFired(once));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    assert!(!context.cancel_timer(once));
+    assert!(context.cancel_timer(repeat));
+    while let Some(event) = event_handler.next_event() {
+        assert_eq!(event, WindowEvent::TimerFired(repeat));
+    }
+
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(event_handler.next_event(), None);
+}
+
+struct Refused;
+
+impl Event for Refused {
+    fn create(_context: Context) -> Option<Self> {
+        None
+    }
+}
+
+struct Panics {
+    window: WindowHandle,
+}
+
+impl Event for Panics {
+    fn create(context: Context) -> Option<Self> {
+        Some(Self { window: WindowBuilder::default().build::<Self>(context).ok()? })
+    }
+
+    fn render(&mut self, _window: WindowHandle) {
+        panic!("render");
+    }
+}
+
+#[test]
+fn headless_ownership_test() {
+    assert!(matches!(EventHandlerBuilder::default().build::<Refused>(), Err(exposed::Error::CreateFailed)));
+
+    let mut event_handler = EventHandlerBuilder::default().build::<Panics>().unwrap();
+    event_handler.user_data().window.show().unwrap();
+
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| dispatch_all(&mut event_handler)));
+    assert_eq!(result.unwrap_err().downcast_ref::<&str>(), Some(&"render"));
+
+    // The event handler is still usable and releases the windows of the thread when it's dropped.
+    assert_eq!(event_handler.poll(), 0);
+    assert!(matches!(EventHandlerBuilder::default().build::<Panics>(), Err(exposed::Error::EventHandlerExists)));
+    let window = event_handler.user_data().window;
+    drop(event_handler);
+    assert!(window.virtual_window().is_none());
+    assert!(matches!(EventHandlerBuilder::default().build::<Refused>(), Err(exposed::Error::CreateFailed)));
+}
+
+#[test]
+fn headless_proxy_test() {
+    let mut event_handler = EventHandlerBuilder::default().build::<Animation>().unwrap();
+    let window = event_handler.user_data().window;
+    let proxy = window.proxy().unwrap();
+
+    let worker = proxy.clone();
+    std::thread::spawn(move || worker.set_window_title("worker")).join().unwrap().unwrap();
+
+    // Requests wait for the thread of the window.
+    assert_eq!(window.virtual_window().unwrap().title, "");
+    assert_eq!(event_handler.poll(), 1);
+    event_handler.dispatch();
+    assert_eq!(window.virtual_window().unwrap().title, "worker");
+
+    drop(event_handler);
+    assert!(matches!(proxy.redraw(), Err(exposed::Error::WindowDestroyed)));
+    assert!(matches!(window.proxy(), Err(exposed::Error::WindowDestroyed)));
+}
+
+struct Animation {
+    window: WindowHandle,
+    updates: u32,
+    draws: u32,
+}
+
+impl Event for Animation {
+    fn create(context: Context) -> Option<Self> {
+        let window = WindowBuilder::default().build::<Self>(context).ok()?;
+        Some(Self { window, updates: 0, draws: 0 })
+    }
+}
+
+impl utility::ExtendedEvent for Animation {
+    fn is_running(&mut self) -> bool {
+        self.draws < 20
+    }
+}
+
+impl FrameLoop for Animation {
+    fn update(&mut self, dt: Duration) {
+        assert_eq!(dt, Duration::from_millis(2));
+        self.updates += 1;
+    }
+
+    fn draw(&mut self, alpha: f32) {
+        assert!((0.0..1.0).contains(&alpha));
+        self.draws += 1;
+
+        // 19 frame intervals of 5 ms are simulated in steps of 2 ms.
+        if self.draws == 20 {
+            assert!(self.updates >= 45, "{} updates", self.updates);
+            self.window.destroy().unwrap();
+        }
+    }
+}
+
+#[test]
+fn headless_run_with_test() {
+    let start = Instant::now();
+    let mut config = RunConfig::default();
+    config.with_pacing(Pacing::Fps(200.0)).with_timestep(Duration::from_millis(2));
+
+    utility::run_with::<Animation>(EventHandlerBuilder::default(), config).unwrap();
+
+    assert!(start.elapsed() >= Duration::from_millis(95));
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
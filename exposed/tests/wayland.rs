@@ -0,0 +1,72 @@
+#![cfg(all(target_os = "linux", feature = "wayland", not(feature = "headless")))]
+
+use std::time::{Duration, Instant};
+
+use exposed::{
+    destroy::Destroy,
+    window::{Backend, Context, Event, EventHandlerBuilder, PixelSurface, WindowBuilder, WindowHandle},
+};
+
+struct App {
+    window: WindowHandle,
+    surface: PixelSurface,
+    size: Option<(i32, i32)>,
+    rendered: bool,
+}
+
+impl Event for App {
+    fn create(context: Context) -> Option<Self> {
+        let window = WindowBuilder::default().with_title("exposed").with_size(320, 200).build::<Self>(context).ok()?;
+        let surface = PixelSurface::new(window).ok()?;
+
+        window.show().ok()?;
+
+        Some(Self { window, surface, size: None, rendered: false })
+    }
+
+    fn render(&mut self, _window: WindowHandle) {
+        self.surface.buffer_mut().unwrap().fill(0x00FF8000);
+        self.surface.present().unwrap();
+        self.rendered = true;
+    }
+
+    fn resized(&mut self, _window: WindowHandle, width: i32, height: i32) {
+        self.size = Some((width, height));
+    }
+}
+
+/// Run `weston --backend=headless --socket=wayland-test` and the test with `WAYLAND_DISPLAY=wayland-test` and `--ignored`.
+#[test]
+#[ignore = "needs a compositor"]
+fn wayland_window_test() {
+    let mut event_handler = EventHandlerBuilder::default().with_backend(Backend::Wayland).build::<App>().unwrap();
+
+    let start = Instant::now();
+    while !event_handler.user_data().rendered {
+        assert!(start.elapsed() < Duration::from_secs(5), "The compositor never configured the window.");
+
+        event_handler.wait();
+        event_handler.dispatch();
+    }
+
+    let app = event_handler.user_data_mut();
+    let size = app.window.client_size().unwrap();
+
+    assert_eq!(app.size, Some((size.width, size.height)));
+    assert!(size.width >= 320 && size.height >= 200);
+    assert_eq!(app.window.window_title().unwrap(), "exposed");
+
+    #[cfg(feature = "rwh_06")]
+    {
+        use exposed::rwh_06::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
+
+        assert!(matches!(app.window.window_handle().unwrap().as_raw(), RawWindowHandle::Wayland(_)));
+        assert!(matches!(app.window.display_handle().unwrap().as_raw(), RawDisplayHandle::Wayland(_)));
+    }
+
+    app.window.set_window_title("renamed").unwrap();
+    assert_eq!(app.window.window_title().unwrap(), "renamed");
+
+    app.surface.destroy().unwrap();
+    app.window.destroy().unwrap();
+}